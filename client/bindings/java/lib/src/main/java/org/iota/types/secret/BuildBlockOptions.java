@@ -7,7 +7,12 @@ import com.google.gson.JsonObject;
 import org.iota.types.JsonUtils;
 import org.iota.types.Output;
 import org.iota.types.UtxoInput;
+import org.iota.types.ids.AliasId;
 import org.iota.types.ids.BlockId;
+import org.iota.types.ids.FoundryId;
+import org.iota.types.ids.NftId;
+
+import java.util.Map;
 
 public class BuildBlockOptions {
 
@@ -23,7 +28,7 @@ public class BuildBlockOptions {
     private String tag;
     private String data;
     private BlockId[] parents;
-    private Boolean allowBurning;
+    private Burn burn;
 
     public BuildBlockOptions withCoinType(Integer coinType) {
         this.coinType = coinType;
@@ -85,8 +90,8 @@ public class BuildBlockOptions {
         return this;
     }
 
-    public BuildBlockOptions withAllowBurning(Boolean allowBurning) {
-        this.allowBurning = allowBurning;
+    public BuildBlockOptions withBurn(Burn burn) {
+        this.burn = burn;
         return this;
     }
 
@@ -108,6 +113,57 @@ public class BuildBlockOptions {
         }
     }
 
+    /**
+     * Aliases, foundries, NFTs and native tokens to burn, nothing else is burned.
+     */
+    public static class Burn {
+        private AliasId[] aliases;
+        private NftId[] nfts;
+        private FoundryId[] foundries;
+        /** Hex encoded amounts of native tokens, by hex encoded token ID */
+        private Map<String, String> nativeTokens;
+
+        public Burn withAliases(AliasId[] aliases) {
+            this.aliases = aliases;
+            return this;
+        }
+
+        public Burn withNfts(NftId[] nfts) {
+            this.nfts = nfts;
+            return this;
+        }
+
+        public Burn withFoundries(FoundryId[] foundries) {
+            this.foundries = foundries;
+            return this;
+        }
+
+        public Burn withNativeTokens(Map<String, String> nativeTokens) {
+            this.nativeTokens = nativeTokens;
+            return this;
+        }
+
+        public JsonObject getAsJson() {
+            // Unset lists are left out, because they can't be null
+            JsonObject o = new JsonObject();
+            if (aliases != null)
+                o.add("aliases", JsonUtils.toJson(aliases));
+            if (nfts != null)
+                o.add("nfts", JsonUtils.toJson(nfts));
+            if (foundries != null)
+                o.add("foundries", JsonUtils.toJson(foundries));
+            if (nativeTokens != null) {
+                JsonObject n = new JsonObject();
+                for (Map.Entry<String, String> nativeToken : nativeTokens.entrySet()) {
+                    n.addProperty(nativeToken.getKey(), nativeToken.getValue());
+                }
+                o.add("nativeTokens", n);
+            }
+
+            return o;
+        }
+    }
+
     public JsonObject getJson() {
         JsonObject o = new JsonObject();
         o.addProperty("coinType", coinType);
@@ -122,7 +178,7 @@ public class BuildBlockOptions {
         o.addProperty("tag", tag);
         o.addProperty("data", data);
         o.add("parents", JsonUtils.toJson(parents));
-        o.addProperty("allowBurning", allowBurning);
+        o.add("burn", burn != null ? burn.getAsJson() : null);
 
         return o;
     }
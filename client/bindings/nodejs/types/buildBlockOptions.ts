@@ -21,8 +21,51 @@ export interface IBuildBlockOptions {
     data?: string;
     /** Parent block IDs */
     parents?: string[];
-    /** Allow burning of native tokens */
-    allowBurning?: boolean;
+    /** Aliases, foundries, NFTs and native tokens to burn */
+    burn?: IBurn;
+    /** Order in which additional basic outputs are selected as inputs */
+    coinSelection?: CoinSelection;
+    /** How the leftovers are split into remainder outputs */
+    remainderPolicy?: IRemainderPolicy;
+}
+
+/** Aliases, foundries, NFTs and native tokens to burn, nothing else is burned */
+export interface IBurn {
+    /** Hex encoded alias IDs */
+    aliases?: string[];
+    /** Hex encoded NFT IDs */
+    nfts?: string[];
+    /** Hex encoded foundry IDs */
+    foundries?: string[];
+    /** Hex encoded amounts of native tokens, by token ID */
+    nativeTokens?: { [tokenId: string]: string };
+}
+
+/** The built-in coin selection strategies */
+export type CoinSelection =
+    | 'largestFirst'
+    | 'smallestFirst'
+    | 'oldestFirst'
+    | 'branchAndBound';
+
+/** How the leftover base coin and native tokens are split into remainder outputs */
+export interface IRemainderPolicy {
+    /** How the leftovers are split into outputs, a single output by default */
+    split?: RemainderSplit;
+    /** Addresses that the remainder outputs are sent to in turn, the remainder address is used if there are none */
+    addresses?: IRemainderPolicyAddress[];
+}
+
+/** A single output, an output per native token or outputs with the given base coin amounts */
+export type RemainderSplit =
+    | { type: 'single' }
+    | { type: 'perNativeToken' }
+    | { type: 'denominations'; data: number[] };
+
+/** Address with hex encoded data */
+export interface IRemainderPolicyAddress {
+    type: 'Ed25519' | 'Alias' | 'Nft';
+    data: string;
 }
 
 /** Address with base coin amount */
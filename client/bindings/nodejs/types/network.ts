@@ -12,12 +12,27 @@ export enum Network {
 }
 
 /**
- * Basic Auth or JWT.
+ * Basic Auth or JWT, static headers and a client certificate.
  */
 export interface IAuth {
+    /** JWT, sent as bearer token */
     jwt?: string;
-    username?: string;
-    password?: string;
+    /** Username and password, sent as basic auth if no JWT is set */
+    basicAuthNamePwd?: [string, string];
+    /** Headers that are added to every request */
+    headers?: { [name: string]: string };
+    /** Client certificate for mutual TLS */
+    clientCertificate?: IClientCertificate;
+}
+
+/**
+ * PEM encoded client certificate and private key.
+ */
+export interface IClientCertificate {
+    /** The PEM encoded certificate chain, starting with the client certificate */
+    certificate: string;
+    /** The PEM encoded private key of the client certificate */
+    privateKey: string;
 }
 
 /**
@@ -24,6 +24,10 @@ export interface IPreparedTransactionData {
      * Optional remainder output information
      */
     remainder?: IRemainder;
+    /**
+     * Further remainder output information if the remainder policy split the remainder
+     */
+    additionalRemainders?: IRemainder[];
 }
 
 /**
@@ -30,7 +30,7 @@ async fn main() -> Result<()> {
     // Get the block metadata.
     let metadata = client.get_block_metadata(&block_id).await?;
 
-    if let Some(ms_index) = metadata.referenced_by_milestone_index {
+    if let Some(ms_index) = metadata.referenced_by_milestone_index() {
         let ms = client.get_milestone_by_index(ms_index).await?;
         println!(
             "Block {} got confirmed by milestone {} at timestamp {}.",
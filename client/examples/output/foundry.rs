@@ -4,6 +4,7 @@
 //! cargo run --example foundry --release
 
 use iota_client::{
+    api::input_selection::burn::Burn,
     block::{
         address::AliasAddress,
         output::{
@@ -257,7 +258,7 @@ async fn main() -> Result<()> {
     let block = client
         .block()
         .with_secret_manager(&secret_manager)
-        .with_burning_allowed(true)
+        .with_burn(Burn::new().add_native_token(token_id, U256::from(20u8)))
         .with_input(basic_output_id.into())?
         .with_outputs(outputs)?
         .finish()
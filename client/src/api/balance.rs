@@ -0,0 +1,239 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashSet};
+
+use iota_types::block::{
+    address::{Address, AliasAddress},
+    output::{AliasId, FoundryId, NativeTokens, NftId, Output, OutputId, TokenId},
+};
+use primitive_types::U256;
+
+use crate::{
+    api::input_selection::sdr_not_expired,
+    node_api::indexer::queries::{AliasOutputQuery, BasicOutputQuery, FoundryOutputQuery, NftOutputQuery},
+    Client, Error, Result,
+};
+
+/// The base coins of addresses, split by whether they can be spent at the time of the balance.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseCoinBalance {
+    /// All base coins, spendable or not.
+    pub total: u64,
+    /// The base coins that can be spent.
+    pub spendable: u64,
+    /// The base coins of outputs with a timelock that isn't expired yet.
+    pub timelocked: u64,
+    /// The base coins of outputs that return to the sender if they aren't claimed before their expiration.
+    pub expiring: u64,
+    /// The base coins that have to be returned to the sender to claim the outputs.
+    pub storage_deposit_return: u64,
+}
+
+/// The amount of a native token of addresses, split by whether it can be spent at the time of the balance.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeTokenBalance {
+    /// The whole amount, spendable or not.
+    pub total: U256,
+    /// The amount that can be spent.
+    pub spendable: U256,
+    /// The amount in outputs with a timelock that isn't expired yet.
+    pub timelocked: U256,
+    /// The amount in outputs that return to the sender if they aren't claimed before their expiration.
+    pub expiring: U256,
+}
+
+/// The balance of one or more addresses, with the funds of their basic, NFT, alias and foundry outputs.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBalance {
+    /// The timestamp of the latest milestone of the node, at which timelocks and expirations were checked.
+    pub milestone_timestamp: u32,
+    /// The base coins.
+    pub base_coin: BaseCoinBalance,
+    /// The native tokens.
+    pub native_tokens: BTreeMap<TokenId, NativeTokenBalance>,
+    /// The NFTs owned by the addresses.
+    pub nfts: Vec<NftId>,
+    /// The aliases controlled by the addresses as state controller.
+    pub aliases: Vec<AliasId>,
+    /// The foundries of the aliases.
+    pub foundries: Vec<FoundryId>,
+}
+
+// How the funds of an output can be used.
+#[derive(Clone, Copy)]
+enum Lock {
+    None,
+    Timelock,
+    Expiration,
+}
+
+impl AddressBalance {
+    fn add_funds(
+        &mut self,
+        amount: u64,
+        native_tokens: Option<&NativeTokens>,
+        lock: Lock,
+        storage_deposit_return: u64,
+    ) {
+        self.base_coin.total += amount;
+        match lock {
+            Lock::None => {
+                self.base_coin.spendable += amount - storage_deposit_return;
+                self.base_coin.storage_deposit_return += storage_deposit_return;
+            }
+            Lock::Timelock => self.base_coin.timelocked += amount,
+            Lock::Expiration => self.base_coin.expiring += amount,
+        }
+
+        for native_token in native_tokens.into_iter().flat_map(|native_tokens| native_tokens.iter()) {
+            let balance = self.native_tokens.entry(*native_token.token_id()).or_default();
+            balance.total += native_token.amount();
+            match lock {
+                Lock::None => balance.spendable += native_token.amount(),
+                Lock::Timelock => balance.timelocked += native_token.amount(),
+                Lock::Expiration => balance.expiring += native_token.amount(),
+            }
+        }
+    }
+}
+
+impl Client {
+    /// Returns the balance of a bech32 encoded address. See [`Client::get_addresses_balance()`].
+    pub async fn get_address_balance(&self, bech32_address: &str) -> Result<AddressBalance> {
+        self.get_addresses_balance(&[bech32_address.to_string()]).await
+    }
+
+    /// Returns the combined balance of bech32 encoded addresses: the base coins and native tokens of the basic and NFT
+    /// outputs they own, of the alias outputs they control as state controller and of the foundry outputs of these
+    /// aliases, with the IDs of the NFTs, aliases and foundries. Timelocks and expirations are checked at the time of
+    /// the latest milestone of the node.
+    pub async fn get_addresses_balance(&self, bech32_addresses: &[String]) -> Result<AddressBalance> {
+        let addresses = bech32_addresses
+            .iter()
+            .map(|bech32_address| Ok(Address::try_from_bech32(bech32_address)?.1))
+            .collect::<Result<HashSet<_>>>()?;
+        let milestone_timestamp = self
+            .get_info()
+            .await?
+            .node_info
+            .status
+            .latest_milestone
+            .timestamp
+            .ok_or(Error::UnexpectedApiResponse)?;
+
+        let mut output_ids = HashSet::new();
+        for address in &addresses {
+            // The outputs that are owned by the address and those that return to it because they expired
+            output_ids.extend(
+                self.output_ids_by_query(&BasicOutputQuery::new().with_address(*address))
+                    .await?,
+            );
+            output_ids.extend(
+                self.output_ids_by_query(
+                    &BasicOutputQuery::new()
+                        .with_expiration_return_address(*address)
+                        .with_expires_before(milestone_timestamp),
+                )
+                .await?,
+            );
+            output_ids.extend(self.output_ids_by_query(&NftOutputQuery::new().with_address(*address)).await?);
+            output_ids.extend(
+                self.output_ids_by_query(
+                    &NftOutputQuery::new()
+                        .with_expiration_return_address(*address)
+                        .with_expires_before(milestone_timestamp),
+                )
+                .await?,
+            );
+            output_ids.extend(
+                self.output_ids_by_query(&AliasOutputQuery::new().with_state_controller(*address))
+                    .await?,
+            );
+        }
+        let mut outputs = self.get_typed_outputs(output_ids).await?;
+
+        // The foundries are controlled by their alias
+        let alias_addresses = addresses
+            .iter()
+            .filter_map(|address| match address {
+                Address::Alias(alias_address) => Some(*alias_address),
+                _ => None,
+            })
+            .chain(outputs.iter().filter_map(|(output_id, output)| match output {
+                Output::Alias(alias_output) => Some(AliasAddress::new(alias_output.alias_id_non_null(output_id))),
+                _ => None,
+            }))
+            .collect::<HashSet<_>>();
+        let mut foundry_output_ids = HashSet::new();
+        for alias_address in alias_addresses {
+            foundry_output_ids.extend(
+                self.output_ids_by_query(&FoundryOutputQuery::new().with_alias_address(alias_address))
+                    .await?,
+            );
+        }
+        outputs.extend(self.get_typed_outputs(foundry_output_ids).await?);
+
+        let mut balance = AddressBalance {
+            milestone_timestamp,
+            ..Default::default()
+        };
+        for (output_id, output) in &outputs {
+            match output {
+                Output::Alias(alias_output) => balance.aliases.push(alias_output.alias_id_non_null(output_id)),
+                Output::Foundry(foundry_output) => balance.foundries.push(foundry_output.id()),
+                _ => {}
+            }
+
+            let lock = match output.unlock_conditions() {
+                Some(unlock_conditions) => {
+                    if let Some(address) = unlock_conditions.address() {
+                        // Expired outputs belong to the return address
+                        if !addresses.contains(unlock_conditions.locked_address(address.address(), milestone_timestamp))
+                        {
+                            continue;
+                        }
+                    }
+
+                    if unlock_conditions.is_time_locked(milestone_timestamp) {
+                        Lock::Timelock
+                    } else if unlock_conditions.expiration().is_some()
+                        && !unlock_conditions.is_expired(milestone_timestamp)
+                    {
+                        Lock::Expiration
+                    } else {
+                        Lock::None
+                    }
+                }
+                None => Lock::None,
+            };
+            if let Output::Nft(nft_output) = output {
+                balance.nfts.push(nft_output.nft_id_non_null(output_id));
+            }
+            let storage_deposit_return = sdr_not_expired(output, milestone_timestamp).map_or(0, |sdr| sdr.amount());
+
+            balance.add_funds(output.amount(), output.native_tokens(), lock, storage_deposit_return);
+        }
+        balance.nfts.sort();
+        balance.aliases.sort();
+        balance.foundries.sort();
+
+        Ok(balance)
+    }
+
+    // Requests the outputs and converts them from their DTOs.
+    async fn get_typed_outputs(&self, output_ids: HashSet<OutputId>) -> Result<Vec<(OutputId, Output)>> {
+        let output_ids = output_ids.into_iter().collect::<Vec<_>>();
+        let outputs = self.get_outputs(output_ids.clone()).await?;
+        let token_supply = self.get_token_supply().await?;
+
+        output_ids
+            .into_iter()
+            .zip(outputs)
+            .map(|(output_id, output)| Ok((output_id, Output::try_from_dto(&output.output, token_supply)?)))
+            .collect()
+    }
+}
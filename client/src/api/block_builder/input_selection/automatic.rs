@@ -85,11 +85,9 @@ impl<'a> ClientBlockBuilder<'a> {
             self.outputs.clone(),
             self.custom_remainder_address,
             rent_structure,
-            // Don't allow burning of native tokens during automatic input selection, because otherwise it
-            // could lead to burned native tokens by accident.
-            false,
             current_time,
             token_supply,
+            self.input_selection_options(),
         ) {
             return Ok(selected_transaction_data);
         };
@@ -137,16 +135,17 @@ impl<'a> ClientBlockBuilder<'a> {
                     empty_address_count = 0;
 
                     for output_response in address_outputs {
+                        let output_id = output_response.metadata.output_id()?;
+                        // Skip outputs that are used by other prepared transactions
+                        if self.client.is_output_reserved(&output_id)? {
+                            continue;
+                        }
                         let output = Output::try_from_dto(&output_response.output, token_supply)?;
                         let address = Address::try_from_bech32(str_address)?.1;
 
                         // We can ignore the unlocked_alias_or_nft_address, since we only requested basic outputs
-                        let (required_unlock_address, _unlocked_alias_or_nft_address) = output
-                            .required_and_unlocked_address(
-                                current_time,
-                                &output_response.metadata.output_id()?,
-                                false,
-                            )?;
+                        let (required_unlock_address, _unlocked_alias_or_nft_address) =
+                            output.required_and_unlocked_address(current_time, &output_id, false)?;
                         if required_unlock_address == address {
                             available_inputs.push(InputSigningData {
                                 output,
@@ -168,11 +167,9 @@ impl<'a> ClientBlockBuilder<'a> {
                         self.outputs.clone(),
                         self.custom_remainder_address,
                         rent_structure,
-                        // Don't allow burning of native tokens during automatic input selection, because otherwise it
-                        // could lead to burned native tokens by accident.
-                        false,
                         current_time,
                         token_supply,
+                        self.input_selection_options(),
                     ) {
                         Ok(r) => r,
                         // for these errors, just try again in the next round with more addresses which might have more
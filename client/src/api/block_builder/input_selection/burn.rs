@@ -0,0 +1,142 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The assets a transaction is allowed to burn
+
+use std::collections::{BTreeMap, HashSet};
+
+use iota_types::block::output::{
+    AliasId, ChainId, FoundryId, NativeToken, NativeTokensBuilder, NftId, Output, OutputId, TokenId,
+};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::input_selection::{
+        get_accumulated_output_amounts, get_minted_and_melted_native_tokens, get_remainder_native_tokens,
+    },
+    secret::types::InputSigningData,
+    Error, Result,
+};
+
+/// The aliases, foundries, NFTs and amounts of native tokens that a transaction burns. Input selection fails for
+/// anything else that would be burned, so aliases, foundries and NFTs of the inputs are added to the outputs again and
+/// remaining native tokens are kept in the remainder.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Burn {
+    /// The aliases to burn.
+    pub aliases: HashSet<AliasId>,
+    /// The NFTs to burn.
+    pub nfts: HashSet<NftId>,
+    /// The foundries to burn.
+    pub foundries: HashSet<FoundryId>,
+    /// The amounts of native tokens to burn.
+    pub native_tokens: BTreeMap<TokenId, U256>,
+}
+
+impl Burn {
+    /// Creates a new [`Burn`] that doesn't burn anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an alias to burn.
+    pub fn add_alias(mut self, alias_id: AliasId) -> Self {
+        self.aliases.insert(alias_id);
+        self
+    }
+
+    /// Adds an NFT to burn.
+    pub fn add_nft(mut self, nft_id: NftId) -> Self {
+        self.nfts.insert(nft_id);
+        self
+    }
+
+    /// Adds a foundry to burn.
+    pub fn add_foundry(mut self, foundry_id: FoundryId) -> Self {
+        self.foundries.insert(foundry_id);
+        self
+    }
+
+    /// Adds an amount of a native token to burn, amounts of the same token are summed up.
+    pub fn add_native_token(mut self, token_id: TokenId, amount: impl Into<U256>) -> Self {
+        let burned_amount = self.native_tokens.entry(token_id).or_default();
+        *burned_amount = burned_amount.saturating_add(amount.into());
+        self
+    }
+
+    /// Returns if nothing is burned.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty() && self.nfts.is_empty() && self.foundries.is_empty() && self.native_tokens.is_empty()
+    }
+
+    /// Returns if the alias, foundry or NFT chain is burned.
+    pub fn contains_chain(&self, chain_id: &ChainId) -> bool {
+        match chain_id {
+            ChainId::Alias(alias_id) => self.aliases.contains(alias_id),
+            ChainId::Foundry(foundry_id) => self.foundries.contains(foundry_id),
+            ChainId::Nft(nft_id) => self.nfts.contains(nft_id),
+        }
+    }
+
+    // The native tokens to burn, to require them like the native tokens of the outputs.
+    pub(crate) fn native_tokens_builder(&self) -> Result<NativeTokensBuilder> {
+        let mut native_tokens = NativeTokensBuilder::new();
+        for (token_id, amount) in &self.native_tokens {
+            native_tokens.add_native_token(NativeToken::new(*token_id, *amount)?)?;
+        }
+
+        Ok(native_tokens)
+    }
+}
+
+// Returns if the output is an alias, foundry or NFT output whose chain is burned.
+pub(crate) fn is_burned(burn: Option<&Burn>, output: &Output, output_id: &OutputId) -> bool {
+    match (burn, output.chain_id()) {
+        (Some(burn), Some(chain_id)) => burn.contains_chain(&chain_id.or_from_output_id(output_id)),
+        _ => false,
+    }
+}
+
+// Fails if the transaction burns an alias, foundry, NFT or native token that isn't listed in the burn, or more of a
+// native token than listed.
+pub(crate) fn check_burn(inputs: &[InputSigningData], outputs: &[Output], burn: Option<&Burn>) -> Result<()> {
+    for input in inputs {
+        if let Some(chain_id) = input.output.chain_id() {
+            let chain_id = chain_id.or_from_output_id(input.output_id());
+            let in_outputs = outputs.iter().any(|output| output.chain_id() == Some(chain_id));
+
+            if !in_outputs && !is_burned(burn, &input.output, input.output_id()) {
+                return Err(Error::UnlistedBurn(chain_id.to_string()));
+            }
+        }
+    }
+
+    let input_outputs = inputs.iter().map(|input| &input.output);
+    let (minted_native_tokens, melted_native_tokens) =
+        get_minted_and_melted_native_tokens(&input_outputs, outputs.iter())?;
+    let mut input_native_tokens = get_accumulated_output_amounts(&std::iter::empty(), input_outputs)?.native_tokens;
+    input_native_tokens.merge(minted_native_tokens)?;
+    let mut output_native_tokens = get_accumulated_output_amounts(&std::iter::empty(), outputs.iter())?.native_tokens;
+    output_native_tokens.merge(melted_native_tokens)?;
+
+    if let Some(burned_native_tokens) = get_remainder_native_tokens(&input_native_tokens, &output_native_tokens)? {
+        for native_token in burned_native_tokens.iter() {
+            let allowed_amount = burn
+                .and_then(|burn| burn.native_tokens.get(native_token.token_id()))
+                .copied()
+                .unwrap_or_default();
+
+            if native_token.amount() > allowed_amount {
+                return Err(Error::UnlistedBurn(format!(
+                    "{} of native token {}",
+                    native_token.amount() - allowed_amount,
+                    native_token.token_id()
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Strategies to choose which basic outputs are used as inputs for the base coin amount of a transaction
+
+use std::{cmp::Reverse, fmt::Debug};
+
+use serde::{Deserialize, Serialize};
+
+use super::helpers::sdr_not_expired;
+use crate::secret::types::InputSigningData;
+
+/// Maximum number of subsets that [`BranchAndBound`] checks for an exact match.
+const BRANCH_AND_BOUND_MAX_TRIES: usize = 100_000;
+
+/// Decides in which order the basic outputs are added to the inputs until the required amount and the storage deposit
+/// of a remainder are covered. Candidates that are removed from the vector aren't selected at all, so a strategy can
+/// for example only keep the outputs of a single address.
+pub trait CoinSelectionStrategy: Debug + Send + Sync {
+    /// Orders the candidate inputs. `missing_amount` is the amount that is still required after the mandatory, alias,
+    /// foundry and NFT inputs and the inputs for native tokens were selected.
+    fn sort_inputs(&self, candidates: &mut Vec<&InputSigningData>, missing_amount: u64, current_time: u32);
+}
+
+/// Selects the outputs with the largest amounts first, which minimizes the number of inputs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LargestFirst;
+
+impl CoinSelectionStrategy for LargestFirst {
+    fn sort_inputs(&self, candidates: &mut Vec<&InputSigningData>, _missing_amount: u64, _current_time: u32) {
+        candidates.sort_by_key(|input| Reverse(input.output.amount()));
+    }
+}
+
+/// Selects the outputs with the smallest amounts first, which consolidates many small outputs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SmallestFirst;
+
+impl CoinSelectionStrategy for SmallestFirst {
+    fn sort_inputs(&self, candidates: &mut Vec<&InputSigningData>, _missing_amount: u64, _current_time: u32) {
+        candidates.sort_by_key(|input| input.output.amount());
+    }
+}
+
+/// Selects the outputs that were booked first.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OldestFirst;
+
+impl CoinSelectionStrategy for OldestFirst {
+    fn sort_inputs(&self, candidates: &mut Vec<&InputSigningData>, _missing_amount: u64, _current_time: u32) {
+        candidates.sort_by_key(|input| input.output_metadata.milestone_timestamp_booked());
+    }
+}
+
+/// Searches outputs without native tokens that exactly cover the missing amount, so no remainder is created. The
+/// outputs of the match are selected first, the other ones follow with the largest amounts first, for when there is no
+/// exact match.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BranchAndBound;
+
+impl CoinSelectionStrategy for BranchAndBound {
+    fn sort_inputs(&self, candidates: &mut Vec<&InputSigningData>, missing_amount: u64, current_time: u32) {
+        LargestFirst.sort_inputs(candidates, missing_amount, current_time);
+
+        // The storage deposit that has to be returned isn't available for the outputs
+        let values = candidates
+            .iter()
+            .map(|input| match input.output.native_tokens() {
+                Some(native_tokens) if !native_tokens.is_empty() => None,
+                _ => Some(
+                    input.output.amount() - sdr_not_expired(&input.output, current_time).map_or(0, |sdr| sdr.amount()),
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(exact_match) = exact_match(&values, missing_amount) {
+            let (mut sorted, rest): (Vec<_>, Vec<_>) = candidates
+                .drain(..)
+                .enumerate()
+                .partition(|(index, _)| exact_match.contains(index));
+            sorted.extend(rest);
+            candidates.extend(sorted.into_iter().map(|(_, input)| input));
+        }
+    }
+}
+
+// Depth first search for the indices of values that sum up to the target, trying the largest values first.
+fn exact_match(values: &[Option<u64>], target: u64) -> Option<Vec<usize>> {
+    if target == 0 {
+        return None;
+    }
+
+    // The sum of the values after every index, to stop searching when the rest can't reach the target anymore
+    let mut remaining = vec![0u64; values.len() + 1];
+    for index in (0..values.len()).rev() {
+        remaining[index] = remaining[index + 1].saturating_add(values[index].unwrap_or(0));
+    }
+
+    let mut selected = Vec::new();
+    let mut tries = 0;
+    search(values, &remaining, target, 0, &mut selected, &mut tries).then_some(selected)
+}
+
+fn search(
+    values: &[Option<u64>],
+    remaining: &[u64],
+    target: u64,
+    index: usize,
+    selected: &mut Vec<usize>,
+    tries: &mut usize,
+) -> bool {
+    if target == 0 {
+        return true;
+    }
+    *tries += 1;
+    if index == values.len() || remaining[index] < target || *tries > BRANCH_AND_BOUND_MAX_TRIES {
+        return false;
+    }
+
+    if let Some(value) = values[index] {
+        if value > 0 && value <= target {
+            selected.push(index);
+            if search(values, remaining, target - value, index + 1, selected, tries) {
+                return true;
+            }
+            selected.pop();
+        }
+    }
+
+    search(values, remaining, target, index + 1, selected, tries)
+}
+
+/// The built-in coin selection strategies, to choose one in the
+/// [`ClientBlockBuilderOptions`](crate::api::ClientBlockBuilderOptions).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CoinSelection {
+    /// [`LargestFirst`]
+    LargestFirst,
+    /// [`SmallestFirst`]
+    SmallestFirst,
+    /// [`OldestFirst`]
+    OldestFirst,
+    /// [`BranchAndBound`]
+    BranchAndBound,
+}
+
+impl CoinSelectionStrategy for CoinSelection {
+    fn sort_inputs(&self, candidates: &mut Vec<&InputSigningData>, missing_amount: u64, current_time: u32) {
+        match self {
+            Self::LargestFirst => LargestFirst.sort_inputs(candidates, missing_amount, current_time),
+            Self::SmallestFirst => SmallestFirst.sort_inputs(candidates, missing_amount, current_time),
+            Self::OldestFirst => OldestFirst.sort_inputs(candidates, missing_amount, current_time),
+            Self::BranchAndBound => BranchAndBound.sort_inputs(candidates, missing_amount, current_time),
+        }
+    }
+}
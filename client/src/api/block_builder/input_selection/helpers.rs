@@ -12,7 +12,7 @@ use iota_types::block::{
 };
 
 use crate::{
-    api::input_selection::{get_minted_and_melted_native_tokens, types::AccumulatedOutputAmounts},
+    api::input_selection::{get_minted_and_melted_native_tokens, report::SkipReason, types::AccumulatedOutputAmounts},
     secret::types::InputSigningData,
     Result,
 };
@@ -86,6 +86,30 @@ pub(crate) fn sdr_not_expired(output: &Output, current_time: u32) -> Option<&Sto
     }
 }
 
+// Get why an input can't be unlocked by its address at the current time, if it can't
+pub(crate) fn unlock_skip_reason(input: &InputSigningData, current_time: u32) -> Option<SkipReason> {
+    let unlock_conditions = input.output.unlock_conditions()?;
+    if unlock_conditions.is_time_locked(current_time) {
+        return Some(SkipReason::Timelocked);
+    }
+    // After the expiration only the return address can unlock the output, it's the required unlock address then
+    if let Some(expiration) = unlock_conditions.expiration() {
+        if expiration.return_address_expired(current_time).is_some() {
+            let (required_unlock_address, _) = input
+                .output
+                .required_and_unlocked_address(current_time, input.output_id(), false)
+                .ok()?;
+            return match Address::try_from_bech32(&input.bech32_address) {
+                Ok((_, address)) if address == required_unlock_address => None,
+                Ok(_) => Some(SkipReason::Expired),
+                Err(_) => Some(SkipReason::InvalidAddress),
+            };
+        }
+    }
+
+    None
+}
+
 // Inputs need to be sorted before signing, because the reference unlock conditions can only reference a lower index
 pub(crate) fn sort_input_signing_data(inputs: Vec<InputSigningData>) -> crate::Result<Vec<InputSigningData>> {
     // filter for ed25519 address first, safe to unwrap since we encoded it before
@@ -13,8 +13,10 @@ use iota_types::block::{
 
 use crate::{
     api::{
-        address::search_address, block_builder::input_selection::types::SelectedTransactionData,
-        input_selection::try_select_inputs, ClientBlockBuilder,
+        address::search_address,
+        block_builder::input_selection::types::{InputSelectionOptions, SelectedTransactionData},
+        input_selection::{burn::Burn, try_select_inputs},
+        ClientBlockBuilder,
     },
     constants::HD_WALLET_TYPE,
     secret::types::{InputSigningData, OutputMetadata},
@@ -25,13 +27,12 @@ impl<'a> ClientBlockBuilder<'a> {
     /// If custom inputs are provided we check if they are unspent, get the balance and search the Ed25519 addresses for
     /// them with the provided input_range so we can later sign them.
     /// Forwards to [try_select_inputs()] with all inputs in `mandatory_inputs`, so they will all be included in the
-    /// transaction, even if not required for the provided outputs. Careful with setting `allow_burning` to `true`,
-    /// native tokens, nfts or alias outputs can get easily burned by accident.
+    /// transaction, even if not required for the provided outputs. Only what's listed in the `burn` gets burned.
     pub(crate) async fn get_custom_inputs(
         &self,
         governance_transition: Option<HashSet<AliasId>>,
         rent_structure: &RentStructure,
-        allow_burning: bool,
+        burn: Option<&Burn>,
     ) -> Result<SelectedTransactionData> {
         log::debug!("[get_custom_inputs]");
 
@@ -98,9 +99,12 @@ impl<'a> ClientBlockBuilder<'a> {
             self.outputs.clone(),
             self.custom_remainder_address,
             rent_structure,
-            allow_burning,
             current_time,
             token_supply,
+            InputSelectionOptions {
+                burn,
+                ..self.input_selection_options()
+            },
         )?;
 
         Ok(selected_transaction_data)
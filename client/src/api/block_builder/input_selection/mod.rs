@@ -4,16 +4,20 @@
 //! Input selection for transactions
 
 mod automatic;
+pub mod burn;
+pub mod coin_selection;
 mod helpers;
 mod manual;
 mod native_token_helpers;
-mod remainder;
+pub mod remainder;
+pub mod report;
 mod sender_issuer;
 pub mod types;
 mod utxo_chains;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub use helpers::minimum_storage_deposit_basic_output;
+pub(crate) use helpers::sdr_not_expired;
 use iota_types::block::{
     address::Address,
     input::INPUT_COUNT_MAX,
@@ -22,18 +26,25 @@ use iota_types::block::{
 use packable::bounded::TryIntoBoundedU16Error;
 
 use self::{
+    burn::{check_burn, Burn},
     helpers::get_accumulated_output_amounts,
     native_token_helpers::{get_minted_and_melted_native_tokens, get_remainder_native_tokens, missing_native_tokens},
-    remainder::{get_additional_required_remainder_amount, get_remainder_output},
+    remainder::{
+        get_additional_required_remainder_amount, get_remainder_outputs, remainder_output_limit, RemainderPolicy,
+    },
+    report::{CandidateDecision, SelectionReason},
     sender_issuer::select_inputs_for_sender_and_issuer,
-    types::SelectedTransactionData,
+    types::{InputSelectionOptions, SelectedTransactionData},
 };
 use crate::{
-    api::input_selection::{
-        helpers::{sdr_not_expired, sort_input_signing_data},
-        remainder::get_storage_deposit_return_outputs,
-        types::AccumulatedOutputAmounts,
-        utxo_chains::{check_utxo_chain_inputs, select_utxo_chain_inputs},
+    api::{
+        input_selection::{
+            helpers::{sort_input_signing_data, unlock_skip_reason},
+            remainder::get_storage_deposit_return_outputs,
+            types::AccumulatedOutputAmounts,
+            utxo_chains::{check_utxo_chain_inputs, select_utxo_chain_inputs},
+        },
+        RemainderData,
     },
     secret::types::InputSigningData,
     Error, Result,
@@ -41,22 +52,64 @@ use crate::{
 
 /// Select inputs from provided mandatory_inputs([InputSigningData]) and additional_inputs([InputSigningData]) for
 /// provided [Output]s, validate amounts and create remainder output if necessary. Also checks for alias, foundry and
-/// nft outputs that there previous output exist in the inputs, when required. Careful with setting `allow_burning` to
-/// `true`, native tokens, nfts or alias outputs can get easily burned by accident. Without burning, alias, foundry and
-/// nft outputs will be created on the output side, if not already present.
+/// nft outputs that there previous output exist in the inputs, when required. Only the aliases, foundries, nfts and
+/// native tokens listed in the `burn` of the [`InputSelectionOptions`] get burned, other alias, foundry and nft outputs
+/// will be created on the output side, if not already present, and it fails with [`Error::UnlistedBurn`] if anything
+/// else would be burned. Additional inputs that are timelocked or expired are skipped. See
+/// [`report::dry_run_select_inputs()`] to find out why inputs were selected or why the selection fails.
 #[allow(clippy::too_many_arguments)]
 pub fn try_select_inputs(
-    mut mandatory_inputs: Vec<InputSigningData>,
-    mut additional_inputs: Vec<InputSigningData>,
+    mandatory_inputs: Vec<InputSigningData>,
+    additional_inputs: Vec<InputSigningData>,
     mut outputs: Vec<Output>,
     remainder_address: Option<Address>,
     rent_structure: &RentStructure,
-    allow_burning: bool,
     current_time: u32,
     token_supply: u64,
+    options: InputSelectionOptions<'_>,
 ) -> Result<SelectedTransactionData> {
     log::debug!("[try_select_inputs]");
 
+    let mut selected_inputs = Vec::new();
+    let remainder_data = select_inputs(
+        mandatory_inputs,
+        additional_inputs,
+        &mut selected_inputs,
+        &mut outputs,
+        remainder_address,
+        rent_structure,
+        current_time,
+        token_supply,
+        options,
+        &mut HashMap::new(),
+    )?;
+
+    finish_selection(selected_inputs, outputs, remainder_data, options.burn)
+}
+
+// Selects the inputs and records the decision for every selected or skipped input. The selected inputs and the
+// outputs are owned by the caller, so their state is still available when the selection fails.
+#[allow(clippy::too_many_arguments)]
+fn select_inputs(
+    mut mandatory_inputs: Vec<InputSigningData>,
+    mut additional_inputs: Vec<InputSigningData>,
+    selected_inputs: &mut Vec<InputSigningData>,
+    outputs: &mut Vec<Output>,
+    remainder_address: Option<Address>,
+    rent_structure: &RentStructure,
+    current_time: u32,
+    token_supply: u64,
+    options: InputSelectionOptions<'_>,
+    decisions: &mut HashMap<OutputId, CandidateDecision>,
+) -> Result<Vec<RemainderData>> {
+    let InputSelectionOptions {
+        burn,
+        coin_selection_strategy,
+        remainder_policy,
+    } = options;
+    let default_remainder_policy = RemainderPolicy::default();
+    let remainder_policy = remainder_policy.unwrap_or(&default_remainder_policy);
+
     // Can't select inputs if there are no inputs.
     if mandatory_inputs.is_empty() && additional_inputs.is_empty() {
         return Err(crate::Error::NoInputs);
@@ -64,8 +117,21 @@ pub fn try_select_inputs(
 
     dedup_inputs(&mut mandatory_inputs, &mut additional_inputs);
 
+    // Skip additional inputs that can't be unlocked at the current time, mandatory inputs are used anyway.
+    let mut unlockable_inputs = Vec::new();
+    for input in additional_inputs {
+        match unlock_skip_reason(&input, current_time) {
+            Some(skip_reason) => {
+                decisions.insert(*input.output_id(), CandidateDecision::Skipped(skip_reason));
+            }
+            None => unlockable_inputs.push(input),
+        }
+    }
+    let additional_inputs = unlockable_inputs;
+
     // Always have the mandatory inputs already selected.
-    let mut selected_inputs: Vec<InputSigningData> = mandatory_inputs.clone();
+    selected_inputs.extend(mandatory_inputs.iter().cloned());
+    record_selection(selected_inputs, decisions, SelectionReason::Mandatory);
     // Keep track of which inputs we selected in a HashSet, so we don't need to iterate over the inputs every time.
     let mut selected_inputs_output_ids: HashSet<OutputId> =
         selected_inputs.iter().map(|input| *input.output_id()).collect();
@@ -74,13 +140,15 @@ pub fn try_select_inputs(
 
     // select outputs for sender/issuer features. Alias and nft outputs added to the inputs will be added to the outputs
     // in select_utxo_chain_inputs().
-    select_inputs_for_sender_and_issuer(
+    let selection = select_inputs_for_sender_and_issuer(
         all_inputs.clone(),
-        &mut selected_inputs,
+        selected_inputs,
         &mut selected_inputs_output_ids,
-        &mut outputs,
+        outputs,
         current_time,
-    )?;
+    );
+    record_selection(selected_inputs, decisions, SelectionReason::SenderOrIssuer);
+    selection?;
 
     let mut required = get_accumulated_output_amounts(&input_outputs, outputs.iter())?;
     // Add the minted tokens to the inputs, because we don't need to provide other inputs for them
@@ -116,25 +184,32 @@ pub fn try_select_inputs(
     // Check the inputs in a loop, because if we add an an output which requires another Alias or NFT output to unlock
     // it, then we might have to add this also.
     // Inputs for which no outputs exists already, will be added automatically to the outputs, if burning isn't allowed.
-    select_utxo_chain_inputs(
-        &mut selected_inputs,
+    let selection = select_utxo_chain_inputs(
+        selected_inputs,
         &mut selected_inputs_output_ids,
         &mut selected_input_amount,
         &mut selected_input_native_tokens,
-        &mut outputs,
+        outputs,
         &mut required,
         &mut utxo_chain_inputs,
-        allow_burning,
+        burn,
         current_time,
         rent_structure,
         token_supply,
-    )?;
+    );
+    record_selection(selected_inputs, decisions, SelectionReason::ChainRequirement);
+    selection?;
 
     // No need to check for sender and issuer again, since these outputs already exist and we don't set new features
     // for them.
 
     // Validate that we have selected the required inputs for alias, nft and foundry outputs.
-    check_utxo_chain_inputs(&selected_inputs, &outputs)?;
+    check_utxo_chain_inputs(selected_inputs, outputs)?;
+
+    // The native tokens to burn are required like the ones of the outputs
+    if let Some(burn) = burn {
+        required.native_tokens.merge(burn.native_tokens_builder()?)?;
+    }
 
     // Remove inputs we added in `select_inputs_for_sender_and_issuer()`
     let mut index = 0;
@@ -187,11 +262,21 @@ pub fn try_select_inputs(
         }
     }
 
+    record_selection(selected_inputs, decisions, SelectionReason::NativeTokens);
+
     // check if we got all required native tokens
     if let Some(native_token) = missing_native_tokens(&selected_input_native_tokens, &required.native_tokens)? {
         return Err(Error::NotEnoughNativeTokens(native_token));
     }
 
+    if let Some(coin_selection_strategy) = coin_selection_strategy {
+        coin_selection_strategy.sort_inputs(
+            &mut basic_outputs,
+            required.amount.saturating_sub(selected_input_amount),
+            current_time,
+        );
+    }
+
     // 3. try to select basic outputs without native tokens
     let mut index = 0;
     while index < basic_outputs.len() {
@@ -199,7 +284,9 @@ pub fn try_select_inputs(
 
         let additional_required_remainder_amount = get_additional_required_remainder_amount(
             remainder_address,
-            &selected_inputs,
+            remainder_policy,
+            remainder_output_limit(outputs.len()),
+            selected_inputs,
             selected_input_amount,
             &selected_input_native_tokens,
             &required,
@@ -213,6 +300,10 @@ pub fn try_select_inputs(
 
             if let Some(output_native_tokens) = output.native_tokens() {
                 if output_native_tokens.is_empty() {
+                    decisions.insert(
+                        *basic_outputs[index].output_id(),
+                        CandidateDecision::Selected(amount_selection_reason(selected_input_amount, required.amount)),
+                    );
                     selected_input_amount += output.amount();
                     selected_inputs.push(basic_outputs[index].clone());
                     added_to_inputs = true;
@@ -240,8 +331,15 @@ pub fn try_select_inputs(
         return Err(Error::ConsolidationRequired(current_selected_input_len.into()));
     }
 
-    // Order input outputs descending, so that as few inputs as necessary are used
-    basic_outputs.sort_by(|l, r| l.output.amount().cmp(&r.output.amount()));
+    match coin_selection_strategy {
+        Some(coin_selection_strategy) => coin_selection_strategy.sort_inputs(
+            &mut basic_outputs,
+            required.amount.saturating_sub(selected_input_amount),
+            current_time,
+        ),
+        // Order input outputs descending, so that as few inputs as necessary are used
+        None => basic_outputs.sort_by(|l, r| l.output.amount().cmp(&r.output.amount())),
+    }
 
     // 4. try to select basic outputs with native tokens we need for the outputs
     let mut index = 0;
@@ -250,7 +348,9 @@ pub fn try_select_inputs(
 
         let additional_required_remainder_amount = get_additional_required_remainder_amount(
             remainder_address,
-            &selected_inputs,
+            remainder_policy,
+            remainder_output_limit(outputs.len()),
+            selected_inputs,
             selected_input_amount,
             &selected_input_native_tokens,
             &required,
@@ -262,6 +362,10 @@ pub fn try_select_inputs(
         if selected_input_amount < required.amount || additional_required_remainder_amount > 0 {
             let output = &basic_outputs[index].output;
 
+            decisions.insert(
+                *basic_outputs[index].output_id(),
+                CandidateDecision::Selected(amount_selection_reason(selected_input_amount, required.amount)),
+            );
             selected_input_amount += output.amount();
             if let Some(output_native_tokens) = output.native_tokens() {
                 selected_input_native_tokens.add_native_tokens(output_native_tokens.clone())?;
@@ -296,35 +400,47 @@ pub fn try_select_inputs(
     outputs.extend(additional_storage_deposit_return_outputs.into_iter());
 
     // Check utxo chain inputs again, because new inputs could have an alias or nft address in their unlock condition
-    select_utxo_chain_inputs(
-        &mut selected_inputs,
+    let selection = select_utxo_chain_inputs(
+        selected_inputs,
         &mut selected_inputs_output_ids,
         &mut selected_input_amount,
         &mut selected_input_native_tokens,
-        &mut outputs,
+        outputs,
         &mut required,
         &mut utxo_chain_inputs,
-        allow_burning,
+        burn,
         current_time,
         rent_structure,
         token_supply,
-    )?;
+    );
+    record_selection(selected_inputs, decisions, SelectionReason::ChainRequirement);
+    selection?;
 
-    // create remainder output if necessary
-    // get_remainder also checks for amounts and returns an error if we don't have enough
-    let remainder_data = get_remainder_output(
+    // create remainder outputs if necessary
+    // get_remainder_outputs also checks for amounts and returns an error if we don't have enough
+    get_remainder_outputs(
         selected_inputs.iter(),
         outputs.iter(),
         remainder_address,
+        remainder_policy,
         rent_structure,
-        allow_burning,
+        burn,
         current_time,
         token_supply,
-    )?;
-    if let Some(remainder_data) = &remainder_data {
-        outputs.push(remainder_data.output.clone());
+    )
+}
+
+// Adds the remainders to the outputs, checks the outputs and sorts the inputs for signing
+fn finish_selection(
+    selected_inputs: Vec<InputSigningData>,
+    mut outputs: Vec<Output>,
+    remainder_data: Vec<RemainderData>,
+    burn: Option<&Burn>,
+) -> Result<SelectedTransactionData> {
+    if !remainder_data.is_empty() {
+        outputs.extend(remainder_data.iter().map(|remainder_data| remainder_data.output.clone()));
 
-        // check if we have too many outputs after adding the remainder output
+        // check if we have too many outputs after adding the remainder outputs
         if outputs.len() as u16 > OUTPUT_COUNT_MAX {
             return Err(Error::BlockError(iota_types::block::Error::InvalidOutputCount(
                 TryIntoBoundedU16Error::Truncated(outputs.len()),
@@ -332,15 +448,40 @@ pub fn try_select_inputs(
         }
     }
 
+    check_burn(&selected_inputs, &outputs, burn)?;
+
     let sorted_inputs = sort_input_signing_data(selected_inputs)?;
 
     Ok(SelectedTransactionData {
         inputs: sorted_inputs,
         outputs,
-        remainder: remainder_data,
+        remainder: remainder_data.first().cloned(),
+        additional_remainders: remainder_data.into_iter().skip(1).collect(),
     })
 }
 
+// Records the reason for the selected inputs that don't have a decision yet
+fn record_selection(
+    selected_inputs: &[InputSigningData],
+    decisions: &mut HashMap<OutputId, CandidateDecision>,
+    reason: SelectionReason,
+) {
+    for input in selected_inputs {
+        decisions
+            .entry(*input.output_id())
+            .or_insert(CandidateDecision::Selected(reason));
+    }
+}
+
+// Basic inputs are selected for the amount of the outputs first, then for the storage deposit of the remainder
+fn amount_selection_reason(selected_input_amount: u64, required_amount: u64) -> SelectionReason {
+    if selected_input_amount < required_amount {
+        SelectionReason::Amount
+    } else {
+        SelectionReason::RemainderStorageDeposit
+    }
+}
+
 // Dedup inputs by output id, because other data could be different, even if it's the same output
 fn dedup_inputs(mandatory_inputs: &mut Vec<InputSigningData>, additional_inputs: &mut Vec<InputSigningData>) {
     // Sorting inputs by OutputId so duplicates can be safely removed.
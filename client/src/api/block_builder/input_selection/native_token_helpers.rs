@@ -12,6 +12,16 @@ pub(crate) fn missing_native_tokens(
     inputs: &NativeTokensBuilder,
     required: &NativeTokensBuilder,
 ) -> Result<Option<NativeTokens>> {
+    let missing_native_tokens = missing_native_tokens_builder(inputs, required);
+
+    if missing_native_tokens.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(missing_native_tokens.finish()?))
+    }
+}
+
+fn missing_native_tokens_builder(inputs: &NativeTokensBuilder, required: &NativeTokensBuilder) -> NativeTokensBuilder {
     let mut missing_native_tokens = NativeTokensBuilder::new();
 
     for (token_id, required_native_token_amount) in required.iter() {
@@ -27,11 +37,7 @@ pub(crate) fn missing_native_tokens(
         }
     }
 
-    if missing_native_tokens.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(missing_native_tokens.finish()?))
-    }
+    missing_native_tokens
 }
 
 pub(crate) fn get_remainder_native_tokens(
@@ -42,6 +48,15 @@ pub(crate) fn get_remainder_native_tokens(
     missing_native_tokens(required, inputs)
 }
 
+// Like get_remainder_native_tokens(), but as list, since a remainder that's split into multiple outputs can have more
+// native tokens than fit into one output
+pub(crate) fn get_remainder_native_token_list(
+    inputs: &NativeTokensBuilder,
+    required: &NativeTokensBuilder,
+) -> Result<Vec<NativeToken>> {
+    Ok(missing_native_tokens_builder(required, inputs).finish_vec()?)
+}
+
 // Get minted and melted tokens from foundry outputs
 // minted first, melted second
 pub(crate) fn get_minted_and_melted_native_tokens<'a>(
@@ -1,21 +1,26 @@
 // Copyright 2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+//! Remainder outputs for the leftover base coin and native tokens of a transaction
+
+use std::{cmp::Reverse, collections::HashMap};
 
 use iota_types::block::{
     address::Address,
     output::{
         unlock_condition::{AddressUnlockCondition, UnlockCondition},
-        BasicOutputBuilder, NativeTokensBuilder, Output, RentStructure,
+        BasicOutputBuilder, NativeToken, NativeTokens, NativeTokensBuilder, Output, RentStructure, OUTPUT_COUNT_MAX,
     },
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     api::{
         input_selection::{
-            get_accumulated_output_amounts, get_minted_and_melted_native_tokens, get_remainder_native_tokens,
+            burn::Burn,
+            get_accumulated_output_amounts, get_minted_and_melted_native_tokens,
             helpers::{minimum_storage_deposit_basic_output, sdr_not_expired},
+            native_token_helpers::get_remainder_native_token_list,
             AccumulatedOutputAmounts,
         },
         RemainderData,
@@ -25,6 +30,52 @@ use crate::{
     Error, Result,
 };
 
+/// How the leftover base coin and native tokens of a transaction are split into remainder outputs. By default, a single
+/// remainder output to the remainder address holds all of them. The number of remainder outputs is limited by
+/// [`OUTPUT_COUNT_MAX`] and every remainder output has to cover its own storage deposit.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemainderPolicy {
+    /// How the leftovers are split into outputs.
+    pub split: RemainderSplit,
+    /// Addresses that the remainder outputs are sent to in turn, the remainder address is used if there are none.
+    pub addresses: Vec<Address>,
+}
+
+impl RemainderPolicy {
+    /// Creates a new [`RemainderPolicy`] that splits the leftovers as given.
+    pub fn new(split: RemainderSplit) -> Self {
+        Self {
+            split,
+            addresses: Vec::new(),
+        }
+    }
+
+    /// Adds an address that remainder outputs are sent to, round-robin with the other added addresses.
+    pub fn add_address(mut self, address: Address) -> Self {
+        self.addresses.push(address);
+        self
+    }
+}
+
+/// How the leftover base coin and native tokens are split into remainder outputs.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum RemainderSplit {
+    /// A single output holds all leftovers.
+    #[default]
+    Single,
+    /// An output for every native token. If there are more native tokens than outputs can be added, the native tokens
+    /// are spread over the possible outputs. The base coin that isn't needed for the storage deposits is added to the
+    /// first output.
+    PerNativeToken,
+    /// Outputs with the given base coin amounts, the largest ones first, as many as the leftovers allow. Amounts below
+    /// the storage deposit of an output are ignored. The native tokens are kept in a separate output and the base coin
+    /// that doesn't match a denomination gets its own output, if it covers the storage deposit, otherwise it's added
+    /// to the first output.
+    Denominations(Vec<u64>),
+}
+
 // Get possible required storage deposit return outputs, if there is already an output for the storage deposit return,
 // then don't return a new output for that.
 pub(crate) fn get_storage_deposit_return_outputs<'a>(
@@ -77,18 +128,21 @@ pub(crate) fn get_storage_deposit_return_outputs<'a>(
     Ok(new_sdr_outputs)
 }
 
-// Get a remainder with amount and native tokens if necessary, if no remainder_address is provided it will be selected
-// from the inputs, also validates the amounts
-pub(crate) fn get_remainder_output<'a>(
+// Get the remainder outputs with amount and native tokens if necessary, split according to the remainder policy. If no
+// remainder_address is provided it will be selected from the inputs, also validates the amounts
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn get_remainder_outputs<'a>(
     inputs: impl Iterator<Item = &'a InputSigningData> + Clone,
     outputs: impl Iterator<Item = &'a Output> + Clone,
     remainder_address: Option<Address>,
+    remainder_policy: &RemainderPolicy,
     rent_structure: &RentStructure,
-    allow_burning: bool,
+    burn: Option<&Burn>,
     current_time: u32,
     token_supply: u64,
-) -> Result<Option<RemainderData>> {
+) -> Result<Vec<RemainderData>> {
     log::debug!("[get_remainder]");
+    let max_remainder_outputs = remainder_output_limit(outputs.clone().count());
     let input_outputs = inputs.clone().map(|i| &i.output);
     let input_data = get_accumulated_output_amounts(&std::iter::empty(), input_outputs.clone())?;
     let output_data = get_accumulated_output_amounts(&std::iter::empty(), outputs.clone())?;
@@ -112,39 +166,185 @@ pub(crate) fn get_remainder_output<'a>(
     let mut output_native_tokens = output_data.native_tokens;
     // add melted native tokens as outputs, because we need to have this amount in the inputs
     output_native_tokens.merge(melted_native_tokens)?;
+    // add burned native tokens as outputs, so they aren't part of the remainder
+    if let Some(burn) = burn {
+        output_native_tokens.merge(burn.native_tokens_builder()?)?;
+    }
+
+    let native_token_remainder = get_remainder_native_token_list(&input_native_tokens, &output_native_tokens)?;
+    if remainder_amount == 0 {
+        // if we have remaining native tokens, but no amount left, then we can't create this transaction, because they
+        // aren't listed in the burn
+        if !native_token_remainder.is_empty() {
+            return Err(Error::NoBalanceForNativeTokenRemainder);
+        }
+        return Ok(Vec::new());
+    }
 
-    let native_token_remainder = get_remainder_native_tokens(&input_native_tokens, &output_native_tokens)?;
     // Output possible remaining tokens back to the original address
-    let remainder_data = if remainder_amount > 0 {
-        let (remainder_addr, address_chain) = match remainder_address {
-            // For provided remainder addresses we can't get the Chain
-            Some(a) => (a, None),
-            None => get_remainder_address(inputs, current_time)?,
-        };
-
-        let mut remainder_output_builder = BasicOutputBuilder::new_with_amount(remainder_amount)?
-            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(remainder_addr)));
-        if let Some(remainder_native_tokens) = native_token_remainder {
-            remainder_output_builder = remainder_output_builder.with_native_tokens(remainder_native_tokens);
+    let addresses = get_remainder_addresses(remainder_policy, remainder_address, inputs, current_time)?;
+    let address = |index: usize| &addresses[index % addresses.len()];
+
+    // Every remainder output gets its storage deposit first
+    let mut remainders = Vec::new();
+    for native_tokens in
+        split_remainder_native_tokens(&remainder_policy.split, native_token_remainder, max_remainder_outputs)?
+    {
+        // With denominations, an output without native tokens is only created for the base coin that's left
+        if native_tokens.is_none() && matches!(remainder_policy.split, RemainderSplit::Denominations(_)) {
+            continue;
         }
-        let remainder = remainder_output_builder.finish_output(token_supply)?;
-        // Check if output has enough amount to cover the storage deposit
-        remainder.verify_storage_deposit(rent_structure.clone(), token_supply)?;
-        Some(RemainderData {
-            output: remainder,
-            chain: address_chain,
-            address: remainder_addr,
-        })
-    } else {
-        // if we have remaining native tokens, but no amount left, then we can't create this transaction unless we want
-        // to burn them
-        if native_token_remainder.is_some() && !allow_burning {
-            return Err(Error::NoBalanceForNativeTokenRemainder);
+        let storage_deposit = minimum_storage_deposit_basic_output(
+            rent_structure,
+            &address(remainders.len()).0,
+            &native_tokens,
+            token_supply,
+        )?;
+        remainders.push((storage_deposit, native_tokens));
+    }
+
+    let storage_deposits = remainders.iter().map(|(amount, _)| amount).sum::<u64>();
+    let mut rest = match remainder_amount.checked_sub(storage_deposits) {
+        Some(rest) => rest,
+        None => {
+            // The storage deposit check of the first output fails below
+            remainders[0].0 = remainders[0].0.saturating_sub(storage_deposits - remainder_amount);
+            0
         }
-        None
     };
 
-    Ok(remainder_data)
+    let mut separate_rest = remainders.is_empty();
+    if let RemainderSplit::Denominations(denominations) = &remainder_policy.split {
+        let mut denominations = denominations.clone();
+        denominations.sort_unstable_by_key(|denomination| Reverse(*denomination));
+
+        for denomination in denominations {
+            while rest >= denomination
+                && remainders.len() < max_remainder_outputs
+                && denomination
+                    >= minimum_storage_deposit_basic_output(
+                        rent_structure,
+                        &address(remainders.len()).0,
+                        &None,
+                        token_supply,
+                    )?
+            {
+                remainders.push((denomination, None));
+                rest -= denomination;
+            }
+        }
+
+        separate_rest = remainders.is_empty()
+            || (remainders.len() < max_remainder_outputs
+                && rest
+                    >= minimum_storage_deposit_basic_output(
+                        rent_structure,
+                        &address(remainders.len()).0,
+                        &None,
+                        token_supply,
+                    )?);
+    }
+    if rest > 0 {
+        if separate_rest {
+            remainders.push((rest, None));
+        } else {
+            remainders[0].0 += rest;
+        }
+    }
+
+    remainders
+        .into_iter()
+        .enumerate()
+        .map(|(index, (amount, native_tokens))| {
+            let (remainder_addr, address_chain) = address(index).clone();
+            let mut remainder_output_builder = BasicOutputBuilder::new_with_amount(amount)?
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(remainder_addr)));
+            if let Some(native_tokens) = native_tokens {
+                remainder_output_builder = remainder_output_builder.with_native_tokens(native_tokens);
+            }
+            let remainder = remainder_output_builder.finish_output(token_supply)?;
+            // Check if output has enough amount to cover the storage deposit
+            remainder.verify_storage_deposit(rent_structure.clone(), token_supply)?;
+
+            Ok(RemainderData {
+                output: remainder,
+                chain: address_chain,
+                address: remainder_addr,
+            })
+        })
+        .collect()
+}
+
+// The number of remainder outputs that can be added to the other outputs
+pub(crate) fn remainder_output_limit(output_count: usize) -> usize {
+    (OUTPUT_COUNT_MAX as usize).saturating_sub(output_count).max(1)
+}
+
+// Get the addresses the remainder outputs are sent to in turn, with the Chain if the address is from the inputs
+pub(crate) fn get_remainder_addresses<'a>(
+    remainder_policy: &RemainderPolicy,
+    remainder_address: Option<Address>,
+    inputs: impl Iterator<Item = &'a InputSigningData>,
+    current_time: u32,
+) -> Result<Vec<(Address, Option<Chain>)>> {
+    // For provided remainder addresses we can't get the Chain
+    if !remainder_policy.addresses.is_empty() {
+        return Ok(remainder_policy
+            .addresses
+            .iter()
+            .map(|address| (*address, None))
+            .collect());
+    }
+
+    Ok(vec![match remainder_address {
+        Some(a) => (a, None),
+        None => get_remainder_address(inputs, current_time)?,
+    }])
+}
+
+// Split the remaining native tokens into the native tokens of the remainder outputs, an output without native tokens
+// if there are none
+fn split_remainder_native_tokens(
+    split: &RemainderSplit,
+    native_tokens: Vec<NativeToken>,
+    max_remainder_outputs: usize,
+) -> Result<Vec<Option<NativeTokens>>> {
+    if native_tokens.is_empty() {
+        return Ok(vec![None]);
+    }
+
+    let chunk_size = match split {
+        RemainderSplit::PerNativeToken => native_tokens.len().div_ceil(max_remainder_outputs),
+        RemainderSplit::Single | RemainderSplit::Denominations(_) => native_tokens.len(),
+    };
+
+    native_tokens
+        .chunks(chunk_size)
+        .map(|native_tokens| Ok(Some(NativeTokens::new(native_tokens.to_vec())?)))
+        .collect()
+}
+
+// Get the minimum storage deposit of the remainder outputs for the remaining native tokens
+pub(crate) fn minimum_remainder_storage_deposit(
+    remainder_policy: &RemainderPolicy,
+    addresses: &[(Address, Option<Chain>)],
+    native_tokens: Vec<NativeToken>,
+    max_remainder_outputs: usize,
+    rent_structure: &RentStructure,
+    token_supply: u64,
+) -> Result<u64> {
+    split_remainder_native_tokens(&remainder_policy.split, native_tokens, max_remainder_outputs)?
+        .iter()
+        .enumerate()
+        .map(|(index, native_tokens)| {
+            minimum_storage_deposit_basic_output(
+                rent_structure,
+                &addresses[index % addresses.len()].0,
+                native_tokens,
+                token_supply,
+            )
+        })
+        .sum()
 }
 
 // Get an Ed25519 address from the inputs as remainder address
@@ -181,10 +381,12 @@ pub(crate) fn get_remainder_address<'a>(
     Err(Error::MissingInputWithEd25519Address)
 }
 
-// Get additional required storage deposit amount for the remainder output
+// Get additional required storage deposit amount for the remainder outputs
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn get_additional_required_remainder_amount(
     remainder_address: Option<Address>,
+    remainder_policy: &RemainderPolicy,
+    max_remainder_outputs: usize,
     selected_inputs: &[InputSigningData],
     selected_input_amount: u64,
     selected_input_native_tokens: &NativeTokensBuilder,
@@ -193,49 +395,30 @@ pub(crate) fn get_additional_required_remainder_amount(
     current_time: u32,
     token_supply: u64,
 ) -> crate::Result<u64> {
-    let additional_required_remainder_amount = {
-        if selected_input_amount > required_accumulated_amounts.amount {
-            let current_remainder_amount = selected_input_amount - required_accumulated_amounts.amount;
-            let native_token_remainder = get_remainder_native_tokens(
-                selected_input_native_tokens,
-                &required_accumulated_amounts.native_tokens,
-            )?;
-
-            let required_deposit = minimum_storage_deposit_basic_output(
-                rent_structure,
-                &match remainder_address {
-                    Some(a) => a,
-                    None => get_remainder_address(selected_inputs.iter(), current_time)?.0,
-                },
-                &native_token_remainder,
-                token_supply,
-            )?;
-            if required_deposit > current_remainder_amount {
-                required_deposit - current_remainder_amount
-            } else {
-                0
-            }
-        } else {
-            // Not more amount than required, but maybe still native tokens left
-            let native_token_remainder = get_remainder_native_tokens(
-                selected_input_native_tokens,
-                &required_accumulated_amounts.native_tokens,
-            )?;
-
-            if let Some(native_token_remainder) = native_token_remainder {
-                minimum_storage_deposit_basic_output(
-                    rent_structure,
-                    &match remainder_address {
-                        Some(a) => a,
-                        None => get_remainder_address(selected_inputs.iter(), current_time)?.0,
-                    },
-                    &Some(native_token_remainder),
-                    token_supply,
-                )?
-            } else {
-                0
-            }
-        }
-    };
-    Ok(additional_required_remainder_amount)
+    let current_remainder_amount = selected_input_amount.saturating_sub(required_accumulated_amounts.amount);
+    let native_token_remainder = get_remainder_native_token_list(
+        selected_input_native_tokens,
+        &required_accumulated_amounts.native_tokens,
+    )?;
+
+    // Not more amount than required and no native tokens left, so there is no remainder
+    if current_remainder_amount == 0 && native_token_remainder.is_empty() {
+        return Ok(0);
+    }
+
+    let required_deposit = minimum_remainder_storage_deposit(
+        remainder_policy,
+        &get_remainder_addresses(
+            remainder_policy,
+            remainder_address,
+            selected_inputs.iter(),
+            current_time,
+        )?,
+        native_token_remainder,
+        max_remainder_outputs,
+        rent_structure,
+        token_supply,
+    )?;
+
+    Ok(required_deposit.saturating_sub(current_remainder_amount))
 }
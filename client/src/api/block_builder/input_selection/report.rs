@@ -0,0 +1,335 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Report of the decisions of the input selection, to find out why inputs were selected or why a selection failed
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use iota_types::block::{
+    address::Address,
+    output::{NativeToken, NativeTokensBuilder, Output, OutputId, RentStructure, TokenId},
+};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    burn::Burn,
+    finish_selection, get_accumulated_output_amounts, get_minted_and_melted_native_tokens,
+    helpers::sdr_not_expired,
+    remainder::{get_remainder_addresses, minimum_remainder_storage_deposit, remainder_output_limit, RemainderPolicy},
+    select_inputs,
+    types::InputSelectionOptions,
+};
+use crate::{secret::types::InputSigningData, Error, Result};
+
+/// Why a candidate was selected as input.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SelectionReason {
+    /// The candidate was provided as mandatory input.
+    Mandatory,
+    /// The candidate unlocks the address of a sender or issuer feature of the outputs.
+    SenderOrIssuer,
+    /// The candidate is an alias, foundry or NFT output that's required by the outputs or to unlock other inputs.
+    ChainRequirement,
+    /// The candidate has native tokens that are required by the outputs or burned.
+    NativeTokens,
+    /// The candidate is required for the base coin amount of the outputs.
+    Amount,
+    /// The candidate is required for the storage deposit of the remainder.
+    RemainderStorageDeposit,
+}
+
+/// Why a candidate wasn't selected as input.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// A timelock prevents unlocking the output at the current time.
+    Timelocked,
+    /// The expiration passed, so only the return address can unlock the output.
+    Expired,
+    /// The Bech32 address of the candidate is invalid, so it's unknown if it can unlock the output.
+    InvalidAddress,
+    /// The output wasn't required for the outputs, or the selection failed before it was considered.
+    NotRequired,
+}
+
+/// The decision of the input selection for a candidate.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", content = "reason", rename_all = "camelCase")]
+pub enum CandidateDecision {
+    /// The candidate was selected as input.
+    Selected(SelectionReason),
+    /// The candidate wasn't selected as input.
+    Skipped(SkipReason),
+}
+
+/// A candidate input and the decision of the input selection for it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateReport {
+    /// The output ID of the candidate.
+    pub output_id: OutputId,
+    /// The base coin amount of the candidate.
+    pub amount: u64,
+    /// The amount of a storage deposit return that isn't expired, it has to be sent back when the candidate is
+    /// selected.
+    pub storage_deposit_return: Option<u64>,
+    /// Whether and why the candidate was selected.
+    pub decision: CandidateDecision,
+}
+
+/// The base coin amounts of the selected inputs and the outputs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseCoinBalanceSheet {
+    /// The amount of the selected inputs.
+    pub inputs: u64,
+    /// The amount of the outputs, including storage deposit returns but without the remainder.
+    pub outputs: u64,
+    /// The amount left for the remainder.
+    pub remainder: u64,
+    /// The amount that the selected inputs are missing for the outputs.
+    pub missing: u64,
+}
+
+/// The amounts of a native token in the selected inputs and the outputs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeTokenBalanceSheet {
+    /// The amount of the selected inputs.
+    pub inputs: U256,
+    /// The amount minted by foundries.
+    pub minted: U256,
+    /// The amount of the outputs, without the remainder.
+    pub outputs: U256,
+    /// The amount melted by foundries.
+    pub melted: U256,
+    /// The amount listed in the burn.
+    pub burned: U256,
+    /// The amount left for the remainder.
+    pub remainder: U256,
+    /// The amount that the selected inputs are missing for the outputs, the melting and the burn.
+    pub missing: U256,
+}
+
+/// The balance of the selected inputs and the outputs, for each asset.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceSheet {
+    /// The balance of the base coin.
+    pub base_coin: BaseCoinBalanceSheet,
+    /// The balances of the native tokens.
+    pub native_tokens: BTreeMap<TokenId, NativeTokenBalanceSheet>,
+}
+
+impl BalanceSheet {
+    fn new(inputs: &[InputSigningData], outputs: &[Output], burn: Option<&Burn>) -> Result<Self> {
+        let input_outputs = inputs.iter().map(|input| &input.output);
+        let input_data = get_accumulated_output_amounts(&std::iter::empty(), input_outputs.clone())?;
+        let output_data = get_accumulated_output_amounts(&std::iter::empty(), outputs.iter())?;
+        let (minted_native_tokens, melted_native_tokens) =
+            get_minted_and_melted_native_tokens(&input_outputs, outputs.iter())?;
+
+        let base_coin = BaseCoinBalanceSheet {
+            inputs: input_data.amount,
+            outputs: output_data.amount,
+            remainder: input_data.amount.saturating_sub(output_data.amount),
+            missing: output_data.amount.saturating_sub(input_data.amount),
+        };
+
+        let burned_native_tokens = burn.map(|burn| &burn.native_tokens);
+        let token_ids = input_data
+            .native_tokens
+            .keys()
+            .chain(output_data.native_tokens.keys())
+            .chain(minted_native_tokens.keys())
+            .chain(melted_native_tokens.keys())
+            .chain(
+                burned_native_tokens
+                    .into_iter()
+                    .flat_map(|native_tokens| native_tokens.keys()),
+            )
+            .collect::<BTreeSet<_>>();
+
+        let native_tokens = token_ids
+            .into_iter()
+            .map(|token_id| {
+                let inputs = native_token_amount(&input_data.native_tokens, token_id);
+                let minted = native_token_amount(&minted_native_tokens, token_id);
+                let outputs = native_token_amount(&output_data.native_tokens, token_id);
+                let melted = native_token_amount(&melted_native_tokens, token_id);
+                let burned = burned_native_tokens
+                    .and_then(|native_tokens| native_tokens.get(token_id))
+                    .copied()
+                    .unwrap_or_default();
+                let available = inputs.saturating_add(minted);
+                let required = outputs.saturating_add(melted).saturating_add(burned);
+
+                (
+                    *token_id,
+                    NativeTokenBalanceSheet {
+                        inputs,
+                        minted,
+                        outputs,
+                        melted,
+                        burned,
+                        remainder: available.saturating_sub(required),
+                        missing: required.saturating_sub(available),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            base_coin,
+            native_tokens,
+        })
+    }
+}
+
+fn native_token_amount(native_tokens: &NativeTokensBuilder, token_id: &TokenId) -> U256 {
+    native_tokens.get(token_id).copied().unwrap_or_default()
+}
+
+/// The decisions of a dry run of the input selection.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputSelectionReport {
+    /// The mandatory and additional inputs, in this order, with the decision for each of them.
+    pub candidates: Vec<CandidateReport>,
+    /// The minimum storage deposit of the remainder outputs, if the selected inputs leave a remainder and a remainder
+    /// address is known.
+    pub remainder_storage_deposit: Option<u64>,
+    /// The balance of the selected inputs and the outputs.
+    pub balance_sheet: BalanceSheet,
+    /// The error the input selection fails with, if any.
+    pub error: Option<String>,
+}
+
+/// Runs the input selection like [`try_select_inputs()`](super::try_select_inputs), but instead of the selected
+/// transaction data it returns a report about the decision for each candidate, the storage deposit of the remainder
+/// and the balance sheet. A failing selection is reported with its error, so it can be used to find out why.
+#[allow(clippy::too_many_arguments)]
+pub fn dry_run_select_inputs(
+    mandatory_inputs: Vec<InputSigningData>,
+    additional_inputs: Vec<InputSigningData>,
+    mut outputs: Vec<Output>,
+    remainder_address: Option<Address>,
+    rent_structure: &RentStructure,
+    current_time: u32,
+    token_supply: u64,
+    options: InputSelectionOptions<'_>,
+) -> Result<InputSelectionReport> {
+    log::debug!("[dry_run_select_inputs]");
+
+    let mut candidate_output_ids = HashSet::new();
+    let candidates = mandatory_inputs
+        .iter()
+        .chain(additional_inputs.iter())
+        .filter(|input| candidate_output_ids.insert(*input.output_id()))
+        .map(|input| {
+            (
+                *input.output_id(),
+                input.output.amount(),
+                sdr_not_expired(&input.output, current_time).map(|sdr| sdr.amount()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut selected_inputs = Vec::new();
+    let mut decisions = HashMap::new();
+    let remainder_data = select_inputs(
+        mandatory_inputs,
+        additional_inputs,
+        &mut selected_inputs,
+        &mut outputs,
+        remainder_address,
+        rent_structure,
+        current_time,
+        token_supply,
+        options,
+        &mut decisions,
+    );
+
+    let balance_sheet = BalanceSheet::new(&selected_inputs, &outputs, options.burn)?;
+    let remainder_storage_deposit = remainder_storage_deposit(
+        &balance_sheet,
+        &selected_inputs,
+        outputs.len(),
+        remainder_address,
+        options.remainder_policy,
+        rent_structure,
+        current_time,
+        token_supply,
+    )?;
+    let error = remainder_data
+        .and_then(|remainder_data| finish_selection(selected_inputs, outputs, remainder_data, options.burn))
+        .err()
+        .map(|e| e.to_string());
+
+    let candidates = candidates
+        .into_iter()
+        .map(|(output_id, amount, storage_deposit_return)| CandidateReport {
+            output_id,
+            amount,
+            storage_deposit_return,
+            decision: decisions
+                .get(&output_id)
+                .copied()
+                .unwrap_or(CandidateDecision::Skipped(SkipReason::NotRequired)),
+        })
+        .collect();
+
+    Ok(InputSelectionReport {
+        candidates,
+        remainder_storage_deposit,
+        balance_sheet,
+        error,
+    })
+}
+
+// The minimum storage deposit of the remainder outputs with the remaining native tokens, if there is a remainder
+#[allow(clippy::too_many_arguments)]
+fn remainder_storage_deposit(
+    balance_sheet: &BalanceSheet,
+    selected_inputs: &[InputSigningData],
+    output_count: usize,
+    remainder_address: Option<Address>,
+    remainder_policy: Option<&RemainderPolicy>,
+    rent_structure: &RentStructure,
+    current_time: u32,
+    token_supply: u64,
+) -> Result<Option<u64>> {
+    let remainder_native_tokens = balance_sheet
+        .native_tokens
+        .iter()
+        .filter(|(_, native_token_balance_sheet)| !native_token_balance_sheet.remainder.is_zero())
+        .map(|(token_id, native_token_balance_sheet)| NativeToken::new(*token_id, native_token_balance_sheet.remainder))
+        .collect::<core::result::Result<Vec<_>, _>>()?;
+    if balance_sheet.base_coin.remainder == 0 && remainder_native_tokens.is_empty() {
+        return Ok(None);
+    }
+
+    let remainder_policy = remainder_policy.cloned().unwrap_or_default();
+    let remainder_addresses = match get_remainder_addresses(
+        &remainder_policy,
+        remainder_address,
+        selected_inputs.iter(),
+        current_time,
+    ) {
+        Ok(remainder_addresses) => remainder_addresses,
+        Err(Error::MissingInputWithEd25519Address) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    minimum_remainder_storage_deposit(
+        &remainder_policy,
+        &remainder_addresses,
+        remainder_native_tokens,
+        remainder_output_limit(output_count),
+        rent_structure,
+        token_supply,
+    )
+    .map(Some)
+}
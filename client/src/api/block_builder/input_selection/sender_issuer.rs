@@ -52,6 +52,10 @@ impl<'a> ClientBlockBuilder<'a> {
 
                     let mut found_output = false;
                     for output_response in address_outputs {
+                        // Skip outputs that are used by other prepared transactions
+                        if self.client.is_output_reserved(&output_response.metadata.output_id()?)? {
+                            continue;
+                        }
                         let output = Output::try_from_dto(&output_response.output, token_supply)?;
 
                         // We can ignore the unlocked_alias_or_nft_address, since we only requested basic outputs
@@ -97,6 +101,7 @@ impl<'a> ClientBlockBuilder<'a> {
                         }
                     }) {
                         let output_id = self.client.alias_output_id(*alias_id).await?;
+                        self.client.check_output_not_reserved(&output_id)?;
                         let output_response = self.client.get_output(&output_id).await?;
                         if let OutputDto::Alias(alias_output_dto) = &output_response.output {
                             let alias_output = AliasOutput::try_from_dto(alias_output_dto, token_supply)?;
@@ -154,6 +159,7 @@ impl<'a> ClientBlockBuilder<'a> {
                         }
                     }) {
                         let output_id = self.client.nft_output_id(*nft_id).await?;
+                        self.client.check_output_not_reserved(&output_id)?;
                         let output_response = self.client.get_output(&output_id).await?;
                         if let OutputDto::Nft(nft_output) = &output_response.output {
                             let nft_output = NftOutput::try_from_dto(nft_output, token_supply)?;
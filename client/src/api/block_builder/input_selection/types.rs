@@ -5,7 +5,24 @@
 
 use iota_types::block::output::{NativeTokensBuilder, Output};
 
-use crate::api::{block_builder::input_selection::InputSigningData, RemainderData};
+use crate::api::{
+    block_builder::input_selection::{
+        burn::Burn, coin_selection::CoinSelectionStrategy, remainder::RemainderPolicy, InputSigningData,
+    },
+    RemainderData,
+};
+
+/// Options for the input selection, the defaults burn nothing, select the additional inputs in the provided order and
+/// create a single remainder output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InputSelectionOptions<'a> {
+    /// Aliases, foundries, nfts and native tokens to burn, nothing else is burned.
+    pub burn: Option<&'a Burn>,
+    /// Decides in which order additional basic outputs are selected.
+    pub coin_selection_strategy: Option<&'a dyn CoinSelectionStrategy>,
+    /// Decides how the leftovers are split into remainder outputs.
+    pub remainder_policy: Option<&'a RemainderPolicy>,
+}
 
 /// Transaction data with selected inputs, input data for signing and outputs, with remainder output if required
 #[derive(Debug, Clone)]
@@ -16,6 +33,8 @@ pub struct SelectedTransactionData {
     pub outputs: Vec<Output>,
     /// Optional remainder output, also already parts of the outputs
     pub remainder: Option<RemainderData>,
+    /// Further remainder outputs if the remainder policy split the remainder, also already parts of the outputs
+    pub additional_remainders: Vec<RemainderData>,
 }
 
 /// Required things from the to be created outputs
@@ -91,6 +91,9 @@ impl<'a> ClientBlockBuilder<'a> {
 
         let mut utxo_chain_inputs = Vec::new();
         for (unlock_address, output_response) in utxo_chains {
+            // A chain has a single current output, so it can't be skipped if another prepared transaction uses it
+            client.check_output_not_reserved(&output_response.metadata.output_id()?)?;
+
             let address_index_internal = match self.secret_manager {
                 Some(secret_manager) => {
                     match unlock_address {
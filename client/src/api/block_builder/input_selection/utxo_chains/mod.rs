@@ -19,7 +19,10 @@ use iota_types::{
 
 use super::types::AccumulatedOutputAmounts;
 use crate::{
-    api::input_selection::{get_accumulated_output_amounts, sdr_not_expired},
+    api::input_selection::{
+        burn::{is_burned, Burn},
+        get_accumulated_output_amounts, sdr_not_expired,
+    },
     secret::types::InputSigningData,
     Client, Result,
 };
@@ -28,7 +31,7 @@ mod validation;
 pub(crate) use validation::check_utxo_chain_inputs;
 
 // Select required alias, nft and foundry outputs. When the amount of alias or nft outputs is > the minimum required
-// storage deposit and burning is not allowed, they will be added in the input and also to the outputs, but there just
+// storage deposit and they aren't burned, they will be added in the input and also to the outputs, but there just
 // with the minimum required storage deposit as amount, so the difference gets available. Sender features will be
 // removed.
 #[allow(clippy::too_many_arguments)]
@@ -40,7 +43,7 @@ pub(crate) fn select_utxo_chain_inputs(
     outputs: &mut Vec<Output>,
     required: &mut AccumulatedOutputAmounts,
     utxo_chain_inputs: &mut [InputSigningData],
-    allow_burning: bool,
+    burn: Option<&Burn>,
     current_time: u32,
     rent_structure: &RentStructure,
     token_supply: u64,
@@ -54,7 +57,7 @@ pub(crate) fn select_utxo_chain_inputs(
     // Add existing selected inputs we added for sender and issuer features before
     for input_signing_data in selected_inputs.iter() {
         // Add inputs to outputs if not already there, so they don't get burned
-        if !allow_burning {
+        if !is_burned(burn, &input_signing_data.output, input_signing_data.output_id()) {
             add_output_for_input(input_signing_data, rent_structure, outputs, token_supply)?;
         }
         added_output_for_input_signing_data.insert(*input_signing_data.output_id());
@@ -76,6 +79,7 @@ pub(crate) fn select_utxo_chain_inputs(
             }
 
             let minimum_required_storage_deposit = input_signing_data.output.rent_cost(rent_structure);
+            let burned = is_burned(burn, &input_signing_data.output, &output_id);
 
             // Since we can have two different types of transitions, we track which address is required
             let mut alias_transition_unlock_address = None;
@@ -93,7 +97,7 @@ pub(crate) fn select_utxo_chain_inputs(
                     });
                     let is_required_for_input =
                         required_alias_nft_addresses.contains(&Address::Nft(NftAddress::new(nft_id)));
-                    if !is_required_for_output && !allow_burning || is_required_for_input {
+                    if !is_required_for_output && !burned || is_required_for_input {
                         let nft_address = Address::Nft(NftAddress::new(nft_id));
                         let nft_required_in_unlock_condition = outputs.iter().any(|output| {
                             if let Ok((required_unlock_address, unlocked_alias_or_nft_address)) = output
@@ -169,7 +173,7 @@ pub(crate) fn select_utxo_chain_inputs(
                     let is_required_for_input =
                         required_alias_nft_addresses.contains(&Address::Alias(AliasAddress::new(alias_id)));
 
-                    if !is_required_for_output && !allow_burning || is_required_for_input {
+                    if !is_required_for_output && !burned || is_required_for_input {
                         let alias_address = Address::Alias(AliasAddress::new(alias_id));
                         let alias_required_in_unlock_condition = outputs.iter().any(|output| {
                             // check if alias address is in unlock condition
@@ -235,7 +239,7 @@ pub(crate) fn select_utxo_chain_inputs(
                         } else {
                             false
                         }
-                    }) && !allow_burning
+                    }) && !burned
                     {
                         // Don't add if it doesn't give us any amount or native tokens
                         if input_signing_data.output.amount() == minimum_required_storage_deposit
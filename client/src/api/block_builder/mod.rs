@@ -5,7 +5,7 @@ pub mod input_selection;
 pub mod pow;
 pub mod transaction;
 
-use std::{collections::HashSet, ops::Range};
+use std::{collections::HashSet, ops::Range, sync::Arc};
 
 use iota_types::block::{
     address::{Address, Ed25519Address},
@@ -24,6 +24,12 @@ use packable::{
 };
 
 pub use self::transaction::verify_semantic;
+use self::input_selection::{
+    burn::Burn,
+    coin_selection::{CoinSelection, CoinSelectionStrategy},
+    remainder::RemainderPolicy,
+    types::InputSelectionOptions,
+};
 use crate::{api::do_pow, constants::SHIMMER_COIN_TYPE, secret::SecretManager, Client, Error, Result};
 
 /// Builder of the block API
@@ -41,7 +47,9 @@ pub struct ClientBlockBuilder<'a> {
     tag: Option<Vec<u8>>,
     data: Option<Vec<u8>>,
     parents: Option<Vec<BlockId>>,
-    allow_burning: bool,
+    burn: Option<Burn>,
+    coin_selection_strategy: Option<Arc<dyn CoinSelectionStrategy>>,
+    remainder_policy: Option<RemainderPolicy>,
 }
 
 /// Block output address
@@ -83,8 +91,12 @@ pub struct ClientBlockBuilderOptions {
     pub data: Option<String>,
     /// Parents
     pub parents: Option<Vec<BlockId>>,
-    /// Allow burning of native tokens
-    pub allow_burning: Option<bool>,
+    /// Aliases, foundries, NFTs and native tokens to burn
+    pub burn: Option<Burn>,
+    /// Coin selection strategy
+    pub coin_selection: Option<CoinSelection>,
+    /// Remainder policy
+    pub remainder_policy: Option<RemainderPolicy>,
 }
 
 impl<'a> ClientBlockBuilder<'a> {
@@ -103,16 +115,39 @@ impl<'a> ClientBlockBuilder<'a> {
             tag: None,
             data: None,
             parents: None,
-            allow_burning: false,
+            burn: None,
+            coin_selection_strategy: None,
+            remainder_policy: None,
         }
     }
 
-    /// Allow burning of native tokens when custom inputs are provided.
-    pub fn with_burning_allowed(mut self, allow_burning: bool) -> Self {
-        self.allow_burning = allow_burning;
+    /// Sets the aliases, foundries, NFTs and native tokens to burn, nothing else will be burned.
+    pub fn with_burn(mut self, burn: Burn) -> Self {
+        self.burn.replace(burn);
+        self
+    }
+
+    /// Sets the strategy that decides which basic outputs are selected as inputs during automatic input selection.
+    pub fn with_coin_selection_strategy(mut self, strategy: impl CoinSelectionStrategy + 'static) -> Self {
+        self.coin_selection_strategy.replace(Arc::new(strategy));
+        self
+    }
+
+    /// Sets how the leftovers of a transaction are split into remainder outputs.
+    pub fn with_remainder_policy(mut self, remainder_policy: RemainderPolicy) -> Self {
+        self.remainder_policy.replace(remainder_policy);
         self
     }
 
+    // The burn, coin selection strategy and remainder policy of the builder for the input selection
+    pub(crate) fn input_selection_options(&self) -> InputSelectionOptions<'_> {
+        InputSelectionOptions {
+            burn: self.burn.as_ref(),
+            coin_selection_strategy: self.coin_selection_strategy.as_deref(),
+            remainder_policy: self.remainder_policy.as_ref(),
+        }
+    }
+
     /// Sets the seed.
     pub fn with_secret_manager(mut self, manager: &'a SecretManager) -> Self {
         self.secret_manager.replace(manager);
@@ -307,8 +342,14 @@ impl<'a> ClientBlockBuilder<'a> {
         if let Some(parents) = options.parents {
             self = self.with_parents(parents)?;
         }
-        if let Some(allow_burning) = options.allow_burning {
-            self = self.with_burning_allowed(allow_burning);
+        if let Some(burn) = options.burn {
+            self = self.with_burn(burn);
+        }
+        if let Some(coin_selection) = options.coin_selection {
+            self = self.with_coin_selection_strategy(coin_selection);
+        }
+        if let Some(remainder_policy) = options.remainder_policy {
+            self = self.with_remainder_policy(remainder_policy);
         }
 
         Ok(self)
@@ -19,7 +19,7 @@ use iota_types::block::{
 use packable::PackableExt;
 
 use crate::{
-    api::{types::PreparedTransactionData, ClientBlockBuilder},
+    api::{input_selection::types::SelectedTransactionData, types::PreparedTransactionData, ClientBlockBuilder},
     secret::{types::InputSigningData, SecretManageExt},
     Error, Result,
 };
@@ -61,13 +61,52 @@ impl<'a> ClientBlockBuilder<'a> {
         }
 
         // Input selection
-        let selected_transaction_data = if self.inputs.is_some() {
-            self.get_custom_inputs(governance_transition, &rent_structure, self.allow_burning)
-                .await?
-        } else {
-            self.get_inputs(&rent_structure).await?
+        let mut attempts = 0;
+        let (selected_transaction_data, output_ids) = loop {
+            attempts += 1;
+            let selected_transaction_data = if self.inputs.is_some() {
+                self.get_custom_inputs(governance_transition.clone(), &rent_structure, self.burn.as_ref())
+                    .await?
+            } else {
+                self.get_inputs(&rent_structure).await?
+            };
+
+            // Reserve the inputs, so concurrent transaction builds skip them. If a concurrent build reserved one of
+            // them in the meantime, the automatic input selection is done once more without it.
+            let output_ids = selected_transaction_data
+                .inputs
+                .iter()
+                .map(|input| *input.output_id())
+                .collect::<Vec<_>>();
+            if self.client.reserve_outputs(&output_ids)? {
+                break (selected_transaction_data, output_ids);
+            }
+            if self.inputs.is_some() || attempts > 1 {
+                let mut reserved_output_id = output_ids[0];
+                for output_id in &output_ids {
+                    if self.client.is_output_reserved(output_id)? {
+                        reserved_output_id = *output_id;
+                        break;
+                    }
+                }
+                return Err(Error::OutputReserved(reserved_output_id));
+            }
         };
 
+        let prepared_transaction_data = self.build_transaction_essence(selected_transaction_data).await;
+        // Release the inputs again if the transaction couldn't be prepared
+        if prepared_transaction_data.is_err() {
+            self.client.release_outputs(&output_ids)?;
+        }
+
+        prepared_transaction_data
+    }
+
+    // Build the transaction essence with the selected inputs and outputs
+    async fn build_transaction_essence(
+        &self,
+        selected_transaction_data: SelectedTransactionData,
+    ) -> Result<PreparedTransactionData> {
         // Build transaction payload
         let inputs_commitment = InputsCommitment::new(selected_transaction_data.inputs.iter().map(|i| &i.output));
 
@@ -102,6 +141,7 @@ impl<'a> ClientBlockBuilder<'a> {
             essence,
             inputs_data: selected_transaction_data.inputs,
             remainder: selected_transaction_data.remainder,
+            additional_remainders: selected_transaction_data.additional_remainders,
         })
     }
 
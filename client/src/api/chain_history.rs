@@ -0,0 +1,141 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use iota_types::{
+    api::response::OutputWithMetadataResponse,
+    block::{
+        input::Input,
+        output::{ChainId, Output, OutputId},
+        payload::{
+            transaction::{TransactionEssence, TransactionId},
+            Payload,
+        },
+    },
+};
+
+use crate::{Client, Error, Result};
+
+/// An output of an alias, foundry or NFT chain and the transition that created it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainTransition {
+    /// The ID of the output.
+    pub output_id: OutputId,
+    /// The output with its metadata.
+    pub output: OutputWithMetadataResponse,
+    /// The ID of the previous output of the chain, `None` if the transaction created the chain.
+    pub previous_output_id: Option<OutputId>,
+    /// The transaction that created the output.
+    pub transaction_id: TransactionId,
+    /// The index of the milestone that booked the output.
+    pub milestone_index: u32,
+    /// The timestamp of the milestone that booked the output.
+    pub milestone_timestamp: u32,
+    /// The state index, for alias outputs.
+    pub state_index: Option<u32>,
+    /// Whether the state metadata of an alias or the metadata feature of a foundry or NFT changed with the transition.
+    pub metadata_changed: bool,
+}
+
+impl Client {
+    /// Returns all outputs of an alias, foundry or NFT chain, from the one that created the chain to the current one.
+    /// The chain is walked backwards through the inputs of the transactions that created the outputs, so spent outputs
+    /// that were pruned by the nodes are requested from the permanodes.
+    pub async fn get_chain_history(&self, chain_id: ChainId) -> Result<Vec<ChainTransition>> {
+        let mut output_id = match chain_id {
+            ChainId::Alias(alias_id) => self.alias_output_id(alias_id).await?,
+            ChainId::Foundry(foundry_id) => self.foundry_output_id(foundry_id).await?,
+            ChainId::Nft(nft_id) => self.nft_output_id(nft_id).await?,
+        };
+        let token_supply = self.get_token_supply().await?;
+
+        // Outputs of the chain, from the current one to the first one
+        let mut chain = Vec::new();
+        loop {
+            let response = self.get_output(&output_id).await?;
+            let output = Output::try_from_dto(&response.output, token_supply)?;
+            let previous_output_id = self.previous_chain_output_id(chain_id, output_id.transaction_id()).await?;
+
+            chain.push((output_id, response, output, previous_output_id));
+            match previous_output_id {
+                Some(previous_output_id) => output_id = previous_output_id,
+                None => break,
+            }
+        }
+
+        let mut transitions = Vec::with_capacity(chain.len());
+        let mut previous_metadata = None;
+        for (output_id, response, output, previous_output_id) in chain.into_iter().rev() {
+            let metadata = chain_metadata(&output);
+            let metadata_changed = match &previous_metadata {
+                Some(previous_metadata) => previous_metadata != &metadata,
+                None => !metadata.is_empty(),
+            };
+
+            transitions.push(ChainTransition {
+                output_id,
+                previous_output_id,
+                transaction_id: *output_id.transaction_id(),
+                milestone_index: response.metadata.milestone_index_booked,
+                milestone_timestamp: response.metadata.milestone_timestamp_booked,
+                state_index: match &output {
+                    Output::Alias(alias_output) => Some(alias_output.state_index()),
+                    _ => None,
+                },
+                metadata_changed,
+                output: response,
+            });
+            previous_metadata.replace(metadata);
+        }
+
+        Ok(transitions)
+    }
+
+    // Returns the ID of the output of the chain that was consumed by the transaction, `None` if the transaction
+    // created the chain.
+    async fn previous_chain_output_id(
+        &self,
+        chain_id: ChainId,
+        transaction_id: &TransactionId,
+    ) -> Result<Option<OutputId>> {
+        let block = self.get_included_block(transaction_id).await?;
+        let input_ids = match block.payload() {
+            Some(Payload::Transaction(transaction_payload)) => match transaction_payload.essence() {
+                TransactionEssence::Regular(essence) => essence
+                    .inputs()
+                    .iter()
+                    .filter_map(|input| match input {
+                        Input::Utxo(input) => Some(*input.output_id()),
+                        Input::Treasury(_) => None,
+                    })
+                    .collect::<Vec<_>>(),
+            },
+            _ => return Err(Error::UnexpectedApiResponse),
+        };
+        let inputs = self.get_outputs(input_ids.clone()).await?;
+        let token_supply = self.get_token_supply().await?;
+
+        for (input_id, input) in input_ids.into_iter().zip(inputs) {
+            let input_chain_id = Output::try_from_dto(&input.output, token_supply)?
+                .chain_id()
+                .map(|input_chain_id| input_chain_id.or_from_output_id(&input_id));
+            if input_chain_id == Some(chain_id) {
+                return Ok(Some(input_id));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+// The mutable metadata of a chain output.
+fn chain_metadata(output: &Output) -> Vec<u8> {
+    match output {
+        Output::Alias(alias_output) => alias_output.state_metadata().to_vec(),
+        _ => output
+            .features()
+            .and_then(|features| features.metadata())
+            .map(|metadata| metadata.data().to_vec())
+            .unwrap_or_default(),
+    }
+}
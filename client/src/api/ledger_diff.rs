@@ -0,0 +1,80 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{ops::RangeInclusive, str::FromStr};
+
+use futures::{Stream, StreamExt};
+use iota_types::{
+    api::response::{OutputWithMetadataResponse, UtxoChangesResponse},
+    block::{output::OutputId, payload::milestone::MilestoneId},
+};
+
+use crate::{Client, Error, Result};
+
+/// The changes of the ledger by a milestone.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerDiff {
+    /// The index of the milestone.
+    pub milestone_index: u32,
+    /// The ID of the milestone.
+    pub milestone_id: MilestoneId,
+    /// The timestamp of the milestone.
+    pub milestone_timestamp: u32,
+    /// The outputs created by the milestone.
+    pub created_outputs: Vec<OutputWithMetadataResponse>,
+    /// The outputs spent by the milestone, with the metadata of when they were spent.
+    pub consumed_outputs: Vec<OutputWithMetadataResponse>,
+}
+
+impl Client {
+    /// Returns the ledger diffs of a range of milestone indexes, in the order of the indexes. Up to `concurrency`
+    /// milestones are requested at the same time. Milestones and outputs that were pruned by the nodes are requested
+    /// from the permanodes.
+    pub fn ledger_diffs(
+        &self,
+        milestone_indexes: RangeInclusive<u32>,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<LedgerDiff>> + '_ {
+        futures::stream::iter(milestone_indexes)
+            .map(move |index| self.get_ledger_diff(index))
+            .buffered(concurrency.max(1))
+    }
+
+    /// Returns the ledger diff of a milestone, with the created and spent outputs.
+    pub async fn get_ledger_diff(&self, milestone_index: u32) -> Result<LedgerDiff> {
+        let milestone = self.get_milestone_by_index(milestone_index).await?;
+        let utxo_changes = self.get_utxo_changes_with_permanode_fallback(milestone_index).await?;
+
+        let output_ids = |output_ids: &[String]| {
+            output_ids
+                .iter()
+                .map(|output_id| OutputId::from_str(output_id))
+                .collect::<std::result::Result<Vec<_>, _>>()
+        };
+        let created_outputs = self.get_outputs(output_ids(&utxo_changes.created_outputs)?).await?;
+        let consumed_outputs = self.get_outputs(output_ids(&utxo_changes.consumed_outputs)?).await?;
+
+        Ok(LedgerDiff {
+            milestone_index,
+            milestone_id: milestone.id(),
+            milestone_timestamp: milestone.essence().timestamp(),
+            created_outputs,
+            consumed_outputs,
+        })
+    }
+
+    // The UTXO changes of pruned milestones are only available on permanodes.
+    async fn get_utxo_changes_with_permanode_fallback(&self, milestone_index: u32) -> Result<UtxoChangesResponse> {
+        match self.get_utxo_changes_by_index(milestone_index).await {
+            Err(Error::NotFound(_)) if self.node_manager.has_permanodes() => {
+                let path = &format!("api/core/v2/milestones/by-index/{milestone_index}/utxo-changes");
+
+                self.node_manager
+                    .get_request(path, None, self.get_timeout(), false, true)
+                    .await
+            }
+            res => res,
+        }
+    }
+}
@@ -4,13 +4,21 @@
 //! High level APIs
 
 mod address;
+mod balance;
 mod block_builder;
+mod chain_history;
 mod consolidation;
+mod ledger_diff;
+mod transaction_tracker;
 mod types;
 
 pub use self::{
     address::*,
+    balance::*,
     block_builder::{pow::*, *},
+    chain_history::*,
+    ledger_diff::*,
+    transaction_tracker::*,
     types::*,
 };
 
@@ -0,0 +1,189 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use iota_types::block::{
+    metadata::BlockInclusionState, payload::transaction::TransactionId, semantic::ConflictReason, BlockId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{DEFAULT_TRANSACTION_TRACKER_INTERVAL, DEFAULT_TRANSACTION_TRACKER_TIMEOUT},
+    utils::sleep,
+    Client, Error, Result,
+};
+
+/// The final status of a tracked transaction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum TransactionStatus {
+    /// The transaction was included in the ledger.
+    #[serde(rename_all = "camelCase")]
+    Confirmed {
+        /// The block that included the transaction.
+        block_id: BlockId,
+        /// The index of the milestone that referenced the block.
+        milestone_index: u32,
+        /// The timestamp of the milestone that referenced the block.
+        milestone_timestamp: u32,
+    },
+    /// A block with the transaction was referenced by a milestone, but the transaction conflicts with the ledger.
+    #[serde(rename_all = "camelCase")]
+    Conflicting {
+        /// The block with the conflicting transaction.
+        block_id: BlockId,
+        /// The index of the milestone that referenced the block.
+        milestone_index: u32,
+        /// Why the transaction conflicts with the ledger.
+        reason: ConflictReason,
+    },
+    /// The transaction was neither confirmed nor conflicting before the timeout.
+    TimedOut,
+}
+
+/// Options for tracking a transaction until it's confirmed or conflicting.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TransactionTrackerOptions {
+    /// Interval between two checks of the transaction. With MQTT, it's the upper bound between two checks.
+    pub interval: Duration,
+    /// Time after which the tracking stops with [`TransactionStatus::TimedOut`].
+    pub timeout: Duration,
+}
+
+impl Default for TransactionTrackerOptions {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_TRANSACTION_TRACKER_INTERVAL,
+            timeout: DEFAULT_TRANSACTION_TRACKER_TIMEOUT,
+        }
+    }
+}
+
+impl Client {
+    /// Returns the status of a transaction, `None` if it's neither confirmed nor conflicting yet. Every attachment of
+    /// the transaction, also by reattachments, is found through the included block. Conflicts can only be found for
+    /// the given blocks with the transaction, since the nodes don't return the blocks of conflicting transactions by
+    /// their transaction ID. The reservations of the inputs are released once the transaction is confirmed or
+    /// conflicting.
+    pub async fn get_transaction_status(
+        &self,
+        transaction_id: &TransactionId,
+        block_ids: &[BlockId],
+    ) -> Result<Option<TransactionStatus>> {
+        match self.get_included_block(transaction_id).await {
+            Ok(block) => {
+                let block_id = block.id();
+                // The block can be known as included before its metadata is updated
+                if let BlockInclusionState::Included {
+                    referenced_by_milestone_index,
+                } = self.get_block_metadata(&block_id).await?.inclusion_state()
+                {
+                    let milestone = self.get_milestone_by_index(*referenced_by_milestone_index).await?;
+                    self.release_transaction_inputs(&block)?;
+
+                    return Ok(Some(TransactionStatus::Confirmed {
+                        block_id,
+                        milestone_index: *referenced_by_milestone_index,
+                        milestone_timestamp: milestone.essence().timestamp(),
+                    }));
+                }
+            }
+            Err(Error::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        for block_id in block_ids {
+            if let BlockInclusionState::Conflicting {
+                referenced_by_milestone_index,
+                reason,
+            } = self.get_block_metadata(block_id).await?.inclusion_state()
+            {
+                if self.reservations.is_some() {
+                    self.release_transaction_inputs(&self.get_block(block_id).await?)?;
+                }
+
+                return Ok(Some(TransactionStatus::Conflicting {
+                    block_id: *block_id,
+                    milestone_index: *referenced_by_milestone_index,
+                    reason: *reason,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Checks the status of a transaction in the interval of the options until it's confirmed or conflicting, or the
+    /// timeout passed. Unlike [`Client::retry_until_included()`], the blocks with the transaction are neither promoted
+    /// nor reattached. See [`Client::get_transaction_status()`].
+    pub async fn track_transaction(
+        &self,
+        transaction_id: &TransactionId,
+        block_ids: &[BlockId],
+        options: TransactionTrackerOptions,
+    ) -> Result<TransactionStatus> {
+        let start = instant::Instant::now();
+
+        loop {
+            if let Some(status) = self.get_transaction_status(transaction_id, block_ids).await? {
+                return Ok(status);
+            }
+
+            let remaining = options.timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                return Ok(TransactionStatus::TimedOut);
+            }
+            sleep(options.interval.min(remaining)).await;
+        }
+    }
+
+    /// Like [`Client::track_transaction()`], but the status is also checked as soon as the node publishes the included
+    /// block of the transaction or new metadata of the given blocks over MQTT. The callback is removed afterwards, the
+    /// topics are only unsubscribed if there are no other callbacks for them.
+    #[cfg(feature = "mqtt")]
+    pub async fn track_transaction_with_mqtt(
+        &mut self,
+        transaction_id: &TransactionId,
+        block_ids: &[BlockId],
+        options: TransactionTrackerOptions,
+    ) -> Result<TransactionStatus> {
+        use std::sync::Arc;
+
+        use crate::node_api::mqtt::Topic;
+
+        let topics = std::iter::once(format!("transactions/{transaction_id}/included-block"))
+            .chain(block_ids.iter().map(|block_id| format!("block-metadata/{block_id}")))
+            .map(Topic::try_new)
+            .collect::<Result<Vec<_>>>()?;
+        let notify = Arc::new(tokio::sync::Notify::new());
+        let notifier = notify.clone();
+        // Only this handler is removed afterwards, other subscriptions to the same topics are kept
+        let handler = self
+            .subscribe_handler(topics.clone(), move |_| notifier.notify_one())
+            .await?;
+
+        let start = tokio::time::Instant::now();
+        let status = loop {
+            match self.get_transaction_status(transaction_id, block_ids).await {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => {}
+                Err(e) => break Err(e),
+            }
+
+            let remaining = options.timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                break Ok(TransactionStatus::TimedOut);
+            }
+            // Either a notification or the interval triggers the next check
+            let _ = tokio::time::timeout(options.interval.min(remaining), notify.notified()).await;
+        };
+        // The status is already known, so failing to unsubscribe doesn't replace it
+        if let Err(e) = self.unsubscribe_handler(topics, &handler).await {
+            log::warn!("failed to unsubscribe the topics of transaction {transaction_id}: {e}");
+        }
+
+        status
+    }
+}
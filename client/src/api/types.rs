@@ -30,6 +30,10 @@ pub struct PreparedTransactionData {
     pub inputs_data: Vec<InputSigningData>,
     /// Optional remainder output information
     pub remainder: Option<RemainderData>,
+    /// Further remainder output information if the remainder policy split the remainder. A Ledger Nano only treats
+    /// `remainder` as remainder and shows these outputs like the other ones
+    #[serde(rename = "additionalRemainders", default)]
+    pub additional_remainders: Vec<RemainderData>,
 }
 
 /// PreparedTransactionData Dto
@@ -42,6 +46,9 @@ pub struct PreparedTransactionDataDto {
     pub inputs_data: Vec<InputSigningDataDto>,
     /// Optional remainder output information
     pub remainder: Option<RemainderDataDto>,
+    /// Further remainder output information if the remainder policy split the remainder
+    #[serde(rename = "additionalRemainders", default)]
+    pub additional_remainders: Vec<RemainderDataDto>,
 }
 
 impl From<&PreparedTransactionData> for PreparedTransactionDataDto {
@@ -50,6 +57,7 @@ impl From<&PreparedTransactionData> for PreparedTransactionDataDto {
             essence: TransactionEssenceDto::from(&value.essence),
             inputs_data: value.inputs_data.iter().map(InputSigningDataDto::from).collect(),
             remainder: value.remainder.as_ref().map(RemainderDataDto::from),
+            additional_remainders: value.additional_remainders.iter().map(RemainderDataDto::from).collect(),
         }
     }
 }
@@ -76,6 +84,12 @@ impl PreparedTransactionData {
                 ),
                 None => None,
             },
+            additional_remainders: value
+                .additional_remainders
+                .iter()
+                .map(|remainder| RemainderData::try_from_dto(remainder, protocol_parameters.token_supply()))
+                .collect::<crate::Result<Vec<RemainderData>>>()
+                .map_err(|_| DtoError::InvalidField("additional_remainders"))?,
         })
     }
 
@@ -97,6 +111,12 @@ impl PreparedTransactionData {
                 ),
                 None => None,
             },
+            additional_remainders: value
+                .additional_remainders
+                .iter()
+                .map(RemainderData::try_from_dto_unverified)
+                .collect::<crate::Result<Vec<RemainderData>>>()
+                .map_err(|_| DtoError::InvalidField("additional_remainders"))?,
         })
     }
 }
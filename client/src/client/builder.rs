@@ -4,7 +4,7 @@
 //! Builder of the Client Instance
 use std::{
     collections::HashMap,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::Duration,
 };
 
@@ -18,12 +18,20 @@ use tokio::{runtime::Runtime, sync::broadcast::channel};
 #[cfg(feature = "mqtt")]
 use crate::node_api::mqtt::{BrokerOptions, MqttEvent};
 use crate::{
-    client::Client,
+    client::{
+        CacheOptions, Client, ImmutableCache, MilestoneVerificationOptions, MilestoneVerifier, OutputReservations,
+        ReservationOptions,
+    },
     constants::{DEFAULT_API_TIMEOUT, DEFAULT_REMOTE_POW_API_TIMEOUT, DEFAULT_TIPS_INTERVAL},
     error::Result,
     node_manager::{
         builder::validate_url,
+        circuit_breaker::CircuitBreakerOptions,
+        http_client::HttpTransport,
         node::{Node, NodeAuth},
+        observer::RequestObserver,
+        retry::RetryOptions,
+        timeouts::RouteTimeouts,
     },
 };
 
@@ -130,6 +138,15 @@ pub struct ClientBuilder {
     /// The amount of threads to be used for proof of work
     #[serde(rename = "powWorkerCount", default)]
     pub pow_worker_count: Option<usize>,
+    /// Options for the cache of immutable resources, the cache is disabled if `None`
+    #[serde(rename = "cacheOptions", default)]
+    pub cache_options: Option<CacheOptions>,
+    /// Options to verify milestones with [`Client::get_verified_milestone_by_index()`], disabled if `None`
+    #[serde(rename = "milestoneVerification", default)]
+    pub milestone_verification: Option<MilestoneVerificationOptions>,
+    /// Options for the reservation of the inputs of prepared transactions, disabled if `None`
+    #[serde(rename = "reservationOptions", default)]
+    pub reservation_options: Option<ReservationOptions>,
 }
 
 fn default_api_timeout() -> Duration {
@@ -163,6 +180,9 @@ impl Default for ClientBuilder {
             api_timeout: DEFAULT_API_TIMEOUT,
             remote_pow_timeout: DEFAULT_REMOTE_POW_API_TIMEOUT,
             pow_worker_count: None,
+            cache_options: None,
+            milestone_verification: None,
+            reservation_options: None,
         }
     }
 }
@@ -273,6 +293,27 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the options of the circuit breaker, which stops sending requests to a node after consecutive failures until
+    /// a cool-down passed.
+    pub fn with_circuit_breaker_options(mut self, options: CircuitBreakerOptions) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_circuit_breaker_options(options);
+        self
+    }
+
+    /// Sets the options for retrying GET requests with exponential backoff and jitter, when all nodes failed with a
+    /// connection or server error.
+    pub fn with_retry_options(mut self, options: RetryOptions) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_retry_options(options);
+        self
+    }
+
+    /// Sets the timeouts of the GET requests per route class, for example a longer one for the indexer. Route classes
+    /// without a timeout use the API timeout.
+    pub fn with_route_timeouts(mut self, route_timeouts: RouteTimeouts) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_route_timeouts(route_timeouts);
+        self
+    }
+
     /// Sets the MQTT broker options.
     #[cfg(feature = "mqtt")]
     pub fn with_mqtt_broker_options(mut self, options: BrokerOptions) -> Self {
@@ -316,6 +357,42 @@ impl ClientBuilder {
         self
     }
 
+    /// Enables the cache for blocks, milestones and the included blocks of transactions, which can't change anymore
+    /// once they exist. The least recently used resources are evicted when a limit is reached.
+    pub fn with_cache_options(mut self, options: CacheOptions) -> Self {
+        self.cache_options.replace(options);
+        self
+    }
+
+    /// Enables the verification of milestones with [`Client::get_verified_milestone_by_index()`], against the public
+    /// keys of the coordinator and the chain of previous milestone IDs.
+    pub fn with_milestone_verification(mut self, options: MilestoneVerificationOptions) -> Self {
+        self.milestone_verification.replace(options);
+        self
+    }
+
+    /// Enables the reservation of the outputs that prepared transactions use as inputs, so concurrent transaction
+    /// builds don't select the same outputs. Reservations are released once [`Client::track_transaction()`] or
+    /// [`Client::retry_until_included()`] see the transaction confirmed or conflicting, or after the TTL. Transactions
+    /// that are only posted with [`Client::post_block()`] keep their inputs reserved until the TTL passed.
+    pub fn with_output_reservations(mut self, options: ReservationOptions) -> Self {
+        self.reservation_options.replace(options);
+        self
+    }
+
+    /// Sets the transport that is used to send the requests to the nodes, instead of the default
+    /// [`ReqwestTransport`](crate::node_manager::http_client::ReqwestTransport).
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_transport(Arc::new(transport));
+        self
+    }
+
+    /// Sets an observer that is called for every request sent to a node, for example to collect metrics.
+    pub fn with_request_observer(mut self, observer: impl RequestObserver + 'static) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_request_observer(Arc::new(observer));
+        self
+    }
+
     /// Set User-Agent header for requests
     /// Default is "iota-client/{version}"
     pub fn with_user_agent(mut self, user_agent: String) -> Self {
@@ -328,33 +405,21 @@ impl ClientBuilder {
         let network_info = Arc::new(RwLock::new(self.network_info));
         let healthy_nodes = Arc::new(RwLock::new(HashMap::new()));
 
+        let node_manager = self.node_manager_builder.build(healthy_nodes);
+
         #[cfg(not(target_family = "wasm"))]
         let (runtime, sync_kill_sender) = {
-            let nodes = self
-                .node_manager_builder
-                .primary_node
-                .iter()
-                .chain(self.node_manager_builder.nodes.iter())
-                .map(|node| node.clone().into())
-                .collect();
-
-            let healthy_nodes_ = healthy_nodes.clone();
+            let nodes = node_manager.nodes_to_sync()?;
+            let node_manager_ = node_manager.clone();
             let network_info_ = network_info.clone();
             let (sync_kill_sender, sync_kill_receiver) = channel(1);
 
             let runtime = std::thread::spawn(move || {
                 let runtime = Runtime::new().expect("failed to create Tokio runtime");
-                if let Err(e) = runtime.block_on(Client::sync_nodes(&healthy_nodes_, &nodes, &network_info_)) {
+                if let Err(e) = runtime.block_on(Client::sync_nodes(&node_manager_, &nodes, &network_info_)) {
                     panic!("failed to sync nodes: {:?}", e);
                 }
-                Client::start_sync_process(
-                    &runtime,
-                    healthy_nodes_,
-                    nodes,
-                    self.node_manager_builder.node_sync_interval,
-                    network_info_,
-                    sync_kill_receiver,
-                );
+                Client::start_sync_process(&runtime, node_manager_, network_info_, sync_kill_receiver);
                 runtime
             })
             .join()
@@ -365,7 +430,7 @@ impl ClientBuilder {
         #[cfg(feature = "mqtt")]
         let (mqtt_event_tx, mqtt_event_rx) = tokio::sync::watch::channel(MqttEvent::Connected);
         let client = Client {
-            node_manager: self.node_manager_builder.build(healthy_nodes),
+            node_manager,
             #[cfg(not(target_family = "wasm"))]
             runtime,
             #[cfg(not(target_family = "wasm"))]
@@ -382,6 +447,15 @@ impl ClientBuilder {
             api_timeout: self.api_timeout,
             remote_pow_timeout: self.remote_pow_timeout,
             pow_worker_count: self.pow_worker_count,
+            cache: self
+                .cache_options
+                .map(|options| Arc::new(Mutex::new(ImmutableCache::new(options)))),
+            milestone_verifier: self
+                .milestone_verification
+                .map(|options| Arc::new(Mutex::new(MilestoneVerifier::new(options)))),
+            reservations: self
+                .reservation_options
+                .map(|options| Arc::new(Mutex::new(OutputReservations::new(options)))),
         };
         Ok(client)
     }
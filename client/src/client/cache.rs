@@ -0,0 +1,177 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounded LRU cache for node API resources that can't change anymore once they exist
+
+use std::collections::{BTreeMap, HashMap};
+
+use iota_types::block::{
+    payload::{
+        milestone::{MilestoneId, MilestonePayload},
+        transaction::TransactionId,
+    },
+    Block, BlockId,
+};
+use packable::PackableExt;
+
+use crate::{
+    constants::{DEFAULT_CACHE_MAX_BYTES, DEFAULT_CACHE_MAX_ENTRIES},
+    Client,
+};
+
+/// Options for the cache of immutable node API resources: blocks, milestones and the included blocks of transactions.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheOptions {
+    /// Maximum amount of cached resources.
+    pub max_entries: usize,
+    /// Maximum size of all cached resources together in bytes.
+    pub max_bytes: usize,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            max_bytes: DEFAULT_CACHE_MAX_BYTES,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum CacheKey {
+    Block(BlockId),
+    IncludedBlock(TransactionId),
+    Milestone(MilestoneId),
+    MilestoneIndex(u32),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum CachedResource {
+    Block(Block),
+    Milestone(MilestonePayload),
+}
+
+impl CachedResource {
+    fn size(&self) -> usize {
+        match self {
+            Self::Block(block) => block.packed_len(),
+            Self::Milestone(milestone) => milestone.packed_len(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    resource: CachedResource,
+    size: usize,
+    last_used: u64,
+}
+
+/// Least recently used cache, bounded by the amount and the size of the entries.
+#[derive(Debug)]
+pub(crate) struct ImmutableCache {
+    options: CacheOptions,
+    entries: HashMap<CacheKey, CacheEntry>,
+    // Keys by the tick in which they were used last, the first one gets evicted next
+    usage: BTreeMap<u64, CacheKey>,
+    size: usize,
+    tick: u64,
+}
+
+impl ImmutableCache {
+    pub(crate) fn new(options: CacheOptions) -> Self {
+        Self {
+            options,
+            entries: HashMap::new(),
+            usage: BTreeMap::new(),
+            size: 0,
+            tick: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &CacheKey) -> Option<CachedResource> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        self.usage.remove(&entry.last_used);
+        self.usage.insert(self.tick, *key);
+        entry.last_used = self.tick;
+
+        Some(entry.resource.clone())
+    }
+
+    pub(crate) fn insert(&mut self, key: CacheKey, resource: CachedResource) {
+        let size = resource.size();
+        if self.options.max_entries == 0 || size > self.options.max_bytes {
+            return;
+        }
+        self.remove(&key);
+
+        while self.entries.len() >= self.options.max_entries || self.size + size > self.options.max_bytes {
+            match self.usage.keys().next().copied() {
+                Some(least_recently_used) => {
+                    let key = self.usage[&least_recently_used];
+                    self.remove(&key);
+                }
+                None => break,
+            }
+        }
+
+        self.tick += 1;
+        self.usage.insert(self.tick, key);
+        self.size += size;
+        self.entries.insert(
+            key,
+            CacheEntry {
+                resource,
+                size,
+                last_used: self.tick,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.usage.remove(&entry.last_used);
+            self.size -= entry.size;
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.usage.clear();
+        self.size = 0;
+    }
+}
+
+impl Client {
+    pub(crate) fn get_cached(&self, key: &CacheKey) -> Option<CachedResource> {
+        self.cache.as_ref()?.lock().ok()?.get(key)
+    }
+
+    pub(crate) fn insert_cached(&self, key: CacheKey, resource: CachedResource) {
+        if let Some(cache) = &self.cache {
+            if let Ok(mut cache) = cache.lock() {
+                cache.insert(key, resource);
+            }
+        }
+    }
+
+    /// Returns the amount of resources in the cache, `None` if the cache isn't enabled.
+    pub fn cache_len(&self) -> Option<usize> {
+        Some(self.cache.as_ref()?.lock().ok()?.len())
+    }
+
+    /// Removes all resources from the cache.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            if let Ok(mut cache) = cache.lock() {
+                cache.clear();
+            }
+        }
+    }
+}
@@ -4,9 +4,10 @@
 use std::{collections::HashSet, str::FromStr};
 
 use iota_types::{
-    api::{dto::LedgerInclusionStateDto, response::OutputWithMetadataResponse},
+    api::response::OutputWithMetadataResponse,
     block::{
         input::{Input, UtxoInput, INPUT_COUNT_MAX},
+        metadata::BlockInclusionState,
         output::{Output, OutputId},
         payload::{
             transaction::{TransactionEssence, TransactionId},
@@ -92,9 +93,9 @@ impl Client {
     pub async fn retry(&self, block_id: &BlockId) -> Result<(BlockId, Block)> {
         // Get the metadata to check if it needs to promote or reattach
         let block_metadata = self.get_block_metadata(block_id).await?;
-        if block_metadata.should_promote.unwrap_or(false) {
+        if block_metadata.should_promote() {
             self.promote_unchecked(block_id).await
-        } else if block_metadata.should_reattach.unwrap_or(false) {
+        } else if block_metadata.should_reattach() {
             self.reattach_unchecked(block_id).await
         } else {
             Err(Error::NoNeedPromoteOrReattach(block_id.to_string()))
@@ -135,32 +136,33 @@ impl Client {
             let mut conflicting = false;
             for (index, block_id_) in block_ids.clone().iter().enumerate() {
                 let block_metadata = self.get_block_metadata(block_id_).await?;
-                if let Some(inclusion_state) = block_metadata.ledger_inclusion_state {
-                    match inclusion_state {
-                        LedgerInclusionStateDto::Included | LedgerInclusionStateDto::NoTransaction => {
-                            // if original block, request it so we can return it on first position
-                            if block_id == block_id_ {
-                                let mut included_and_reattached_blocks =
-                                    vec![(*block_id, self.get_block(block_id).await?)];
-                                included_and_reattached_blocks.extend(blocks_with_id);
-                                return Ok(included_and_reattached_blocks);
-                            } else {
-                                // Move included block to first position
-                                blocks_with_id.rotate_left(index);
-                                return Ok(blocks_with_id);
-                            }
+                match block_metadata.inclusion_state() {
+                    BlockInclusionState::Included { .. } | BlockInclusionState::NoTransaction { .. } => {
+                        // if original block, request it so we can return it on first position
+                        if block_id == block_id_ {
+                            let block = self.get_block(block_id).await?;
+                            self.release_transaction_inputs(&block)?;
+                            let mut included_and_reattached_blocks = vec![(*block_id, block)];
+                            included_and_reattached_blocks.extend(blocks_with_id);
+                            return Ok(included_and_reattached_blocks);
+                        } else {
+                            // Move included block to first position
+                            blocks_with_id.rotate_left(index);
+                            self.release_transaction_inputs(&blocks_with_id[0].1)?;
+                            return Ok(blocks_with_id);
                         }
-                        // only set it as conflicting here and don't return, because another reattached block could
-                        // have the included transaction
-                        LedgerInclusionStateDto::Conflicting => conflicting = true,
-                    };
+                    }
+                    // only set it as conflicting here and don't return, because another reattached block could
+                    // have the included transaction
+                    BlockInclusionState::Conflicting { .. } => conflicting = true,
+                    BlockInclusionState::Pending => {}
                 }
                 // Only reattach or promote latest attachment of the block
                 if index == block_ids_len - 1 {
-                    if block_metadata.should_promote.unwrap_or(false) {
+                    if block_metadata.should_promote() {
                         // Safe to unwrap since we iterate over it
                         self.promote_unchecked(block_ids.last().unwrap()).await?;
-                    } else if block_metadata.should_reattach.unwrap_or(false) {
+                    } else if block_metadata.should_reattach() {
                         // Safe to unwrap since we iterate over it
                         let reattached = self.reattach_unchecked(block_ids.last().unwrap()).await?;
                         block_ids.push(reattached.0);
@@ -173,6 +175,7 @@ impl Client {
             if conflicting {
                 let block = self.get_block(block_id).await?;
                 if let Some(Payload::Transaction(transaction_payload)) = block.payload() {
+                    self.release_transaction_inputs(&block)?;
                     let included_block = self.get_included_block(&transaction_payload.id()).await?;
                     let mut included_and_reattached_blocks = vec![(included_block.id(), included_block)];
                     included_and_reattached_blocks.extend(blocks_with_id);
@@ -280,7 +283,7 @@ impl Client {
     /// confirmed for a while.
     pub async fn reattach(&self, block_id: &BlockId) -> Result<(BlockId, Block)> {
         let metadata = self.get_block_metadata(block_id).await?;
-        if metadata.should_reattach.unwrap_or(false) {
+        if metadata.should_reattach() {
             self.reattach_unchecked(block_id).await
         } else {
             Err(Error::NoNeedPromoteOrReattach(block_id.to_string()))
@@ -308,7 +311,7 @@ impl Client {
     /// method should error out and should not allow unnecessary promotions.
     pub async fn promote(&self, block_id: &BlockId) -> Result<(BlockId, Block)> {
         let metadata = self.get_block_metadata(block_id).await?;
-        if metadata.should_promote.unwrap_or(false) {
+        if metadata.should_promote() {
             self.promote_unchecked(block_id).await
         } else {
             Err(Error::NoNeedPromoteOrReattach(block_id.to_string()))
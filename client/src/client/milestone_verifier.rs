@@ -0,0 +1,197 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of milestones against the public keys of the coordinator and the chain of previous milestone IDs
+
+use std::collections::BTreeMap;
+
+use iota_types::block::payload::milestone::{MilestoneId, MilestonePayload};
+
+use crate::{Client, Error, Result};
+
+/// A public key of the coordinator and the range of milestone indexes it signs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneKeyRange {
+    /// The hex encoded Ed25519 public key.
+    pub public_key: String,
+    /// The first milestone index the key is applicable for.
+    pub start_index: u32,
+    /// The last milestone index the key is applicable for, 0 if it doesn't expire.
+    pub end_index: u32,
+}
+
+impl MilestoneKeyRange {
+    fn contains(&self, index: u32) -> bool {
+        self.start_index <= index && (self.end_index == 0 || index <= self.end_index)
+    }
+}
+
+/// Options to verify milestones, like in the protocol configuration of the nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneVerificationOptions {
+    /// The number of valid signatures a milestone needs.
+    pub public_key_count: usize,
+    /// The public keys of the coordinator with their milestone index ranges.
+    pub key_ranges: Vec<MilestoneKeyRange>,
+    /// A milestone that is trusted, from which the milestone chain is verified. Without it the first milestone that
+    /// is requested and has valid signatures is trusted.
+    #[serde(default)]
+    pub trusted_milestone_id: Option<MilestoneId>,
+}
+
+impl MilestoneVerificationOptions {
+    /// Returns the public keys of the coordinator that are applicable for a milestone index, hex encoded without
+    /// prefix.
+    pub fn applicable_public_keys(&self, index: u32) -> Vec<String> {
+        self.key_ranges
+            .iter()
+            .filter(|key_range| key_range.contains(index))
+            .map(|key_range| key_range.public_key.trim_start_matches("0x").to_lowercase())
+            .collect()
+    }
+
+    /// Verifies the signatures of a milestone against the applicable public keys.
+    pub fn verify_signatures(&self, milestone: &MilestonePayload) -> Result<()> {
+        let index = *milestone.essence().index();
+
+        milestone
+            .validate(&self.applicable_public_keys(index), self.public_key_count)
+            .map_err(|e| Error::MilestoneVerificationError {
+                index,
+                reason: format!("{e:?}"),
+            })
+    }
+}
+
+// The verified milestones, with the IDs of their previous milestones. They form a chain without gaps, so new
+// milestones are verified by their link to the highest or the lowest one.
+#[derive(Debug)]
+pub(crate) struct MilestoneVerifier {
+    options: MilestoneVerificationOptions,
+    verified: BTreeMap<u32, (MilestoneId, MilestoneId)>,
+}
+
+impl MilestoneVerifier {
+    pub(crate) fn new(options: MilestoneVerificationOptions) -> Self {
+        Self {
+            options,
+            verified: BTreeMap::new(),
+        }
+    }
+}
+
+fn unchained(index: u32, reason: &str) -> Error {
+    Error::MilestoneVerificationError {
+        index,
+        reason: reason.to_string(),
+    }
+}
+
+impl Client {
+    /// Gets a milestone by its index and verifies it: its signatures must be valid for the public keys of the
+    /// coordinator and it must be linked by the previous milestone IDs to the milestones that were verified before,
+    /// starting at the trusted milestone. Milestones in between are requested and verified too, so the first request
+    /// far from the verified milestones can take a while. Requires
+    /// [`ClientBuilder::with_milestone_verification()`](crate::ClientBuilder::with_milestone_verification).
+    pub async fn get_verified_milestone_by_index(&self, index: u32) -> Result<MilestonePayload> {
+        let verifier = self
+            .milestone_verifier
+            .as_ref()
+            .ok_or_else(|| unchained(index, "milestone verification isn't enabled"))?;
+        let (options, trusted_milestone) = {
+            let verifier = verifier.lock().map_err(|_| Error::PoisonError)?;
+            let trusted_milestone = if verifier.verified.is_empty() {
+                verifier.options.trusted_milestone_id
+            } else {
+                None
+            };
+            (verifier.options.clone(), trusted_milestone)
+        };
+
+        if let Some(trusted_milestone_id) = trusted_milestone {
+            let milestone = self.get_milestone_by_id(&trusted_milestone_id).await?;
+            if milestone.id() != trusted_milestone_id {
+                return Err(unchained(*milestone.essence().index(), "not the trusted milestone"));
+            }
+            self.insert_verified_milestone(&options, &milestone, None)?;
+        }
+
+        loop {
+            let bounds = {
+                let verifier = verifier.lock().map_err(|_| Error::PoisonError)?;
+                let lowest = verifier.verified.iter().next().map(|(index, ids)| (*index, *ids));
+                let highest = verifier.verified.iter().next_back().map(|(index, ids)| (*index, *ids));
+                lowest.zip(highest)
+            };
+
+            match bounds {
+                // Nothing to chain to yet, so the milestone is trusted on first use
+                None => {
+                    let milestone = self.get_milestone_by_index(index).await?;
+                    if self.insert_verified_milestone(&options, &milestone, None)? {
+                        return Ok(milestone);
+                    }
+                }
+                Some(((lowest_index, (_, lowest_previous_id)), (highest_index, (highest_id, _)))) => {
+                    if (lowest_index..=highest_index).contains(&index) {
+                        let milestone = self.get_milestone_by_index(index).await?;
+                        let expected_id = verifier
+                            .lock()
+                            .map_err(|_| Error::PoisonError)?
+                            .verified
+                            .get(&index)
+                            .map(|(milestone_id, _)| *milestone_id);
+                        if expected_id != Some(milestone.id()) {
+                            return Err(unchained(index, "different from the verified milestone"));
+                        }
+                        return Ok(milestone);
+                    }
+
+                    // Extends the chain by one milestone towards the index
+                    if index > highest_index {
+                        let milestone = self.get_milestone_by_index(highest_index + 1).await?;
+                        if milestone.essence().previous_milestone_id() != &highest_id {
+                            return Err(unchained(highest_index + 1, "previous milestone ID doesn't match"));
+                        }
+                        self.insert_verified_milestone(&options, &milestone, Some(highest_index + 1))?;
+                    } else {
+                        let milestone = self.get_milestone_by_index(lowest_index - 1).await?;
+                        if milestone.id() != lowest_previous_id {
+                            return Err(unchained(lowest_index - 1, "not the previous milestone"));
+                        }
+                        self.insert_verified_milestone(&options, &milestone, Some(lowest_index - 1))?;
+                    }
+                }
+            }
+        }
+    }
+
+    // Verifies the signatures and the index of a milestone and adds it to the verified milestones. Without expected
+    // index it's the first milestone of the chain, which is only added if no other request added one meanwhile.
+    fn insert_verified_milestone(
+        &self,
+        options: &MilestoneVerificationOptions,
+        milestone: &MilestonePayload,
+        expected_index: Option<u32>,
+    ) -> Result<bool> {
+        let index = *milestone.essence().index();
+        if matches!(expected_index, Some(expected_index) if expected_index != index) {
+            return Err(unchained(index, "unexpected milestone index"));
+        }
+        options.verify_signatures(milestone)?;
+
+        if let Some(verifier) = &self.milestone_verifier {
+            let mut verifier = verifier.lock().map_err(|_| Error::PoisonError)?;
+            if expected_index.is_none() && !verifier.verified.is_empty() {
+                return Ok(false);
+            }
+            verifier
+                .verified
+                .insert(index, (milestone.id(), *milestone.essence().previous_milestone_id()));
+        }
+
+        Ok(true)
+    }
+}
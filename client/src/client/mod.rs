@@ -4,10 +4,13 @@
 //! The Client module to connect through HORNET or Bee with API usages
 
 mod builder;
+mod cache;
 mod high_level;
+mod milestone_verifier;
+mod reservations;
 
 use std::{
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::Duration,
 };
 
@@ -22,7 +25,17 @@ use {
     tokio::sync::watch::{Receiver as WatchReceiver, Sender as WatchSender},
 };
 
-pub use self::builder::{ClientBuilder, NetworkInfo, NetworkInfoDto};
+pub(crate) use self::{
+    cache::{CacheKey, CachedResource, ImmutableCache},
+    milestone_verifier::MilestoneVerifier,
+    reservations::OutputReservations,
+};
+pub use self::{
+    builder::{ClientBuilder, NetworkInfo, NetworkInfoDto},
+    cache::CacheOptions,
+    milestone_verifier::{MilestoneKeyRange, MilestoneVerificationOptions},
+    reservations::ReservationOptions,
+};
 use crate::{constants::DEFAULT_TIPS_INTERVAL, error::Result};
 
 /// An instance of the client using HORNET or Bee URI
@@ -53,6 +66,12 @@ pub struct Client {
     #[allow(dead_code)] // not used for wasm
     /// pow_worker_count for local PoW.
     pub(crate) pow_worker_count: Option<usize>,
+    /// Cache for immutable resources, if enabled.
+    pub(crate) cache: Option<Arc<Mutex<ImmutableCache>>>,
+    /// The verified milestones, if milestone verification is enabled.
+    pub(crate) milestone_verifier: Option<Arc<Mutex<MilestoneVerifier>>>,
+    /// The outputs reserved by prepared transactions, if output reservations are enabled.
+    pub(crate) reservations: Option<Arc<Mutex<OutputReservations>>>,
 }
 
 impl std::fmt::Debug for Client {
@@ -70,7 +89,10 @@ impl Drop for Client {
     fn drop(&mut self) {
         #[cfg(not(target_family = "wasm"))]
         if let Some(sender) = self.sync_kill_sender.take() {
-            sender.send(()).expect("failed to stop syncing process");
+            // Clones of the client share the sync process, so it's only stopped with the last one
+            if let Ok(sender) = Arc::try_unwrap(sender) {
+                sender.send(()).expect("failed to stop syncing process");
+            }
         }
 
         #[cfg(not(target_family = "wasm"))]
@@ -0,0 +1,153 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Registry of the outputs that prepared transactions use as inputs, so concurrent transaction builds don't select the
+//! same outputs
+
+use std::{collections::HashMap, time::Duration};
+
+use instant::Instant;
+use iota_types::block::{
+    input::Input,
+    output::OutputId,
+    payload::{transaction::TransactionEssence, Payload},
+    Block,
+};
+
+use crate::{constants::DEFAULT_OUTPUT_RESERVATION_TTL, Client, Error, Result};
+
+/// Options for the reservation of the outputs that prepared transactions use as inputs.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReservationOptions {
+    /// Time after which a reservation is released, if the transaction wasn't confirmed or conflicting before.
+    pub ttl: Duration,
+}
+
+impl Default for ReservationOptions {
+    fn default() -> Self {
+        Self {
+            ttl: DEFAULT_OUTPUT_RESERVATION_TTL,
+        }
+    }
+}
+
+/// Reserved outputs with the time their reservation expires.
+#[derive(Debug)]
+pub(crate) struct OutputReservations {
+    options: ReservationOptions,
+    reservations: HashMap<OutputId, Instant>,
+}
+
+impl OutputReservations {
+    pub(crate) fn new(options: ReservationOptions) -> Self {
+        Self {
+            options,
+            reservations: HashMap::new(),
+        }
+    }
+
+    fn remove_expired(&mut self) {
+        let now = Instant::now();
+        self.reservations.retain(|_, expires| *expires > now);
+    }
+
+    fn is_reserved(&mut self, output_id: &OutputId) -> bool {
+        self.remove_expired();
+        self.reservations.contains_key(output_id)
+    }
+
+    fn reserve(&mut self, output_ids: &[OutputId]) -> bool {
+        self.remove_expired();
+        if output_ids.iter().any(|output_id| self.reservations.contains_key(output_id)) {
+            return false;
+        }
+
+        let expires = Instant::now() + self.options.ttl;
+        self.reservations.extend(output_ids.iter().map(|output_id| (*output_id, expires)));
+
+        true
+    }
+
+    fn release(&mut self, output_ids: &[OutputId]) {
+        for output_id in output_ids {
+            self.reservations.remove(output_id);
+        }
+    }
+
+    fn reserved(&mut self) -> Vec<OutputId> {
+        self.remove_expired();
+        self.reservations.keys().copied().collect()
+    }
+}
+
+impl Client {
+    /// Reserves the outputs until they're released or the TTL passed, but only if none of them is reserved already.
+    /// Returns if the outputs were reserved, always `true` if output reservations aren't enabled. Automatic input
+    /// selection skips reserved outputs and reserves the selected ones.
+    pub fn reserve_outputs(&self, output_ids: &[OutputId]) -> Result<bool> {
+        match &self.reservations {
+            Some(reservations) => Ok(reservations
+                .lock()
+                .map_err(|_| Error::PoisonError)?
+                .reserve(output_ids)),
+            None => Ok(true),
+        }
+    }
+
+    /// Releases the reservation of the outputs.
+    pub fn release_outputs(&self, output_ids: &[OutputId]) -> Result<()> {
+        if let Some(reservations) = &self.reservations {
+            reservations.lock().map_err(|_| Error::PoisonError)?.release(output_ids);
+        }
+
+        Ok(())
+    }
+
+    /// Returns if the output is reserved.
+    pub fn is_output_reserved(&self, output_id: &OutputId) -> Result<bool> {
+        match &self.reservations {
+            Some(reservations) => Ok(reservations
+                .lock()
+                .map_err(|_| Error::PoisonError)?
+                .is_reserved(output_id)),
+            None => Ok(false),
+        }
+    }
+
+    // Returns an error if the output is reserved, for outputs that can't be replaced by other ones, like the current
+    // output of an alias, foundry or NFT chain
+    pub(crate) fn check_output_not_reserved(&self, output_id: &OutputId) -> Result<()> {
+        if self.is_output_reserved(output_id)? {
+            return Err(Error::OutputReserved(*output_id));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the reserved outputs.
+    pub fn reserved_outputs(&self) -> Result<Vec<OutputId>> {
+        match &self.reservations {
+            Some(reservations) => Ok(reservations.lock().map_err(|_| Error::PoisonError)?.reserved()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    // Releases the inputs of the transaction in the block, once it's confirmed or conflicting
+    pub(crate) fn release_transaction_inputs(&self, block: &Block) -> Result<()> {
+        if let Some(Payload::Transaction(transaction)) = block.payload() {
+            let TransactionEssence::Regular(essence) = transaction.essence();
+            let output_ids = essence
+                .inputs()
+                .iter()
+                .filter_map(|input| match input {
+                    Input::Utxo(input) => Some(*input.output_id()),
+                    Input::Treasury(_) => None,
+                })
+                .collect::<Vec<_>>();
+            self.release_outputs(&output_ids)?;
+        }
+
+        Ok(())
+    }
+}
@@ -8,13 +8,38 @@ use std::time::Duration;
 /// Default timeout for all API requests apart from post_block with remote PoW
 pub(crate) const DEFAULT_API_TIMEOUT: Duration = Duration::from_secs(15);
 pub(crate) const DEFAULT_REMOTE_POW_API_TIMEOUT: Duration = Duration::from_secs(100);
+/// Default timeout for pruning the database and creating snapshots, which can take a while
+pub(crate) const DEFAULT_MANAGEMENT_TIMEOUT: Duration = Duration::from_secs(300);
 pub(crate) const DEFAULT_RETRY_UNTIL_INCLUDED_INTERVAL: u64 = 1;
 pub(crate) const DEFAULT_RETRY_UNTIL_INCLUDED_MAX_AMOUNT: u64 = 40;
+/// Default interval and timeout of tracking a transaction until it's confirmed or conflicting
+pub(crate) const DEFAULT_TRANSACTION_TRACKER_INTERVAL: Duration = Duration::from_secs(1);
+pub(crate) const DEFAULT_TRANSACTION_TRACKER_TIMEOUT: Duration = Duration::from_secs(60);
 /// Interval in seconds when new tips will be requested during PoW, so the final block always will be attached to a
 /// new part of the Tangle
 pub(crate) const DEFAULT_TIPS_INTERVAL: u64 = 5;
 /// Interval in which the node info will be requested and healthy nodes will be added to the healthy node pool
 pub(crate) const NODE_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+/// Amount of network events that are buffered for a subscriber that doesn't receive them
+#[cfg(not(target_family = "wasm"))]
+pub(crate) const NETWORK_EVENT_CHANNEL_CAPACITY: usize = 32;
+/// Weight of a new sample in the moving averages of the node stats
+pub(crate) const NODE_STATS_SMOOTHING_FACTOR: f64 = 0.2;
+/// Latency in milliseconds at which the latency factor of the node score is halved
+pub(crate) const NODE_STATS_LATENCY_REFERENCE_MS: f64 = 500.0;
+/// Amount of consecutive failed requests after which a node isn't used until the cool-down passed
+pub(crate) const DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 5;
+pub(crate) const DEFAULT_CIRCUIT_BREAKER_COOL_DOWN: Duration = Duration::from_secs(30);
+/// Amount of retries of GET requests, when all nodes failed with a connection or server error
+pub(crate) const DEFAULT_MAX_REQUEST_RETRIES: u32 = 2;
+pub(crate) const DEFAULT_REQUEST_RETRY_INITIAL_DELAY: Duration = Duration::from_millis(200);
+pub(crate) const DEFAULT_REQUEST_RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+/// Default maximum amount of resources in the cache for immutable resources
+pub(crate) const DEFAULT_CACHE_MAX_ENTRIES: usize = 10_000;
+/// Default maximum size of the cache for immutable resources, 32 MiB
+pub(crate) const DEFAULT_CACHE_MAX_BYTES: usize = 32 * 1024 * 1024;
+/// Default time after which the reservation of the inputs of a prepared transaction is released
+pub(crate) const DEFAULT_OUTPUT_RESERVATION_TTL: Duration = Duration::from_secs(300);
 pub(crate) const DEFAULT_MIN_QUORUM_SIZE: usize = 3;
 pub(crate) const DEFAULT_QUORUM_THRESHOLD: usize = 66;
 pub(crate) const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
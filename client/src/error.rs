@@ -5,11 +5,14 @@
 
 use std::fmt::{Debug, Display};
 
-use iota_types::block::{output::NativeTokens, semantic::ConflictReason};
+use iota_types::block::{
+    output::{NativeTokens, OutputId},
+    semantic::ConflictReason,
+};
 use packable::error::UnexpectedEOF;
 use serde::{ser::Serializer, Serialize};
 
-use crate::node_api::indexer::QueryParameter;
+use crate::{node_api::indexer::QueryParameter, node_manager::quorum::QuorumReport};
 
 /// Type alias of `Result` in iota-client
 pub type Result<T> = std::result::Result<T, Error>;
@@ -34,6 +37,9 @@ pub enum Error {
     #[error("{0}")]
     #[serde(serialize_with = "display_string")]
     BlockError(#[from] iota_types::block::Error),
+    /// Invalid client certificate for mutual TLS
+    #[error("invalid client certificate: {0}")]
+    ClientCertificateError(String),
     /// The wallet account has enough funds, but split on too many outputs
     #[error("the wallet account has enough funds, but split on too many outputs: {0}, max. is 128, consolidate them")]
     ConsolidationRequired(usize),
@@ -53,6 +59,12 @@ pub enum Error {
     /// Invalid mnemonic error
     #[error("invalid mnemonic {0}")]
     InvalidMnemonic(String),
+    /// Invalid indexer output query
+    #[error("invalid output query: {0}")]
+    InvalidOutputQuery(String),
+    /// A proof of inclusion that doesn't prove that the block was referenced by the milestone
+    #[error("invalid proof of inclusion: {0}")]
+    InvalidProofOfInclusion(String),
     /// The transaction essence is too large
     #[error("the transaction essence is too large. Its length is {length}, max length is {max_length}")]
     InvalidRegularTransactionEssenceLength {
@@ -73,6 +85,14 @@ pub enum Error {
     #[error("{0}")]
     #[serde(serialize_with = "display_string")]
     Json(#[from] serde_json::Error),
+    /// A milestone failed the verification against the coordinator public keys or the milestone chain
+    #[error("milestone {index} failed the verification: {reason}")]
+    MilestoneVerificationError {
+        /// The index of the milestone.
+        index: u32,
+        /// Why the verification failed.
+        reason: String,
+    },
     /// Missing input for utxo chain
     #[error("missing input: {0}")]
     MissingInput(String),
@@ -111,6 +131,9 @@ pub enum Error {
     /// Output Error
     #[error("output error: {0}")]
     OutputError(&'static str),
+    /// The output is reserved as input of another prepared transaction
+    #[error("output {0} is reserved as input of another prepared transaction")]
+    OutputReserved(OutputId),
     /// PlaceholderSecretManager can't be used for address generation or signing
     #[error("placeholderSecretManager can't be used for address generation or signing")]
     PlaceholderSecretManager,
@@ -139,6 +162,8 @@ pub enum Error {
         quorum_size: usize,
         /// The minimum quorum threshold.
         minimum_threshold: usize,
+        /// The responses of the nodes and which of them agreed.
+        report: QuorumReport,
     },
     /// Error from RestAPI calls with unexpected status code response
     #[error("response error with status code {code}: {text}, URL: {url}")]
@@ -187,6 +212,9 @@ pub enum Error {
     /// Unexpected API response error
     #[error("unexpected API response")]
     UnexpectedApiResponse,
+    /// An alias, foundry, NFT or native token would be burned without being listed in the burn.
+    #[error("{0} would be burned, but it's not listed in the burn")]
+    UnlistedBurn(String),
     /// An indexer API request contains a query parameter not supported by the endpoint.
     #[error("an indexer API request contains a query parameter not supported by the endpoint: {0}.")]
     UnsupportedQueryParameter(QueryParameter),
@@ -23,8 +23,8 @@ use crate::{
         ClientBlockBuilderOptions as BuildBlockOptions, GetAddressesBuilderOptions as GenerateAddressesOptions,
         PreparedTransactionDataDto,
     },
-    node_api::indexer::query_parameters::QueryParameter,
-    node_manager::node::NodeAuth,
+    node_api::{core::routes::PruneDatabaseOptions, indexer::query_parameters::QueryParameter},
+    node_manager::node::{NodeAuth, NodeDto},
     secret::SecretManagerDto,
 };
 
@@ -137,6 +137,27 @@ pub enum Message {
     /// Returns the unhealthy nodes.
     #[cfg(not(target_family = "wasm"))]
     UnhealthyNodes,
+    /// Returns the nodes of the node pool.
+    /// Expected response: [`Nodes`](crate::message_interface::Response::Nodes)
+    ListNodes,
+    /// Adds a node to the node pool, replacing a node with the same URL origin.
+    /// Expected response: [`Ok`](crate::message_interface::Response::Ok)
+    AddNode {
+        /// The node url or the node with its auth options
+        node: NodeDto,
+    },
+    /// Removes a node from the node pool.
+    /// Expected response: [`NodeRemoved`](crate::message_interface::Response::NodeRemoved)
+    RemoveNode {
+        /// Url
+        url: String,
+    },
+    /// Sets or unsets the primary node.
+    /// Expected response: [`Ok`](crate::message_interface::Response::Ok)
+    SetPrimaryNode {
+        /// The node url or the node with its auth options, `None` to unset the primary node
+        node: Option<NodeDto>,
+    },
     /// Get the ledger status
     /// Expected response: [`LedgerNanoStatus`](crate::message_interface::Response::LedgerNanoStatus)
     #[cfg(feature = "ledger_nano")]
@@ -196,6 +217,41 @@ pub enum Message {
     GetInfo,
     /// Get peers
     GetPeers,
+    /// Get a peer of the node by its ID. Needs a JWT for the node.
+    /// Expected response: [`Peer`](crate::message_interface::Response::Peer)
+    GetPeer {
+        /// Peer ID
+        #[serde(rename = "peerId")]
+        peer_id: String,
+    },
+    /// Adds a peer to the node by its multiaddress. Needs a JWT for the node.
+    /// Expected response: [`Peer`](crate::message_interface::Response::Peer)
+    AddPeer {
+        /// Multiaddress of the peer
+        #[serde(rename = "multiAddress")]
+        multi_address: String,
+        /// Alias of the peer
+        alias: Option<String>,
+    },
+    /// Removes a peer from the node. Needs a JWT for the node.
+    /// Expected response: [`Ok`](crate::message_interface::Response::Ok)
+    RemovePeer {
+        /// Peer ID
+        #[serde(rename = "peerId")]
+        peer_id: String,
+    },
+    /// Prunes the database of the node. Needs a JWT for the node.
+    /// Expected response: [`PrunedDatabase`](crate::message_interface::Response::PrunedDatabase)
+    PruneDatabase {
+        /// What to prune
+        options: PruneDatabaseOptions,
+    },
+    /// Creates a snapshot at a milestone index. Needs a JWT for the node.
+    /// Expected response: [`CreatedSnapshot`](crate::message_interface::Response::CreatedSnapshot)
+    CreateSnapshot {
+        /// Milestone index
+        index: u32,
+    },
     /// Get tips
     GetTips,
     /// Post block (JSON)
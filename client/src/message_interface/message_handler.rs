@@ -5,19 +5,22 @@ use std::{any::Any, panic::AssertUnwindSafe};
 
 use backtrace::Backtrace;
 use futures::{Future, FutureExt};
-use iota_types::block::{
-    address::dto::AddressDto,
-    input::dto::UtxoInputDto,
-    output::{
-        dto::{OutputBuilderAmountDto, OutputDto, RentStructureDto},
-        AliasId, AliasOutput, BasicOutput, FoundryId, FoundryOutput, NftId, NftOutput, Output,
+use iota_types::{
+    api::response::BlockMetadataResponse,
+    block::{
+        address::dto::AddressDto,
+        input::dto::UtxoInputDto,
+        output::{
+            dto::{OutputBuilderAmountDto, OutputDto, RentStructureDto},
+            AliasId, AliasOutput, BasicOutput, FoundryId, FoundryOutput, NftId, NftOutput, Output,
+        },
+        payload::{
+            dto::{MilestonePayloadDto, PayloadDto},
+            Payload, TransactionPayload,
+        },
+        protocol::dto::ProtocolParametersDto,
+        Block, BlockDto,
     },
-    payload::{
-        dto::{MilestonePayloadDto, PayloadDto},
-        Payload, TransactionPayload,
-    },
-    protocol::dto::ProtocolParametersDto,
-    Block, BlockDto,
 };
 use tokio::sync::mpsc::UnboundedSender;
 use zeroize::Zeroize;
@@ -387,13 +390,33 @@ impl ClientMessageHandler {
                 Ok(Response::BlockIdWithBlock(block_id, BlockDto::from(&block)))
             }
             #[cfg(not(target_family = "wasm"))]
-            Message::UnhealthyNodes => Ok(Response::UnhealthyNodes(
-                self.client.unhealthy_nodes().into_iter().cloned().collect(),
-            )),
+            Message::UnhealthyNodes => Ok(Response::UnhealthyNodes(self.client.unhealthy_nodes())),
+            Message::ListNodes => Ok(Response::Nodes(self.client.list_nodes()?)),
+            Message::AddNode { node } => {
+                self.client.add_node(node).await?;
+                Ok(Response::Ok)
+            }
+            Message::RemoveNode { url } => Ok(Response::NodeRemoved(self.client.remove_node(&url)?)),
+            Message::SetPrimaryNode { node } => {
+                self.client.set_primary_node(node).await?;
+                Ok(Response::Ok)
+            }
             Message::GetHealth { url } => Ok(Response::Health(self.client.get_health(&url).await?)),
             Message::GetNodeInfo { url, auth } => Ok(Response::NodeInfo(Client::get_node_info(&url, auth).await?)),
             Message::GetInfo => Ok(Response::Info(self.client.get_info().await?)),
             Message::GetPeers => Ok(Response::Peers(self.client.get_peers().await?)),
+            Message::GetPeer { peer_id } => Ok(Response::Peer(self.client.get_peer(&peer_id).await?)),
+            Message::AddPeer { multi_address, alias } => Ok(Response::Peer(
+                self.client.add_peer(&multi_address, alias.as_deref()).await?,
+            )),
+            Message::RemovePeer { peer_id } => {
+                self.client.remove_peer(&peer_id).await?;
+                Ok(Response::Ok)
+            }
+            Message::PruneDatabase { options } => {
+                Ok(Response::PrunedDatabase(self.client.prune_database(options).await?))
+            }
+            Message::CreateSnapshot { index } => Ok(Response::CreatedSnapshot(self.client.create_snapshot(index).await?)),
             Message::GetTips => Ok(Response::Tips(self.client.get_tips().await?)),
             Message::PostBlockRaw { block_bytes } => Ok(Response::BlockId(
                 self.client
@@ -414,9 +437,9 @@ impl ClientMessageHandler {
             Message::GetBlock { block_id } => Ok(Response::Block(BlockDto::from(
                 &self.client.get_block(&block_id).await?,
             ))),
-            Message::GetBlockMetadata { block_id } => Ok(Response::BlockMetadata(
-                self.client.get_block_metadata(&block_id).await?,
-            )),
+            Message::GetBlockMetadata { block_id } => Ok(Response::BlockMetadata(BlockMetadataResponse::from(
+                &self.client.get_block_metadata(&block_id).await?,
+            ))),
             Message::GetBlockRaw { block_id } => Ok(Response::BlockRaw(self.client.get_block_raw(&block_id).await?)),
             Message::GetOutput { output_id } => Ok(Response::Output(self.client.get_output(&output_id).await?)),
             Message::GetOutputMetadata { output_id } => Ok(Response::OutputMetadata(
@@ -8,8 +8,9 @@ use iota_types::{
     api::{
         dto::{PeerDto, ReceiptDto},
         response::{
-            BlockMetadataResponse, InfoResponse as NodeInfo, OutputMetadataResponse, OutputWithMetadataResponse,
-            TreasuryResponse, UtxoChangesResponse as MilestoneUTXOChanges,
+            BlockMetadataResponse, CreateSnapshotResponse, InfoResponse as NodeInfo, OutputMetadataResponse,
+            OutputWithMetadataResponse, PruneDatabaseResponse, TreasuryResponse,
+            UtxoChangesResponse as MilestoneUTXOChanges,
         },
     },
     block::{
@@ -85,6 +86,12 @@ pub enum Response {
     #[cfg(not(target_family = "wasm"))]
     UnhealthyNodes(HashSet<Node>),
     /// Response for:
+    /// - [`ListNodes`](crate::message_interface::Message::ListNodes)
+    Nodes(Vec<Node>),
+    /// Response for:
+    /// - [`RemoveNode`](crate::message_interface::Message::RemoveNode)
+    NodeRemoved(bool),
+    /// Response for:
     /// - [`GetHealth`](crate::message_interface::Message::GetHealth)
     Health(bool),
     /// Response for:
@@ -97,6 +104,16 @@ pub enum Response {
     /// - [`GetPeers`](crate::message_interface::Message::GetPeers)
     Peers(Vec<PeerDto>),
     /// Response for:
+    /// - [`GetPeer`](crate::message_interface::Message::GetPeer)
+    /// - [`AddPeer`](crate::message_interface::Message::AddPeer)
+    Peer(PeerDto),
+    /// Response for:
+    /// - [`PruneDatabase`](crate::message_interface::Message::PruneDatabase)
+    PrunedDatabase(PruneDatabaseResponse),
+    /// Response for:
+    /// - [`CreateSnapshot`](crate::message_interface::Message::CreateSnapshot)
+    CreatedSnapshot(CreateSnapshotResponse),
+    /// Response for:
     /// - [`GetTips`](crate::message_interface::Message::GetTips)
     Tips(Vec<BlockId>),
     /// Response for:
@@ -216,6 +233,9 @@ pub enum Response {
     Faucet(String),
     /// Response for:
     /// - [`StoreMnemonic`](crate::message_interface::Message::StoreMnemonic)
+    /// - [`AddNode`](crate::message_interface::Message::AddNode)
+    /// - [`SetPrimaryNode`](crate::message_interface::Message::SetPrimaryNode)
+    /// - [`RemovePeer`](crate::message_interface::Message::RemovePeer)
     Ok,
     /// Response for any method that returns an error.
     Error(Error),
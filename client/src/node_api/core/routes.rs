@@ -9,12 +9,14 @@ use iota_types::{
     api::{
         dto::{PeerDto, ReceiptDto},
         response::{
-            BlockMetadataResponse, BlockResponse, InfoResponse, MilestoneResponse, OutputMetadataResponse,
-            OutputWithMetadataResponse, PeersResponse, ReceiptsResponse, RoutesResponse, SubmitBlockResponse,
-            TipsResponse, TreasuryResponse, UtxoChangesResponse,
+            AddPeerResponse, BlockMetadataResponse, BlockResponse, CreateSnapshotResponse, InfoResponse,
+            MilestoneResponse, OutputMetadataResponse, OutputWithMetadataResponse, PeerResponse, PeersResponse,
+            PruneDatabaseResponse, ReceiptsResponse, RoutesResponse, SubmitBlockResponse, TipsResponse,
+            TreasuryResponse, UtxoChangesResponse,
         },
     },
     block::{
+        metadata::BlockMetadata,
         output::OutputId,
         payload::{
             milestone::{MilestoneId, MilestonePayload},
@@ -27,11 +29,28 @@ use packable::PackableExt;
 use url::Url;
 
 use crate::{
-    constants::{DEFAULT_API_TIMEOUT, DEFAULT_USER_AGENT},
-    node_manager::node::{Node, NodeAuth},
+    client::{CacheKey, CachedResource},
+    constants::{DEFAULT_API_TIMEOUT, DEFAULT_MANAGEMENT_TIMEOUT, DEFAULT_USER_AGENT},
+    node_manager::{
+        http_client::{HttpClient, HttpMethod},
+        node::{Node, NodeAuth},
+        observer::RequestAttempt,
+    },
     Client, Error, Result,
 };
 
+/// What to prune with [`Client::prune_database()`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PruneDatabaseOptions {
+    /// Prunes the milestones up to this index.
+    Index(u32),
+    /// Prunes the milestones that are older than this number of milestones.
+    Depth(u32),
+    /// Prunes the oldest milestones until the database is smaller than this size, like `"30GB"`.
+    TargetDatabaseSize(String),
+}
+
 /// NodeInfo wrapper which contains the node info and the url from the node (useful when multiple nodes are used)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NodeInfoWrapper {
@@ -52,14 +71,17 @@ impl Client {
 
         let mut url = Url::parse(url)?;
         url.set_path(path);
-        let status = crate::node_manager::http_client::HttpClient::new(DEFAULT_USER_AGENT.to_string())
+        let status = self
+            .node_manager
+            .http_client
             .get(
                 Node {
                     url,
                     auth: None,
                     disabled: false,
                 },
-                DEFAULT_API_TIMEOUT,
+                self.node_manager.timeout(path, true, DEFAULT_API_TIMEOUT),
+                RequestAttempt::First,
             )
             .await?
             .status();
@@ -92,19 +114,20 @@ impl Client {
 
     /// GET /api/core/v2/info endpoint
     pub async fn get_node_info(url: &str, auth: Option<NodeAuth>) -> Result<InfoResponse> {
+        Self::get_node_info_with_http_client(&HttpClient::new(DEFAULT_USER_AGENT.to_string()), url, auth).await
+    }
+
+    /// GET /api/core/v2/info endpoint, using the provided HTTP client
+    pub(crate) async fn get_node_info_with_http_client(
+        http_client: &HttpClient,
+        url: &str,
+        auth: Option<NodeAuth>,
+    ) -> Result<InfoResponse> {
         let mut url = crate::node_manager::builder::validate_url(Url::parse(url)?)?;
-        if let Some(auth) = &auth {
-            if let Some((name, password)) = &auth.basic_auth_name_pwd {
-                url.set_username(name)
-                    .map_err(|_| crate::Error::UrlAuthError("username"))?;
-                url.set_password(Some(password))
-                    .map_err(|_| crate::Error::UrlAuthError("password"))?;
-            }
-        }
         let path = "api/core/v2/info";
         url.set_path(path);
 
-        let resp: InfoResponse = crate::node_manager::http_client::HttpClient::new(DEFAULT_USER_AGENT.to_string())
+        let resp: InfoResponse = http_client
             .get(
                 Node {
                     url,
@@ -112,10 +135,10 @@ impl Client {
                     disabled: false,
                 },
                 DEFAULT_API_TIMEOUT,
+                RequestAttempt::First,
             )
             .await?
-            .into_json()
-            .await?;
+            .into_json()?;
 
         Ok(resp)
     }
@@ -280,6 +303,10 @@ impl Client {
     /// Finds a block by its BlockId. This method returns the given block object.
     /// GET /api/core/v2/blocks/{BlockId}
     pub async fn get_block(&self, block_id: &BlockId) -> Result<Block> {
+        if let Some(CachedResource::Block(block)) = self.get_cached(&CacheKey::Block(*block_id)) {
+            return Ok(block);
+        }
+
         let path = &format!("api/core/v2/blocks/{}", block_id);
 
         let resp = self
@@ -287,10 +314,13 @@ impl Client {
             .get_request::<BlockResponse>(path, None, self.get_timeout(), false, true)
             .await?;
 
-        match resp {
-            BlockResponse::Json(dto) => Ok(Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?),
-            BlockResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
-        }
+        let block = match resp {
+            BlockResponse::Json(dto) => Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?,
+            BlockResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+        self.insert_cached(CacheKey::Block(*block_id), CachedResource::Block(block.clone()));
+
+        Ok(block)
     }
 
     /// Finds a block by its BlockId. This method returns the given block raw data.
@@ -305,12 +335,15 @@ impl Client {
 
     /// Returns the metadata of a block.
     /// GET /api/core/v2/blocks/{BlockId}/metadata
-    pub async fn get_block_metadata(&self, block_id: &BlockId) -> Result<BlockMetadataResponse> {
+    pub async fn get_block_metadata(&self, block_id: &BlockId) -> Result<BlockMetadata> {
         let path = &format!("api/core/v2/blocks/{}/metadata", block_id);
 
-        self.node_manager
-            .get_request(path, None, self.get_timeout(), true, true)
-            .await
+        let response = self
+            .node_manager
+            .get_request::<BlockMetadataResponse>(path, None, self.get_timeout(), true, true)
+            .await?;
+
+        Ok(BlockMetadata::try_from(&response)?)
     }
 
     // UTXO routes.
@@ -321,7 +354,7 @@ impl Client {
         let path = &format!("api/core/v2/outputs/{}", output_id);
 
         self.node_manager
-            .get_request(path, None, self.get_timeout(), false, true)
+            .get_request(path, None, self.get_timeout(), true, true)
             .await
     }
 
@@ -341,7 +374,7 @@ impl Client {
         let path = &format!("api/core/v2/outputs/{}/metadata", output_id);
 
         self.node_manager
-            .get_request::<OutputMetadataResponse>(path, None, self.get_timeout(), false, true)
+            .get_request::<OutputMetadataResponse>(path, None, self.get_timeout(), true, true)
             .await
     }
 
@@ -385,6 +418,11 @@ impl Client {
     /// Returns the block, as object, that was included in the ledger for a given TransactionId.
     /// GET /api/core/v2/transactions/{transactionId}/included-block
     pub async fn get_included_block(&self, transaction_id: &TransactionId) -> Result<Block> {
+        let key = CacheKey::IncludedBlock(*transaction_id);
+        if let Some(CachedResource::Block(block)) = self.get_cached(&key) {
+            return Ok(block);
+        }
+
         let path = &format!("api/core/v2/transactions/{}/included-block", transaction_id);
 
         let resp = self
@@ -392,10 +430,13 @@ impl Client {
             .get_request::<BlockResponse>(path, None, self.get_timeout(), true, true)
             .await?;
 
-        match resp {
-            BlockResponse::Json(dto) => Ok(Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?),
-            BlockResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
-        }
+        let block = match resp {
+            BlockResponse::Json(dto) => Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?,
+            BlockResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+        self.insert_cached(key, CachedResource::Block(block.clone()));
+
+        Ok(block)
     }
 
     /// Returns the block, as raw bytes, that was included in the ledger for a given TransactionId.
@@ -413,6 +454,10 @@ impl Client {
     /// Gets the milestone by the given milestone id.
     /// GET /api/core/v2/milestones/{milestoneId}
     pub async fn get_milestone_by_id(&self, milestone_id: &MilestoneId) -> Result<MilestonePayload> {
+        if let Some(CachedResource::Milestone(milestone)) = self.get_cached(&CacheKey::Milestone(*milestone_id)) {
+            return Ok(milestone);
+        }
+
         let path = &format!("api/core/v2/milestones/{}", milestone_id);
 
         let resp = self
@@ -420,13 +465,18 @@ impl Client {
             .get_request::<MilestoneResponse>(path, None, self.get_timeout(), false, true)
             .await?;
 
-        match resp {
-            MilestoneResponse::Json(dto) => Ok(MilestonePayload::try_from_dto(
-                &dto,
-                &self.get_protocol_parameters().await?,
-            )?),
-            MilestoneResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
-        }
+        let milestone = match resp {
+            MilestoneResponse::Json(dto) => {
+                MilestonePayload::try_from_dto(&dto, &self.get_protocol_parameters().await?)?
+            }
+            MilestoneResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+        self.insert_cached(
+            CacheKey::Milestone(*milestone_id),
+            CachedResource::Milestone(milestone.clone()),
+        );
+
+        Ok(milestone)
     }
 
     /// Gets the milestone by the given milestone id.
@@ -452,6 +502,10 @@ impl Client {
     /// Gets the milestone by the given milestone index.
     /// GET /api/core/v2/milestones/{index}
     pub async fn get_milestone_by_index(&self, index: u32) -> Result<MilestonePayload> {
+        if let Some(CachedResource::Milestone(milestone)) = self.get_cached(&CacheKey::MilestoneIndex(index)) {
+            return Ok(milestone);
+        }
+
         let path = &format!("api/core/v2/milestones/by-index/{}", index);
 
         let resp = self
@@ -459,13 +513,18 @@ impl Client {
             .get_request::<MilestoneResponse>(path, None, self.get_timeout(), false, true)
             .await?;
 
-        match resp {
-            MilestoneResponse::Json(dto) => Ok(MilestonePayload::try_from_dto(
-                &dto,
-                &self.get_protocol_parameters().await?,
-            )?),
-            MilestoneResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
-        }
+        let milestone = match resp {
+            MilestoneResponse::Json(dto) => {
+                MilestonePayload::try_from_dto(&dto, &self.get_protocol_parameters().await?)?
+            }
+            MilestoneResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+        self.insert_cached(
+            CacheKey::MilestoneIndex(index),
+            CachedResource::Milestone(milestone.clone()),
+        );
+
+        Ok(milestone)
     }
 
     /// Gets the milestone by the given milestone index.
@@ -502,23 +561,82 @@ impl Client {
         Ok(resp.0)
     }
 
-    // // RoutePeer is the route for getting peers by their peerID.
-    // // GET returns the peer
-    // // DELETE deletes the peer.
-    // RoutePeer = "/peers/:" + restapipkg.ParameterPeerID
+    // Management routes, which are protected by the node, so the node needs a JWT in its `NodeAuth`. They're only
+    // sent to a single node: the node of the request options, else the primary node, else the best ranked node.
+
+    /// GET /api/core/v2/peers/{peerId}
+    pub async fn get_peer(&self, peer_id: &str) -> Result<PeerDto> {
+        let path = &format!("api/core/v2/peers/{peer_id}");
+
+        let resp: PeerResponse = self
+            .node_manager
+            .management_request(HttpMethod::Get, path, None, self.get_timeout())
+            .await?
+            .into_json()?;
+
+        Ok(resp.0)
+    }
+
+    /// Adds a peer by its multiaddress, like `/ip4/127.0.0.1/tcp/15600/p2p/12D3KooW...`, with an optional alias.
+    /// POST /api/core/v2/peers
+    pub async fn add_peer(&self, multi_address: &str, alias: Option<&str>) -> Result<PeerDto> {
+        let path = "api/core/v2/peers";
+        let mut json = serde_json::json!({ "multiAddress": multi_address });
+        if let Some(alias) = alias {
+            json["alias"] = alias.into();
+        }
+
+        let resp: AddPeerResponse = self
+            .node_manager
+            .management_request(HttpMethod::Post, path, Some(json), self.get_timeout())
+            .await?
+            .into_json()?;
+
+        Ok(resp.0)
+    }
+
+    /// DELETE /api/core/v2/peers/{peerId}
+    pub async fn remove_peer(&self, peer_id: &str) -> Result<()> {
+        let path = &format!("api/core/v2/peers/{peer_id}");
 
-    // // RoutePeers is the route for getting all peers of the node.
-    // // GET returns a list of all peers.
-    // // POST adds a new peer.
-    // RoutePeers = "/peers"
+        self.node_manager
+            .management_request(HttpMethod::Delete, path, None, self.get_timeout())
+            .await?;
+
+        Ok(())
+    }
 
     // Control routes.
 
-    // // RouteControlDatabasePrune is the control route to manually prune the database.
-    // // POST prunes the database.
-    // RouteControlDatabasePrune = "/control/database/prune"
+    /// Prunes the database of the node, returns the index of the oldest milestone that is kept.
+    /// POST /api/core/v2/control/database/prune
+    pub async fn prune_database(&self, options: PruneDatabaseOptions) -> Result<PruneDatabaseResponse> {
+        let path = "api/core/v2/control/database/prune";
+
+        self.node_manager
+            .management_request(
+                HttpMethod::Post,
+                path,
+                Some(serde_json::to_value(options)?),
+                DEFAULT_MANAGEMENT_TIMEOUT,
+            )
+            .await?
+            .into_json()
+    }
+
+    /// Creates a full snapshot at the milestone index.
+    /// POST /api/core/v2/control/snapshots/create
+    pub async fn create_snapshot(&self, index: u32) -> Result<CreateSnapshotResponse> {
+        let path = "api/core/v2/control/snapshots/create";
 
-    // // RouteControlSnapshotsCreate is the control route to manually create a snapshot files.
-    // // POST creates a snapshot (full, delta or both).
-    // RouteControlSnapshotsCreate = "/control/snapshots/create"
+        self.node_manager
+            .management_request(
+                HttpMethod::Post,
+                path,
+                Some(serde_json::json!({ "index": index })),
+                DEFAULT_MANAGEMENT_TIMEOUT,
+            )
+            .await?
+            .into_json()
+    }
 }
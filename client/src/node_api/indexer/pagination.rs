@@ -0,0 +1,208 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lazy pagination of indexer queries
+
+use std::{fmt, str::FromStr};
+
+use futures::Stream;
+use iota_types::{api::response::OutputWithMetadataResponse, block::output::OutputId};
+
+use crate::{
+    node_api::indexer::{OutputIdsResponse, QueryParameter, QueryParameters},
+    Client, Result,
+};
+
+/// Position of an indexer query, returned by the node with every page that isn't the last one. It can be stored to
+/// resume the query later with [`OutputIdsPaginator::with_cursor()`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Cursor(String);
+
+impl Cursor {
+    /// Creates a cursor from the value returned by the node.
+    pub fn new(cursor: impl Into<String>) -> Self {
+        Self(cursor.into())
+    }
+
+    /// Returns the cursor as it's sent to the node.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A page of output IDs of an indexer query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputIdsPage {
+    /// The ledger index at which the output IDs were collected.
+    pub ledger_index: u32,
+    /// The output IDs of the page.
+    pub items: Vec<OutputId>,
+    /// The cursor of the next page, `None` if this is the last page.
+    pub cursor: Option<Cursor>,
+}
+
+/// A page of outputs of an indexer query, with their metadata.
+#[derive(Clone, Debug)]
+pub struct OutputsPage {
+    /// The ledger index at which the output IDs were collected.
+    pub ledger_index: u32,
+    /// The outputs of the page, in the order of their output IDs.
+    pub items: Vec<OutputWithMetadataResponse>,
+    /// The cursor of the next page, `None` if this is the last page.
+    pub cursor: Option<Cursor>,
+}
+
+/// Requests the pages of an indexer query one after the other, only when they're needed. The cursor only moves when
+/// a page was requested successfully, so after an error the same page is requested again.
+#[derive(Debug)]
+pub struct OutputIdsPaginator<'a> {
+    client: &'a Client,
+    route: String,
+    query_parameters: QueryParameters,
+    need_quorum: bool,
+    prefer_permanode: bool,
+    cursor: Option<Cursor>,
+    finished: bool,
+}
+
+impl<'a> OutputIdsPaginator<'a> {
+    pub(crate) fn new(
+        client: &'a Client,
+        route: impl Into<String>,
+        query_parameters: Vec<QueryParameter>,
+        need_quorum: bool,
+        prefer_permanode: bool,
+    ) -> Self {
+        let cursor = query_parameters.iter().find_map(|query_parameter| match query_parameter {
+            QueryParameter::Cursor(cursor) => Some(Cursor::new(cursor.clone())),
+            _ => None,
+        });
+
+        Self {
+            client,
+            route: route.into(),
+            query_parameters: QueryParameters::new(query_parameters),
+            need_quorum,
+            prefer_permanode,
+            cursor,
+            finished: false,
+        }
+    }
+
+    /// Continues the query from the given cursor, for example one that was stored from an interrupted query.
+    #[must_use]
+    pub fn with_cursor(mut self, cursor: Cursor) -> Self {
+        self.cursor.replace(cursor);
+        self.finished = false;
+        self
+    }
+
+    /// Returns the cursor of the next page, `None` before the first page and after the last one.
+    pub fn cursor(&self) -> Option<&Cursor> {
+        self.cursor.as_ref()
+    }
+
+    /// Returns if the last page was already returned.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Requests the next page of output IDs, returns `None` after the last page.
+    pub async fn next_page(&mut self) -> Result<Option<OutputIdsPage>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        if let Some(cursor) = &self.cursor {
+            self.query_parameters
+                .replace(QueryParameter::Cursor(cursor.as_str().to_string()));
+        }
+
+        let response = self
+            .client
+            .node_manager
+            .get_request::<OutputIdsResponse>(
+                &self.route,
+                self.query_parameters.to_query_string().as_deref(),
+                self.client.get_timeout(),
+                self.need_quorum,
+                self.prefer_permanode,
+            )
+            .await?;
+
+        let items = response
+            .items
+            .iter()
+            .map(|output_id| OutputId::from_str(output_id))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        self.cursor = response.cursor.map(Cursor::new);
+        self.finished = self.cursor.is_none();
+
+        Ok(Some(OutputIdsPage {
+            ledger_index: response.ledger_index,
+            items,
+            cursor: self.cursor.clone(),
+        }))
+    }
+
+    /// Requests the next page of output IDs and the outputs with their metadata, returns `None` after the last page.
+    /// If requesting the outputs fails, the cursor stays at the page.
+    pub async fn next_outputs_page(&mut self) -> Result<Option<OutputsPage>> {
+        let (cursor, finished) = (self.cursor.clone(), self.finished);
+
+        let page = match self.next_page().await? {
+            Some(page) => page,
+            None => return Ok(None),
+        };
+
+        match self.client.get_outputs(page.items).await {
+            Ok(items) => Ok(Some(OutputsPage {
+                ledger_index: page.ledger_index,
+                items,
+                cursor: page.cursor,
+            })),
+            Err(error) => {
+                self.cursor = cursor;
+                self.finished = finished;
+                Err(error)
+            }
+        }
+    }
+
+    /// Converts the paginator into a stream of output ID pages. The stream ends after an error, the cursor of the last
+    /// returned page can be used to resume the query.
+    pub fn into_stream(self) -> impl Stream<Item = Result<OutputIdsPage>> + 'a {
+        futures::stream::try_unfold(self, |mut paginator| async move {
+            Ok(paginator.next_page().await?.map(|page| (page, paginator)))
+        })
+    }
+
+    /// Converts the paginator into a stream of output pages. The stream ends after an error, the cursor of the last
+    /// returned page can be used to resume the query.
+    pub fn into_outputs_stream(self) -> impl Stream<Item = Result<OutputsPage>> + 'a {
+        futures::stream::try_unfold(self, |mut paginator| async move {
+            Ok(paginator.next_outputs_page().await?.map(|page| (page, paginator)))
+        })
+    }
+}
+
+impl Client {
+    /// Creates a paginator for the indexer query with the given URL route and query parameters, which requests the
+    /// pages only when they're needed, instead of collecting all output IDs like
+    /// [`Client::get_output_ids_with_pagination()`].
+    pub fn output_ids_paginator(
+        &self,
+        route: impl Into<String>,
+        query_parameters: Vec<QueryParameter>,
+    ) -> OutputIdsPaginator<'_> {
+        OutputIdsPaginator::new(self, route, query_parameters, true, false)
+    }
+}
@@ -0,0 +1,452 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed queries for the indexer output routes, which only allow the filters that the route supports
+
+use iota_types::block::{
+    address::{Address, AliasAddress},
+    output::OutputId,
+};
+
+use crate::{
+    node_api::indexer::{
+        pagination::{Cursor, OutputIdsPaginator},
+        QueryParameter,
+    },
+    Client, Error, Result,
+};
+
+/// A typed query of an indexer output route, like [`BasicOutputQuery`].
+pub trait OutputQuery {
+    /// Returns the route of the query, like `api/indexer/v1/outputs/basic`.
+    fn route(&self) -> &'static str;
+
+    /// Validates the filters and converts them to query parameters, with the addresses encoded with the bech32 HRP.
+    fn query_parameters(&self, bech32_hrp: &str) -> Result<Vec<QueryParameter>>;
+}
+
+// The filters of all queries, each query only exposes the ones its route supports.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Filters {
+    address: Option<Address>,
+    alias_address: Option<AliasAddress>,
+    state_controller: Option<Address>,
+    governor: Option<Address>,
+    issuer: Option<Address>,
+    sender: Option<Address>,
+    has_native_tokens: Option<bool>,
+    min_native_token_count: Option<u32>,
+    max_native_token_count: Option<u32>,
+    has_storage_deposit_return: Option<bool>,
+    storage_deposit_return_address: Option<Address>,
+    has_timelock: Option<bool>,
+    timelocked_before: Option<u32>,
+    timelocked_after: Option<u32>,
+    has_expiration: Option<bool>,
+    expires_before: Option<u32>,
+    expires_after: Option<u32>,
+    expiration_return_address: Option<Address>,
+    tag: Option<Vec<u8>>,
+    created_before: Option<u32>,
+    created_after: Option<u32>,
+    page_size: Option<usize>,
+    cursor: Option<Cursor>,
+}
+
+// The lower bound must be before the upper bound, the bounds are exclusive.
+fn validate_range(after: Option<u32>, before: Option<u32>, after_name: &str, before_name: &str) -> Result<()> {
+    match (after, before) {
+        (Some(after), Some(before)) if after >= before => Err(Error::InvalidOutputQuery(format!(
+            "{after_name} {after} must be lower than {before_name} {before}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// A filter that requires a condition can't be combined with a filter excluding the condition.
+fn validate_requirement(present: bool, has_condition: Option<bool>, name: &str, condition_name: &str) -> Result<()> {
+    if present && has_condition == Some(false) {
+        Err(Error::InvalidOutputQuery(format!(
+            "{name} can't be combined with {condition_name} false"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+impl Filters {
+    fn validate(&self) -> Result<()> {
+        validate_range(self.created_after, self.created_before, "createdAfter", "createdBefore")?;
+        validate_range(
+            self.timelocked_after,
+            self.timelocked_before,
+            "timelockedAfter",
+            "timelockedBefore",
+        )?;
+        validate_range(self.expires_after, self.expires_before, "expiresAfter", "expiresBefore")?;
+
+        if let (Some(min), Some(max)) = (self.min_native_token_count, self.max_native_token_count) {
+            if min > max {
+                return Err(Error::InvalidOutputQuery(format!(
+                    "minNativeTokenCount {min} must not be greater than maxNativeTokenCount {max}"
+                )));
+            }
+        }
+        validate_requirement(
+            self.min_native_token_count.unwrap_or_default() > 0,
+            self.has_native_tokens,
+            "minNativeTokenCount",
+            "hasNativeTokens",
+        )?;
+        validate_requirement(
+            self.storage_deposit_return_address.is_some(),
+            self.has_storage_deposit_return,
+            "storageDepositReturnAddress",
+            "hasStorageDepositReturn",
+        )?;
+        validate_requirement(
+            self.timelocked_before.is_some() || self.timelocked_after.is_some(),
+            self.has_timelock,
+            "timelockedBefore/timelockedAfter",
+            "hasTimelock",
+        )?;
+        validate_requirement(
+            self.expires_before.is_some() || self.expires_after.is_some() || self.expiration_return_address.is_some(),
+            self.has_expiration,
+            "expiresBefore/expiresAfter/expirationReturnAddress",
+            "hasExpiration",
+        )?;
+
+        if self.page_size == Some(0) {
+            return Err(Error::InvalidOutputQuery("pageSize must not be 0".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn query_parameters(&self, bech32_hrp: &str) -> Result<Vec<QueryParameter>> {
+        self.validate()?;
+
+        let bech32 = |address: &Address| address.to_bech32(bech32_hrp);
+        let parameters = [
+            self.address.as_ref().map(bech32).map(QueryParameter::Address),
+            self.alias_address
+                .map(|address| bech32(&Address::Alias(address)))
+                .map(QueryParameter::AliasAddress),
+            self.state_controller
+                .as_ref()
+                .map(bech32)
+                .map(QueryParameter::StateController),
+            self.governor.as_ref().map(bech32).map(QueryParameter::Governor),
+            self.issuer.as_ref().map(bech32).map(QueryParameter::Issuer),
+            self.sender.as_ref().map(bech32).map(QueryParameter::Sender),
+            self.has_native_tokens.map(QueryParameter::HasNativeTokens),
+            self.min_native_token_count.map(QueryParameter::MinNativeTokenCount),
+            self.max_native_token_count.map(QueryParameter::MaxNativeTokenCount),
+            self.has_storage_deposit_return.map(QueryParameter::HasStorageDepositReturn),
+            self.storage_deposit_return_address
+                .as_ref()
+                .map(bech32)
+                .map(QueryParameter::StorageDepositReturnAddress),
+            self.has_timelock.map(QueryParameter::HasTimelock),
+            self.timelocked_before.map(QueryParameter::TimelockedBefore),
+            self.timelocked_after.map(QueryParameter::TimelockedAfter),
+            self.has_expiration.map(QueryParameter::HasExpiration),
+            self.expires_before.map(QueryParameter::ExpiresBefore),
+            self.expires_after.map(QueryParameter::ExpiresAfter),
+            self.expiration_return_address
+                .as_ref()
+                .map(bech32)
+                .map(QueryParameter::ExpirationReturnAddress),
+            self.tag.as_deref().map(prefix_hex::encode).map(QueryParameter::Tag),
+            self.created_before.map(QueryParameter::CreatedBefore),
+            self.created_after.map(QueryParameter::CreatedAfter),
+            self.page_size.map(QueryParameter::PageSize),
+            self.cursor
+                .as_ref()
+                .map(|cursor| QueryParameter::Cursor(cursor.as_str().to_string())),
+        ];
+
+        Ok(parameters.into_iter().flatten().collect())
+    }
+}
+
+// Defines a query with the builder methods of the given filters.
+macro_rules! output_query {
+    ($(#[$meta:meta])* $query:ident, $route:literal, [$($filter:ident),+ $(,)?]) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Default, PartialEq, Eq)]
+        pub struct $query(Filters);
+
+        impl $query {
+            /// Creates a query without filters.
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            $(output_query!(@$filter);)+
+        }
+
+        impl OutputQuery for $query {
+            fn route(&self) -> &'static str {
+                $route
+            }
+
+            fn query_parameters(&self, bech32_hrp: &str) -> Result<Vec<QueryParameter>> {
+                self.0.query_parameters(bech32_hrp)
+            }
+        }
+    };
+    (@$method:ident, $field:ident: $ty:ty, $doc:literal) => {
+        #[doc = $doc]
+        #[must_use]
+        pub fn $method(mut self, $field: $ty) -> Self {
+            self.0.$field.replace($field);
+            self
+        }
+    };
+    (@address) => {
+        output_query!(@with_address, address: Address, "Only returns outputs that are unlockable by the address.");
+    };
+    (@alias_address) => {
+        output_query!(
+            @with_alias_address,
+            alias_address: AliasAddress,
+            "Only returns outputs that are controlled by the alias address."
+        );
+    };
+    (@state_controller) => {
+        output_query!(
+            @with_state_controller,
+            state_controller: Address,
+            "Only returns outputs with the state controller address."
+        );
+    };
+    (@governor) => {
+        output_query!(@with_governor, governor: Address, "Only returns outputs with the governor address.");
+    };
+    (@issuer) => {
+        output_query!(@with_issuer, issuer: Address, "Only returns outputs with the issuer address.");
+    };
+    (@sender) => {
+        output_query!(@with_sender, sender: Address, "Only returns outputs with the validated sender address.");
+    };
+    (@has_native_tokens) => {
+        output_query!(
+            @with_has_native_tokens,
+            has_native_tokens: bool,
+            "Only returns outputs with or without native tokens."
+        );
+    };
+    (@min_native_token_count) => {
+        output_query!(
+            @with_min_native_token_count,
+            min_native_token_count: u32,
+            "Only returns outputs with at least this number of distinct native tokens."
+        );
+    };
+    (@max_native_token_count) => {
+        output_query!(
+            @with_max_native_token_count,
+            max_native_token_count: u32,
+            "Only returns outputs with at most this number of distinct native tokens."
+        );
+    };
+    (@has_storage_deposit_return) => {
+        output_query!(
+            @with_has_storage_deposit_return,
+            has_storage_deposit_return: bool,
+            "Only returns outputs with or without storage deposit return unlock condition."
+        );
+    };
+    (@storage_deposit_return_address) => {
+        output_query!(
+            @with_storage_deposit_return_address,
+            storage_deposit_return_address: Address,
+            "Only returns outputs with the return address in the storage deposit return unlock condition."
+        );
+    };
+    (@has_timelock) => {
+        output_query!(
+            @with_has_timelock,
+            has_timelock: bool,
+            "Only returns outputs with or without timelock unlock condition."
+        );
+    };
+    (@timelocked_before) => {
+        output_query!(
+            @with_timelocked_before,
+            timelocked_before: u32,
+            "Only returns outputs that are timelocked before the Unix timestamp."
+        );
+    };
+    (@timelocked_after) => {
+        output_query!(
+            @with_timelocked_after,
+            timelocked_after: u32,
+            "Only returns outputs that are timelocked after the Unix timestamp."
+        );
+    };
+    (@has_expiration) => {
+        output_query!(
+            @with_has_expiration,
+            has_expiration: bool,
+            "Only returns outputs with or without expiration unlock condition."
+        );
+    };
+    (@expires_before) => {
+        output_query!(
+            @with_expires_before,
+            expires_before: u32,
+            "Only returns outputs that expire before the Unix timestamp."
+        );
+    };
+    (@expires_after) => {
+        output_query!(
+            @with_expires_after,
+            expires_after: u32,
+            "Only returns outputs that expire after the Unix timestamp."
+        );
+    };
+    (@expiration_return_address) => {
+        output_query!(
+            @with_expiration_return_address,
+            expiration_return_address: Address,
+            "Only returns outputs with the return address in the expiration unlock condition."
+        );
+    };
+    (@tag) => {
+        output_query!(@with_tag, tag: Vec<u8>, "Only returns outputs with the tag feature.");
+    };
+    (@created_before) => {
+        output_query!(
+            @with_created_before,
+            created_before: u32,
+            "Only returns outputs that were created before the Unix timestamp."
+        );
+    };
+    (@created_after) => {
+        output_query!(
+            @with_created_after,
+            created_after: u32,
+            "Only returns outputs that were created after the Unix timestamp."
+        );
+    };
+    (@page_size) => {
+        output_query!(
+            @with_page_size,
+            page_size: usize,
+            "Sets the maximum number of output IDs of a page."
+        );
+    };
+    (@cursor) => {
+        output_query!(@with_cursor, cursor: Cursor, "Starts the query at the cursor of a previous page.");
+    };
+}
+
+output_query!(
+    /// Query of basic outputs, for `api/indexer/v1/outputs/basic`.
+    BasicOutputQuery,
+    "api/indexer/v1/outputs/basic",
+    [
+        address,
+        has_native_tokens,
+        min_native_token_count,
+        max_native_token_count,
+        has_storage_deposit_return,
+        storage_deposit_return_address,
+        has_timelock,
+        timelocked_before,
+        timelocked_after,
+        has_expiration,
+        expires_before,
+        expires_after,
+        expiration_return_address,
+        sender,
+        tag,
+        created_before,
+        created_after,
+        page_size,
+        cursor,
+    ]
+);
+
+output_query!(
+    /// Query of alias outputs, for `api/indexer/v1/outputs/alias`.
+    AliasOutputQuery,
+    "api/indexer/v1/outputs/alias",
+    [
+        state_controller,
+        governor,
+        issuer,
+        sender,
+        has_native_tokens,
+        min_native_token_count,
+        max_native_token_count,
+        created_before,
+        created_after,
+        page_size,
+        cursor,
+    ]
+);
+
+output_query!(
+    /// Query of foundry outputs, for `api/indexer/v1/outputs/foundry`.
+    FoundryOutputQuery,
+    "api/indexer/v1/outputs/foundry",
+    [
+        alias_address,
+        has_native_tokens,
+        min_native_token_count,
+        max_native_token_count,
+        created_before,
+        created_after,
+        page_size,
+        cursor,
+    ]
+);
+
+output_query!(
+    /// Query of NFT outputs, for `api/indexer/v1/outputs/nft`.
+    NftOutputQuery,
+    "api/indexer/v1/outputs/nft",
+    [
+        address,
+        has_native_tokens,
+        min_native_token_count,
+        max_native_token_count,
+        has_storage_deposit_return,
+        storage_deposit_return_address,
+        has_timelock,
+        timelocked_before,
+        timelocked_after,
+        has_expiration,
+        expires_before,
+        expires_after,
+        expiration_return_address,
+        issuer,
+        sender,
+        tag,
+        created_before,
+        created_after,
+        page_size,
+        cursor,
+    ]
+);
+
+impl Client {
+    /// Get all output IDs of a typed query, like [`BasicOutputQuery`].
+    pub async fn output_ids_by_query(&self, query: &impl OutputQuery) -> Result<Vec<OutputId>> {
+        let query_parameters = query.query_parameters(&self.get_bech32_hrp().await?)?;
+
+        self.get_output_ids_with_pagination(query.route(), query_parameters, true, false)
+            .await
+    }
+
+    /// Creates a paginator for a typed query, like [`BasicOutputQuery`].
+    pub async fn output_ids_paginator_by_query(&self, query: &impl OutputQuery) -> Result<OutputIdsPaginator<'_>> {
+        let query_parameters = query.query_parameters(&self.get_bech32_hrp().await?)?;
+
+        Ok(self.output_ids_paginator(query.route(), query_parameters))
+    }
+}
@@ -156,6 +156,7 @@ impl Client {
             QueryParameter::ExpiresBefore,
             QueryParameter::ExpiresAfter,
             QueryParameter::ExpirationReturnAddress,
+            QueryParameter::Issuer,
             QueryParameter::Sender,
             QueryParameter::Tag,
             QueryParameter::CreatedBefore,
@@ -9,3 +9,4 @@ pub mod indexer;
 pub mod mqtt;
 #[cfg(feature = "participation")]
 pub mod participation;
+pub mod poi;
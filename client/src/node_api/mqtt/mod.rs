@@ -51,6 +51,39 @@ impl Client {
         MqttManager::new(self).with_topics(topics).unsubscribe().await
     }
 
+    // Subscribe to MQTT events with a callback and return its handler, to unsubscribe only this callback later
+    pub(crate) async fn subscribe_handler<C: Fn(&TopicEvent) + Send + Sync + 'static>(
+        &mut self,
+        topics: Vec<Topic>,
+        callback: C,
+    ) -> crate::Result<Arc<TopicHandler>> {
+        let handler = Arc::new(Box::new(callback) as TopicHandler);
+        MqttManager::new(self)
+            .with_topics(topics)
+            .subscribe_handler(handler.clone())
+            .await?;
+
+        Ok(handler)
+    }
+
+    // Remove the handler from the topics, only the topics without other handlers are unsubscribed
+    pub(crate) async fn unsubscribe_handler(
+        &mut self,
+        topics: Vec<Topic>,
+        handler: &Arc<TopicHandler>,
+    ) -> crate::Result<()> {
+        let unused_topics = {
+            let mut mqtt_topic_handlers = self.mqtt_topic_handlers.write().await;
+            remove_topic_handler(&mut mqtt_topic_handlers, &topics, handler)
+        };
+
+        // Without topics, unsubscribe() would unsubscribe every topic
+        if unused_topics.is_empty() {
+            return Ok(());
+        }
+        self.unsubscribe(unused_topics).await
+    }
+
     /// Returns the mqtt event receiver.
     #[cfg(feature = "mqtt")]
     pub fn mqtt_event_receiver(&self) -> WatchReceiver<MqttEvent> {
@@ -58,6 +91,26 @@ impl Client {
     }
 }
 
+// Removes the handler from the topics and returns the topics that have no handlers anymore
+fn remove_topic_handler(
+    mqtt_topic_handlers: &mut TopicHandlerMap,
+    topics: &[Topic],
+    handler: &Arc<TopicHandler>,
+) -> Vec<Topic> {
+    let mut unused_topics = Vec::new();
+    for topic in topics {
+        if let Some(handlers) = mqtt_topic_handlers.get_mut(topic) {
+            handlers.retain(|topic_handler| !Arc::ptr_eq(topic_handler, handler));
+            if handlers.is_empty() {
+                mqtt_topic_handlers.remove(topic);
+                unused_topics.push(topic.clone());
+            }
+        }
+    }
+
+    unused_topics
+}
+
 async fn get_mqtt_client(client: &mut Client) -> Result<&mut MqttClient> {
     // if the client was disconnected, we clear it so we can start over
     if *client.mqtt_event_receiver().borrow() == MqttEvent::Disconnected {
@@ -73,16 +126,17 @@ async fn get_mqtt_client(client: &mut Client) -> Result<&mut MqttClient> {
                         .node_manager
                         .healthy_nodes
                         .read()
-                        .map_or(client.node_manager.nodes.clone(), |healthy_nodes| {
-                            healthy_nodes.iter().map(|(node, _)| node.clone()).collect()
-                        })
+                        .map_err(|_| crate::Error::PoisonError)?
+                        .iter()
+                        .map(|(node, _)| node.clone())
+                        .collect()
                 }
                 #[cfg(target_family = "wasm")]
                 {
-                    client.node_manager.nodes.clone()
+                    client.node_manager.nodes.read().map_err(|_| crate::Error::PoisonError)?.clone()
                 }
             } else {
-                client.node_manager.nodes.clone()
+                client.node_manager.nodes.read().map_err(|_| crate::Error::PoisonError)?.clone()
             };
             for node in &nodes {
                 let host = node.url.host_str().expect("can't get host from URL");
@@ -342,9 +396,12 @@ impl<'a> MqttTopicManager<'a> {
         self,
         callback: C,
     ) -> Result<()> {
+        self.subscribe_handler(Arc::new(Box::new(callback))).await
+    }
+
+    // Subscribe to the given topics with the handler.
+    async fn subscribe_handler(self, cb: Arc<TopicHandler>) -> Result<()> {
         let client = get_mqtt_client(self.client).await?;
-        let cb =
-            Arc::new(Box::new(callback) as Box<dyn Fn(&crate::node_api::mqtt::TopicEvent) + Send + Sync + 'static>);
         client
             .subscribe_many(
                 self.topics
@@ -403,3 +460,30 @@ impl<'a> MqttTopicManager<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_topic_handler_keeps_other_handlers() {
+        let shared_topic = Topic::new_unchecked("milestones/latest".to_string());
+        let own_topic = Topic::new_unchecked("milestones/confirmed".to_string());
+        let handler = Arc::new(Box::new(|_: &TopicEvent| {}) as TopicHandler);
+        let other_handler = Arc::new(Box::new(|_: &TopicEvent| {}) as TopicHandler);
+        let mut mqtt_topic_handlers = TopicHandlerMap::new();
+        mqtt_topic_handlers.insert(shared_topic.clone(), vec![other_handler.clone(), handler.clone()]);
+        mqtt_topic_handlers.insert(own_topic.clone(), vec![handler.clone()]);
+
+        let unused_topics = remove_topic_handler(
+            &mut mqtt_topic_handlers,
+            &[shared_topic.clone(), own_topic.clone()],
+            &handler,
+        );
+
+        assert_eq!(unused_topics, vec![own_topic]);
+        let shared_topic_handlers = &mqtt_topic_handlers[&shared_topic];
+        assert_eq!(shared_topic_handlers.len(), 1);
+        assert!(Arc::ptr_eq(&shared_topic_handlers[0], &other_handler));
+    }
+}
@@ -14,7 +14,7 @@ use serde_json::Value;
 
 use crate::Result;
 
-type TopicHandler = Box<dyn Fn(&TopicEvent) + Send + Sync>;
+pub(crate) type TopicHandler = Box<dyn Fn(&TopicEvent) + Send + Sync>;
 
 pub(crate) type TopicHandlerMap = HashMap<Topic, Vec<Arc<TopicHandler>>>;
 
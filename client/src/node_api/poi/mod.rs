@@ -0,0 +1,8 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Node proof of inclusion API.
+//! https://github.com/iotaledger/inx-poi
+
+pub mod proof;
+pub mod routes;
@@ -0,0 +1,112 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proof of inclusion of a block in the past cone of a milestone.
+
+use crypto::hashes::{blake2b::Blake2b256, Digest};
+use iota_types::block::{
+    payload::milestone::{dto::MilestonePayloadDto, MerkleRoot, MilestonePayload},
+    Block, BlockDto, BlockId,
+};
+
+use crate::{Error, MilestoneVerificationOptions, Result};
+
+// Domain separation of the merkle tree hashes, like in RFC 6962.
+const LEAF_HASH_PREFIX: u8 = 0;
+const NODE_HASH_PREFIX: u8 = 1;
+
+/// A path of a merkle tree of block IDs. The block ID that is proven is a value, the other subtrees are only
+/// represented by their hashes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MerkleProof {
+    /// A node of the tree.
+    Node {
+        /// The left subtree.
+        #[serde(rename = "l")]
+        left: Box<MerkleProof>,
+        /// The right subtree.
+        #[serde(rename = "r")]
+        right: Box<MerkleProof>,
+    },
+    /// The hex encoded hash of a subtree.
+    Hash {
+        /// The hash.
+        #[serde(rename = "h")]
+        hash: String,
+    },
+    /// A hex encoded block ID.
+    Value {
+        /// The block ID.
+        value: String,
+    },
+}
+
+impl MerkleProof {
+    /// Computes the merkle root of the tree.
+    pub fn hash(&self) -> Result<[u8; MerkleRoot::LENGTH]> {
+        let mut hasher = Blake2b256::new();
+
+        match self {
+            Self::Node { left, right } => {
+                hasher.update([NODE_HASH_PREFIX]);
+                hasher.update(left.hash()?);
+                hasher.update(right.hash()?);
+            }
+            Self::Hash { hash } => return Ok(prefix_hex::decode(hash)?),
+            Self::Value { value } => {
+                hasher.update([LEAF_HASH_PREFIX]);
+                hasher.update(prefix_hex::decode::<[u8; BlockId::LENGTH]>(value)?);
+            }
+        }
+
+        Ok(hasher.finalize().into())
+    }
+
+    /// Returns if the block ID is a value of the tree.
+    pub fn contains(&self, block_id: &BlockId) -> bool {
+        match self {
+            Self::Node { left, right } => left.contains(block_id) || right.contains(block_id),
+            Self::Hash { .. } => false,
+            Self::Value { value } => {
+                matches!(prefix_hex::decode::<[u8; BlockId::LENGTH]>(value), Ok(bytes) if bytes == **block_id)
+            }
+        }
+    }
+}
+
+/// The proof that a block was referenced by a milestone. It contains everything needed to verify it without a node,
+/// so it can be stored as evidence, for example of a payment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofOfInclusion {
+    /// The milestone that referenced the block.
+    pub milestone: MilestonePayloadDto,
+    /// The block.
+    pub block: BlockDto,
+    /// The path from the block ID to the inclusion merkle root of the milestone.
+    pub proof: MerkleProof,
+}
+
+impl ProofOfInclusion {
+    /// Verifies the proof: the block ID must be in the merkle tree, the merkle tree must hash to the inclusion merkle
+    /// root of the milestone and the milestone must be signed by the coordinator. Returns the ID of the proven block.
+    pub fn verify(&self, options: &MilestoneVerificationOptions) -> Result<BlockId> {
+        let block_id = Block::try_from_dto_unverified(&self.block)?.id();
+        if !self.proof.contains(&block_id) {
+            return Err(Error::InvalidProofOfInclusion(format!(
+                "block {block_id} isn't part of the merkle proof"
+            )));
+        }
+
+        let milestone = MilestonePayload::try_from_dto_unverified(&self.milestone)?;
+        if MerkleRoot::new(self.proof.hash()?) != *milestone.essence().inclusion_merkle_root() {
+            return Err(Error::InvalidProofOfInclusion(format!(
+                "the merkle proof doesn't match the inclusion merkle root of milestone {}",
+                milestone.essence().index()
+            )));
+        }
+        options.verify_signatures(&milestone)?;
+
+        Ok(block_id)
+    }
+}
@@ -0,0 +1,22 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! IOTA node proof of inclusion routes.
+//! https://github.com/iotaledger/inx-poi/blob/develop/core/poi/routes.go
+
+use iota_types::block::BlockId;
+
+use super::proof::ProofOfInclusion;
+use crate::{Client, Result};
+
+impl Client {
+    /// RouteCreateProof is the route to create the proof of inclusion of a block, which has to be referenced by a
+    /// milestone. Verify it with [`ProofOfInclusion::verify()`].
+    pub async fn create_proof_of_inclusion(&self, block_id: &BlockId) -> Result<ProofOfInclusion> {
+        let route = format!("api/poi/v1/create/{block_id}");
+
+        self.node_manager
+            .get_request(&route, None, self.get_timeout(), false, false)
+            .await
+    }
+}
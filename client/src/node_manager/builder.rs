@@ -9,7 +9,6 @@ use std::{
     time::Duration,
 };
 
-use iota_types::api::response::InfoResponse;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
@@ -17,8 +16,13 @@ use crate::{
     constants::{DEFAULT_MIN_QUORUM_SIZE, DEFAULT_QUORUM_THRESHOLD, DEFAULT_USER_AGENT, NODE_SYNC_INTERVAL},
     error::{Error, Result},
     node_manager::{
-        http_client::HttpClient,
-        node::{Node, NodeAuth, NodeDto},
+        circuit_breaker::CircuitBreakerOptions,
+        http_client::{HttpClient, HttpTransport, TransportHandle},
+        node::{HealthyNode, Node, NodeAuth, NodeDto},
+        observer::{ObserverHandle, RequestObserver},
+        request_options::RequestOptions,
+        retry::RetryOptions,
+        timeouts::RouteTimeouts,
         NodeManager,
     },
 };
@@ -57,6 +61,21 @@ pub struct NodeManagerBuilder {
     /// The User-Agent header for requests
     #[serde(rename = "userAgent", default = "default_user_agent")]
     pub user_agent: String,
+    /// Options for the circuit breaker, which stops using nodes after consecutive failed requests
+    #[serde(rename = "circuitBreakerOptions", default)]
+    pub circuit_breaker_options: CircuitBreakerOptions,
+    /// Options for retrying GET requests with exponential backoff, when all nodes failed
+    #[serde(rename = "retryOptions", default)]
+    pub retry_options: RetryOptions,
+    /// Timeouts of GET requests per route class, instead of the API timeout
+    #[serde(rename = "routeTimeouts", default)]
+    pub route_timeouts: RouteTimeouts,
+    /// The transport used to send requests, can't be serialized
+    #[serde(skip)]
+    pub(crate) transport: Option<TransportHandle>,
+    /// The observer of the requests, can't be serialized
+    #[serde(skip)]
+    pub(crate) observer: Option<ObserverHandle>,
 }
 
 fn default_user_agent() -> String {
@@ -217,11 +236,45 @@ impl NodeManagerBuilder {
         self
     }
 
-    pub(crate) fn build(self, healthy_nodes: Arc<RwLock<HashMap<Node, InfoResponse>>>) -> NodeManager {
+    pub(crate) fn with_circuit_breaker_options(mut self, options: CircuitBreakerOptions) -> Self {
+        self.circuit_breaker_options = options;
+        self
+    }
+
+    pub(crate) fn with_retry_options(mut self, options: RetryOptions) -> Self {
+        self.retry_options = options;
+        self
+    }
+
+    pub(crate) fn with_route_timeouts(mut self, route_timeouts: RouteTimeouts) -> Self {
+        self.route_timeouts = route_timeouts;
+        self
+    }
+
+    pub(crate) fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport.replace(TransportHandle(transport));
+        self
+    }
+
+    pub(crate) fn with_request_observer(mut self, observer: Arc<dyn RequestObserver>) -> Self {
+        self.observer.replace(ObserverHandle(observer));
+        self
+    }
+
+    pub(crate) fn http_client(&self) -> HttpClient {
+        let http_client = match &self.transport {
+            Some(transport) => HttpClient::with_transport(transport.0.clone(), self.user_agent.clone()),
+            None => HttpClient::new(self.user_agent.clone()),
+        };
+        http_client.with_observer(self.observer.as_ref().map(|observer| observer.0.clone()))
+    }
+
+    pub(crate) fn build(self, healthy_nodes: Arc<RwLock<HashMap<Node, HealthyNode>>>) -> NodeManager {
+        let http_client = self.http_client();
         NodeManager {
-            primary_node: self.primary_node.map(|node| node.into()),
+            primary_node: Arc::new(RwLock::new(self.primary_node.map(|node| node.into()))),
             primary_pow_node: self.primary_pow_node.map(|node| node.into()),
-            nodes: self.nodes.into_iter().map(|node| node.into()).collect(),
+            nodes: Arc::new(RwLock::new(self.nodes.into_iter().map(|node| node.into()).collect())),
             permanodes: self
                 .permanodes
                 .map(|nodes| nodes.into_iter().map(|node| node.into()).collect()),
@@ -231,7 +284,13 @@ impl NodeManagerBuilder {
             quorum: self.quorum,
             min_quorum_size: self.min_quorum_size,
             quorum_threshold: self.quorum_threshold,
-            http_client: HttpClient::new(self.user_agent),
+            circuit_breaker_options: self.circuit_breaker_options,
+            retry_options: self.retry_options,
+            route_timeouts: self.route_timeouts,
+            request_options: RequestOptions::default(),
+            http_client,
+            #[cfg(not(target_family = "wasm"))]
+            network_tracker: Default::default(),
         }
     }
 }
@@ -249,6 +308,11 @@ impl Default for NodeManagerBuilder {
             min_quorum_size: DEFAULT_MIN_QUORUM_SIZE,
             quorum_threshold: DEFAULT_QUORUM_THRESHOLD,
             user_agent: DEFAULT_USER_AGENT.to_string(),
+            circuit_breaker_options: CircuitBreakerOptions::default(),
+            retry_options: RetryOptions::default(),
+            route_timeouts: RouteTimeouts::default(),
+            transport: None,
+            observer: None,
         }
     }
 }
@@ -0,0 +1,102 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Circuit breaker that stops sending requests to a failing node until it had time to recover
+
+use std::time::Duration;
+
+use instant::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{DEFAULT_CIRCUIT_BREAKER_COOL_DOWN, DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD};
+
+/// Options for the circuit breaker of the nodes.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CircuitBreakerOptions {
+    /// Amount of consecutive failed requests after which the circuit of a node opens and it isn't used anymore, `0`
+    /// disables the circuit breaker.
+    pub failure_threshold: u32,
+    /// Duration for which a node isn't used after its circuit opened. Afterwards the circuit is half-open and a single
+    /// request is sent to the node to probe if it recovered.
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitBreakerOptions {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+            cool_down: DEFAULT_CIRCUIT_BREAKER_COOL_DOWN,
+        }
+    }
+}
+
+/// The state of the circuit of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CircuitState {
+    /// The node is used for requests.
+    Closed,
+    /// The node failed too often and isn't used until the cool-down passed.
+    Open,
+    /// The cool-down passed, the next request probes the node.
+    HalfOpen,
+}
+
+/// Per-node circuit breaker, counting the consecutive failed requests.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct CircuitBreaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_sent_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn state(&self, options: &CircuitBreakerOptions) -> CircuitState {
+        if options.failure_threshold == 0 {
+            return CircuitState::Closed;
+        }
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < options.cool_down => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Returns if a half-open circuit lets a probe request through and marks it as sent. A probe that never got a
+    /// response is considered lost after another cool-down, so the node gets probed again.
+    pub(crate) fn try_probe(&mut self, options: &CircuitBreakerOptions) -> bool {
+        if self.state(options) != CircuitState::HalfOpen {
+            return false;
+        }
+        match self.probe_sent_at {
+            Some(probe_sent_at) if probe_sent_at.elapsed() < options.cool_down => false,
+            _ => {
+                self.probe_sent_at.replace(Instant::now());
+                true
+            }
+        }
+    }
+
+    /// Records the outcome of a request, returns `true` if the circuit opened because of it.
+    pub(crate) fn record_request(&mut self, failed: bool, options: &CircuitBreakerOptions) -> bool {
+        self.probe_sent_at = None;
+        if !failed {
+            self.consecutive_failures = 0;
+            self.opened_at = None;
+            return false;
+        }
+
+        self.consecutive_failures += 1;
+        // A failed probe opens the circuit again, without waiting for the threshold
+        if options.failure_threshold != 0
+            && (self.opened_at.is_some() || self.consecutive_failures >= options.failure_threshold)
+        {
+            return self.opened_at.replace(Instant::now()).is_none();
+        }
+        false
+    }
+
+    pub(crate) fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+}
@@ -1,113 +1,366 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-//! The node manager that takes care of sending requests with healthy nodes and quorum if enabled
+//! The HTTP layer used by the node manager to send requests to the nodes
 
-use std::time::Duration;
+#[cfg(all(feature = "tls", not(target_family = "wasm")))]
+use std::{collections::HashMap, sync::Mutex};
+use std::{sync::Arc, time::Duration};
 
-use reqwest::RequestBuilder;
+use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
+use url::Url;
 
 use crate::{
     error::{Error, Result},
-    node_manager::node::Node,
+    node_manager::{
+        node::{ClientCertificate, Node},
+        observer::{route_template, RequestAttempt, RequestEvent, RequestObserver},
+    },
 };
-pub(crate) struct Response(reqwest::Response);
+
+/// The HTTP method of a [`HttpRequest`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    /// GET
+    Get,
+    /// POST
+    Post,
+    /// DELETE
+    Delete,
+}
+
+/// A request to a node, as it is handed to a [`HttpTransport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// The HTTP method.
+    pub method: HttpMethod,
+    /// The full URL, including path and query parameters.
+    pub url: Url,
+    /// The request headers.
+    pub headers: Vec<(String, String)>,
+    /// The request body.
+    pub body: Option<Vec<u8>>,
+    /// The timeout after which the request should be aborted.
+    pub timeout: Duration,
+    /// The client certificate for mutual TLS, if the node requires one.
+    pub client_certificate: Option<ClientCertificate>,
+}
+
+impl HttpRequest {
+    /// Returns the value of the first header with the given name, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response from a node, as it is returned by a [`HttpTransport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// The status code.
+    pub status: u16,
+    /// The raw response body.
+    pub body: Vec<u8>,
+}
+
+/// The transport used to send HTTP requests to the nodes.
+///
+/// [`ReqwestTransport`] is used by default, a different implementation can be set with
+/// [`ClientBuilder::with_transport()`](crate::ClientBuilder::with_transport), for example a
+/// `MockNode` of the `mock` feature to test against fixtures without a running node.
+#[cfg_attr(target_family = "wasm", async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait)]
+pub trait HttpTransport: std::fmt::Debug + Send + Sync {
+    /// Sends the request and returns the response, for any status code. Only failures to get a response at all, like
+    /// connection errors or timeouts, should be returned as error.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// The default [`HttpTransport`], using `reqwest`.
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    // Clients with a client certificate for mutual TLS, built once per certificate
+    #[cfg(all(feature = "tls", not(target_family = "wasm")))]
+    client_certificate_clients: Arc<Mutex<HashMap<ClientCertificate, reqwest::Client>>>,
+}
+
+impl ReqwestTransport {
+    fn client(&self, client_certificate: Option<&ClientCertificate>) -> Result<reqwest::Client> {
+        let client_certificate = match client_certificate {
+            Some(client_certificate) => client_certificate,
+            None => return Ok(self.client.clone()),
+        };
+
+        #[cfg(all(feature = "tls", not(target_family = "wasm")))]
+        {
+            let mut clients = self.client_certificate_clients.lock().map_err(|_| Error::PoisonError)?;
+            if let Some(client) = clients.get(client_certificate) {
+                return Ok(client.clone());
+            }
+
+            let pem = format!("{}\n{}", client_certificate.certificate, client_certificate.private_key);
+            let identity = reqwest::Identity::from_pem(pem.as_bytes())
+                .map_err(|e| Error::ClientCertificateError(e.to_string()))?;
+            let client = reqwest::Client::builder()
+                .identity(identity)
+                .build()
+                .map_err(|e| Error::ClientCertificateError(e.to_string()))?;
+            clients.insert(client_certificate.clone(), client.clone());
+
+            Ok(client)
+        }
+        #[cfg(not(all(feature = "tls", not(target_family = "wasm"))))]
+        {
+            let _ = client_certificate;
+            Err(Error::ClientCertificateError(
+                "mutual TLS requires the `tls` feature and isn't supported on wasm".into(),
+            ))
+        }
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait)]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let method = match request.method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+        };
+        let client = self.client(request.client_certificate.as_ref())?;
+        let mut request_builder = client.request(method, request.url);
+        for (key, value) in request.headers {
+            request_builder = request_builder.header(key, value);
+        }
+        if let Some(body) = request.body {
+            request_builder = request_builder.body(body);
+        }
+        #[cfg(not(target_family = "wasm"))]
+        {
+            request_builder = request_builder.timeout(request.timeout);
+        }
+        let response = request_builder.send().await?;
+        let status = response.status().as_u16();
+        let body = response.bytes().await?.to_vec();
+
+        Ok(HttpResponse { status, body })
+    }
+}
+
+/// Shared handle to a [`HttpTransport`], so it can be stored in the builders.
+#[derive(Clone, Debug)]
+pub(crate) struct TransportHandle(pub(crate) Arc<dyn HttpTransport>);
+
+impl PartialEq for TransportHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for TransportHandle {}
+
+pub(crate) struct Response(HttpResponse);
 
 impl Response {
     pub(crate) fn status(&self) -> u16 {
-        self.0.status().as_u16()
+        self.0.status
     }
 
-    pub(crate) async fn into_json<T: DeserializeOwned>(self) -> Result<T> {
-        self.0.json().await.map_err(Into::into)
+    pub(crate) fn into_json<T: DeserializeOwned>(self) -> Result<T> {
+        serde_json::from_slice(&self.0.body).map_err(Into::into)
     }
 
-    pub(crate) async fn into_text(self) -> Result<String> {
-        self.0.text().await.map_err(Into::into)
+    pub(crate) fn into_text(self) -> Result<String> {
+        String::from_utf8(self.0.body).map_err(|_| Error::NodeError("non UTF8 node response".into()))
     }
 
-    pub(crate) async fn into_bytes(self) -> Result<Vec<u8>> {
-        self.0.bytes().await.map(|b| b.to_vec()).map_err(Into::into)
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.0.body
     }
 }
 
 #[derive(Clone)]
 pub(crate) struct HttpClient {
-    client: reqwest::Client,
+    transport: Arc<dyn HttpTransport>,
+    observer: Option<Arc<dyn RequestObserver>>,
     user_agent: String,
 }
 
 impl HttpClient {
     pub(crate) fn new(user_agent: String) -> Self {
+        Self::with_transport(Arc::new(ReqwestTransport::default()), user_agent)
+    }
+
+    pub(crate) fn with_transport(transport: Arc<dyn HttpTransport>, user_agent: String) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            transport,
+            observer: None,
             user_agent,
         }
     }
 
-    async fn parse_response(response: reqwest::Response, url: &url::Url) -> Result<Response> {
-        let status = response.status();
-        if status.is_success() {
+    pub(crate) fn with_observer(mut self, observer: Option<Arc<dyn RequestObserver>>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    fn parse_response(response: HttpResponse, url: &Url) -> Result<Response> {
+        if (200..300).contains(&response.status) {
             Ok(Response(response))
         } else {
             Err(Error::ResponseError {
-                code: status.as_u16(),
-                text: response.text().await?,
+                code: response.status,
+                text: String::from_utf8_lossy(&response.body).into_owned(),
                 url: url.to_string(),
             })
         }
     }
 
-    fn build_request(&self, request_builder: RequestBuilder, node: &Node, _timeout: Duration) -> RequestBuilder {
-        let mut request_builder = request_builder.header(reqwest::header::USER_AGENT, &self.user_agent);
+    fn build_request(&self, method: HttpMethod, node: &Node, timeout: Duration) -> HttpRequest {
+        let mut headers = vec![("User-Agent".to_string(), self.user_agent.clone())];
+        let mut client_certificate = None;
+
+        // Credentials are only sent as header, so they don't end up in logs and errors
+        let mut url = node.url.clone();
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
 
         if let Some(node_auth) = &node.auth {
             if let Some(jwt) = &node_auth.jwt {
-                request_builder = request_builder.bearer_auth(jwt);
+                headers.push(("Authorization".to_string(), format!("Bearer {jwt}")));
+            } else if let Some((name, password)) = &node_auth.basic_auth_name_pwd {
+                let credentials = base64::encode(format!("{name}:{password}"));
+                headers.push(("Authorization".to_string(), format!("Basic {credentials}")));
+            }
+            for (key, value) in &node_auth.headers {
+                headers.push((key.clone(), value.clone()));
             }
+            client_certificate = node_auth.client_certificate.clone();
         }
-        #[cfg(not(target_family = "wasm"))]
-        {
-            request_builder = request_builder.timeout(_timeout);
+
+        HttpRequest {
+            method,
+            url,
+            headers,
+            body: None,
+            timeout,
+            client_certificate,
         }
-        request_builder
     }
 
-    pub(crate) async fn get(&self, node: Node, timeout: Duration) -> Result<Response> {
-        let mut request_builder = self.client.get(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
+    // Sends the request with the transport and reports it to the observer and, with the `tracing` feature, as span.
+    async fn send(&self, request: HttpRequest, attempt: RequestAttempt) -> Result<HttpResponse> {
+        let method = request.method;
+        let path = request.url.path().trim_start_matches('/').to_string();
+        let node_url = request.url.origin().ascii_serialization();
+        let request_bytes = request.body.as_ref().map_or(0, Vec::len);
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "node_request",
+            method = ?method,
+            route = %route_template(&path),
+            node_url = %node_url,
+            attempt = ?attempt,
+            status = tracing::field::Empty,
+            response_bytes = tracing::field::Empty,
+        );
+
         let start_time = instant::Instant::now();
-        let resp = request_builder.send().await?;
+        #[cfg(feature = "tracing")]
+        let response = tracing::Instrument::instrument(self.transport.send(request), span.clone()).await;
+        #[cfg(not(feature = "tracing"))]
+        let response = self.transport.send(request).await;
+        let latency = start_time.elapsed();
+
+        let (status, response_bytes) = match &response {
+            Ok(response) => (Some(response.status), response.body.len()),
+            Err(_) => (None, 0),
+        };
         log::debug!(
-            "GET: {:?} ms for {} {}",
-            start_time.elapsed().as_millis(),
-            resp.status(),
-            node.url
+            "{method:?}: {:?} ms for {status:?} {node_url}/{path}",
+            latency.as_millis()
         );
-        Self::parse_response(resp, &node.url).await
+        #[cfg(feature = "tracing")]
+        if let Some(status) = status {
+            span.record("status", status);
+            span.record("response_bytes", response_bytes);
+        }
+
+        if let Some(observer) = &self.observer {
+            observer.on_request(&RequestEvent {
+                method,
+                route: route_template(&path),
+                path,
+                node_url,
+                latency,
+                status,
+                request_bytes,
+                response_bytes,
+                attempt,
+            });
+        }
+
+        response
+    }
+
+    pub(crate) async fn get(&self, node: Node, timeout: Duration, attempt: RequestAttempt) -> Result<Response> {
+        let request = self.build_request(HttpMethod::Get, &node, timeout);
+        let url = request.url.clone();
+        Self::parse_response(self.send(request, attempt).await?, &url)
     }
 
     // Get with header: "accept", "application/vnd.iota.serializer-v1"
-    pub(crate) async fn get_bytes(&self, node: Node, timeout: Duration) -> Result<Response> {
-        let mut request_builder = self.client.get(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
-        request_builder = request_builder.header("accept", "application/vnd.iota.serializer-v1");
-        let resp = request_builder.send().await?;
-        Self::parse_response(resp, &node.url).await
+    pub(crate) async fn get_bytes(&self, node: Node, timeout: Duration, attempt: RequestAttempt) -> Result<Response> {
+        let mut request = self.build_request(HttpMethod::Get, &node, timeout);
+        request
+            .headers
+            .push(("Accept".to_string(), "application/vnd.iota.serializer-v1".to_string()));
+        let url = request.url.clone();
+        Self::parse_response(self.send(request, attempt).await?, &url)
+    }
+
+    pub(crate) async fn post_json(
+        &self,
+        node: Node,
+        timeout: Duration,
+        json: Value,
+        attempt: RequestAttempt,
+    ) -> Result<Response> {
+        let mut request = self.build_request(HttpMethod::Post, &node, timeout);
+        request
+            .headers
+            .push(("Content-Type".to_string(), "application/json".to_string()));
+        request.body = Some(serde_json::to_vec(&json)?);
+        let url = request.url.clone();
+        Self::parse_response(self.send(request, attempt).await?, &url)
     }
 
-    pub(crate) async fn post_json(&self, node: Node, timeout: Duration, json: Value) -> Result<Response> {
-        let mut request_builder = self.client.post(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
-        Self::parse_response(request_builder.json(&json).send().await?, &node.url).await
+    pub(crate) async fn delete(&self, node: Node, timeout: Duration, attempt: RequestAttempt) -> Result<Response> {
+        let request = self.build_request(HttpMethod::Delete, &node, timeout);
+        let url = request.url.clone();
+        Self::parse_response(self.send(request, attempt).await?, &url)
     }
 
-    pub(crate) async fn post_bytes(&self, node: Node, timeout: Duration, body: &[u8]) -> Result<Response> {
-        let mut request_builder = self.client.post(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
-        request_builder = request_builder.header("Content-Type", "application/vnd.iota.serializer-v1");
-        Self::parse_response(request_builder.body(body.to_vec()).send().await?, &node.url).await
+    pub(crate) async fn post_bytes(
+        &self,
+        node: Node,
+        timeout: Duration,
+        body: &[u8],
+        attempt: RequestAttempt,
+    ) -> Result<Response> {
+        let mut request = self.build_request(HttpMethod::Post, &node, timeout);
+        request
+            .headers
+            .push(("Content-Type".to_string(), "application/vnd.iota.serializer-v1".to_string()));
+        request.body = Some(body.to_vec());
+        let url = request.url.clone();
+        Self::parse_response(self.send(request, attempt).await?, &url)
     }
 }
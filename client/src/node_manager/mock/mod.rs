@@ -0,0 +1,365 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-memory node that serves the node API from fixtures, to test the client without a running node.
+//!
+//! ```
+//! # use iota_client::{node_manager::mock::MockNode, Client, Result};
+//! # #[tokio::main]
+//! # async fn main() -> Result<()> {
+//! let node = MockNode::new();
+//! let client = Client::builder()
+//!     .with_node("http://localhost:14265")?
+//!     .with_transport(node.clone())
+//!     .finish()?;
+//!
+//! let info = client.get_info().await?;
+//! assert_eq!(info.node_info.name, "HORNET");
+//! # Ok(())}
+//! ```
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use iota_types::{
+    api::{
+        dto::LedgerInclusionStateDto,
+        response::{BlockMetadataResponse, InfoResponse, OutputWithMetadataResponse, SubmitBlockResponse},
+    },
+    block::{
+        output::OutputId,
+        payload::{dto::MilestonePayloadDto, transaction::TransactionId, MilestonePayload, Payload},
+        protocol::ProtocolParameters,
+        Block, BlockDto, BlockId,
+    },
+};
+use packable::PackableExt;
+use serde::Serialize;
+
+use crate::{
+    node_api::indexer::OutputIdsResponse,
+    node_manager::http_client::{HttpMethod, HttpRequest, HttpResponse, HttpTransport},
+    Error, Result,
+};
+
+const INFO_FIXTURE: &str = include_str!("fixtures/info.json");
+const ROUTES_FIXTURE: &str = include_str!("fixtures/routes.json");
+const TIPS_FIXTURE: &str = include_str!("fixtures/tips.json");
+
+const INFO_ROUTE: &str = "api/core/v2/info";
+const BLOCKS_ROUTE: &str = "api/core/v2/blocks";
+const SERIALIZER_MIME_TYPE: &str = "application/vnd.iota.serializer-v1";
+
+#[derive(Debug, Default)]
+struct MockNodeState {
+    // Fixtures by method and route, the route can contain query parameters.
+    routes: HashMap<(HttpMethod, String), HttpResponse>,
+    blocks: HashMap<BlockId, Block>,
+    included_blocks: HashMap<TransactionId, BlockId>,
+    requests: Vec<HttpRequest>,
+}
+
+/// An in-memory node implementing [`HttpTransport`], which answers requests to `/api/core/v2/*` and
+/// `/api/indexer/v1/*` from fixtures.
+///
+/// A new mock node is healthy and serves the node info, routes and tips from bundled fixtures. Blocks posted to it are
+/// validated against the protocol parameters of the node info and can be fetched again afterwards, together with
+/// their metadata and, for transactions, as included block. Further responses are registered with the `with_*`
+/// methods. Requests for anything else are answered with 404.
+///
+/// Clones share their state, so a clone can be handed to
+/// [`ClientBuilder::with_transport()`](crate::ClientBuilder::with_transport) while the original is kept to register
+/// more fixtures or to inspect the received [`requests()`](MockNode::requests).
+///
+/// The host of the requested URL is ignored, every node of a client using this transport is served by the same mock
+/// node.
+#[derive(Clone, Debug)]
+pub struct MockNode {
+    state: Arc<Mutex<MockNodeState>>,
+}
+
+impl Default for MockNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockNode {
+    /// Creates a new mock node serving the bundled fixtures.
+    pub fn new() -> Self {
+        let node = Self {
+            state: Default::default(),
+        };
+
+        node.with_response(HttpMethod::Get, "health", HttpResponse { status: 200, body: Vec::new() })
+            .with_response(HttpMethod::Get, INFO_ROUTE, json_response(200, INFO_FIXTURE))
+            .with_response(HttpMethod::Get, "api/routes", json_response(200, ROUTES_FIXTURE))
+            .with_response(HttpMethod::Get, "api/core/v2/tips", json_response(200, TIPS_FIXTURE))
+    }
+
+    /// Registers the response for a route. The route is the URL path without leading slash and can contain query
+    /// parameters, like `api/indexer/v1/outputs/basic?tag=0x01`. Routes with query parameters take precedence over
+    /// the same route without them.
+    pub fn with_response(self, method: HttpMethod, route: &str, response: HttpResponse) -> Self {
+        self.lock()
+            .routes
+            .insert((method, route.trim_start_matches('/').to_string()), response);
+        self
+    }
+
+    /// Registers a JSON response with status code 200 for a route.
+    pub fn with_json<T: Serialize>(self, method: HttpMethod, route: &str, json: &T) -> Result<Self> {
+        let body = serde_json::to_vec(json)?;
+        Ok(self.with_response(method, route, HttpResponse { status: 200, body }))
+    }
+
+    /// Registers an error response with the given status code for a route.
+    pub fn with_status(self, method: HttpMethod, route: &str, status: u16) -> Self {
+        self.with_response(method, route, error_response(status, "mocked error"))
+    }
+
+    /// Replaces the node info.
+    pub fn with_info(self, info: &InfoResponse) -> Result<Self> {
+        self.with_json(HttpMethod::Get, INFO_ROUTE, info)
+    }
+
+    /// Adds a block, which is then served like a block that was posted to the node.
+    pub fn with_block(self, block: Block) -> Self {
+        self.lock().insert_block(block);
+        self
+    }
+
+    /// Adds an output, served by `api/core/v2/outputs/{outputId}` and `api/core/v2/outputs/{outputId}/metadata`.
+    pub fn with_output(self, output: &OutputWithMetadataResponse) -> Result<Self> {
+        let output_id = output.metadata.output_id()?;
+
+        self.with_json(HttpMethod::Get, &format!("api/core/v2/outputs/{output_id}"), output)?
+            .with_json(
+                HttpMethod::Get,
+                &format!("api/core/v2/outputs/{output_id}/metadata"),
+                &output.metadata,
+            )
+    }
+
+    /// Sets the output ids returned by an indexer route like `api/indexer/v1/outputs/basic`, as a single page.
+    pub fn with_output_ids(self, route: &str, output_ids: &[OutputId]) -> Result<Self> {
+        self.with_output_ids_page(route, output_ids, None)
+    }
+
+    /// Sets a page of output ids returned by an indexer route, with the cursor of the next page. The route contains
+    /// the query parameters of the page, like `api/indexer/v1/outputs/basic?cursor=abc.1&pageSize=1`.
+    pub fn with_output_ids_page(self, route: &str, output_ids: &[OutputId], cursor: Option<&str>) -> Result<Self> {
+        let ledger_index = self.info()?.status.confirmed_milestone.index;
+        let response = OutputIdsResponse {
+            ledger_index,
+            cursor: cursor.map(ToString::to_string),
+            items: output_ids.iter().map(ToString::to_string).collect(),
+        };
+
+        self.with_json(HttpMethod::Get, route, &response)
+    }
+
+    /// Adds a milestone, served by its milestone id and by its index.
+    pub fn with_milestone(self, milestone: &MilestonePayload) -> Result<Self> {
+        let dto = MilestonePayloadDto::from(milestone);
+
+        self.with_json(HttpMethod::Get, &format!("api/core/v2/milestones/{}", milestone.id()), &dto)?
+            .with_json(
+                HttpMethod::Get,
+                &format!("api/core/v2/milestones/by-index/{}", *milestone.essence().index()),
+                &dto,
+            )
+    }
+
+    /// Returns all requests received so far, in order.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockNodeState> {
+        // A panicking test can't leave the state inconsistent, so the poison flag can be ignored.
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn info(&self) -> Result<InfoResponse> {
+        let state = self.lock();
+        let response = state
+            .routes
+            .get(&(HttpMethod::Get, INFO_ROUTE.to_string()))
+            .ok_or(Error::UnexpectedApiResponse)?;
+
+        Ok(serde_json::from_slice(&response.body)?)
+    }
+
+    fn handle(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        let path = request.url.path().trim_start_matches('/');
+        let route = match request.url.query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path.to_string(),
+        };
+
+        {
+            let state = self.lock();
+            if let Some(response) = state
+                .routes
+                .get(&(request.method, route))
+                .or_else(|| state.routes.get(&(request.method, path.to_string())))
+            {
+                return Ok(response.clone());
+            }
+        }
+
+        match (request.method, path.strip_prefix(BLOCKS_ROUTE)) {
+            (HttpMethod::Post, Some("")) => self.post_block(request),
+            (HttpMethod::Get, Some(block_route)) => self.get_block(request, block_route),
+            (HttpMethod::Get, None) => self.get_included_block(request, path),
+            _ => Ok(error_response(404, &format!("no fixture for {path}"))),
+        }
+    }
+
+    fn post_block(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        let protocol_parameters = ProtocolParameters::try_from(self.info()?.protocol)?;
+        let body = request.body.as_deref().unwrap_or_default();
+
+        let block = if request.header("Content-Type") == Some(SERIALIZER_MIME_TYPE) {
+            Block::unpack_strict(body, &protocol_parameters)
+                .map_err(|e| Error::NodeError(format!("invalid block bytes: {e:?}")))
+        } else {
+            serde_json::from_slice::<BlockDto>(body)
+                .map_err(Error::from)
+                .and_then(|dto| Ok(Block::try_from_dto(&dto, &protocol_parameters)?))
+        };
+
+        match block {
+            Ok(block) => {
+                let block_id = self.lock().insert_block(block);
+                let response = SubmitBlockResponse {
+                    block_id: block_id.to_string(),
+                };
+                Ok(HttpResponse {
+                    status: 201,
+                    body: serde_json::to_vec(&response)?,
+                })
+            }
+            Err(e) => Ok(error_response(400, &e.to_string())),
+        }
+    }
+
+    // Serves `api/core/v2/blocks/{blockId}` and `api/core/v2/blocks/{blockId}/metadata`.
+    fn get_block(&self, request: &HttpRequest, block_route: &str) -> Result<HttpResponse> {
+        let (block_id, metadata) = match block_route.trim_start_matches('/').split_once('/') {
+            Some((block_id, "metadata")) => (block_id, true),
+            Some(_) => return Ok(error_response(404, "unknown block route")),
+            None => (block_route.trim_start_matches('/'), false),
+        };
+        let block_id = match BlockId::from_str(block_id) {
+            Ok(block_id) => block_id,
+            Err(e) => return Ok(error_response(400, &e.to_string())),
+        };
+        let block = match self.lock().blocks.get(&block_id) {
+            Some(block) => block.clone(),
+            None => return Ok(error_response(404, &format!("block {block_id} not found"))),
+        };
+
+        if metadata {
+            let referenced_by_milestone_index = self.info()?.status.confirmed_milestone.index;
+            let ledger_inclusion_state = match block.payload() {
+                Some(Payload::Transaction(_)) => LedgerInclusionStateDto::Included,
+                _ => LedgerInclusionStateDto::NoTransaction,
+            };
+            let response = BlockMetadataResponse {
+                block_id: block_id.to_string(),
+                parents: block.parents().iter().map(ToString::to_string).collect(),
+                is_solid: true,
+                referenced_by_milestone_index: Some(referenced_by_milestone_index),
+                milestone_index: None,
+                ledger_inclusion_state: Some(ledger_inclusion_state),
+                conflict_reason: None,
+                white_flag_index: None,
+                should_promote: None,
+                should_reattach: None,
+            };
+            Ok(HttpResponse {
+                status: 200,
+                body: serde_json::to_vec(&response)?,
+            })
+        } else {
+            Ok(block_response(request, &block)?)
+        }
+    }
+
+    // Serves `api/core/v2/transactions/{transactionId}/included-block`.
+    fn get_included_block(&self, request: &HttpRequest, path: &str) -> Result<HttpResponse> {
+        let transaction_id = path
+            .strip_prefix("api/core/v2/transactions/")
+            .and_then(|route| route.strip_suffix("/included-block"))
+            .and_then(|transaction_id| TransactionId::from_str(transaction_id).ok());
+
+        let block = transaction_id.and_then(|transaction_id| {
+            let state = self.lock();
+            state
+                .included_blocks
+                .get(&transaction_id)
+                .and_then(|block_id| state.blocks.get(block_id))
+                .cloned()
+        });
+
+        match block {
+            Some(block) => block_response(request, &block),
+            None => Ok(error_response(404, &format!("no fixture for {path}"))),
+        }
+    }
+}
+
+impl MockNodeState {
+    fn insert_block(&mut self, block: Block) -> BlockId {
+        let block_id = block.id();
+        if let Some(Payload::Transaction(transaction)) = block.payload() {
+            self.included_blocks.insert(transaction.id(), block_id);
+        }
+        self.blocks.insert(block_id, block);
+        block_id
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait)]
+impl HttpTransport for MockNode {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        self.lock().requests.push(request.clone());
+        self.handle(&request)
+    }
+}
+
+fn block_response(request: &HttpRequest, block: &Block) -> Result<HttpResponse> {
+    let body = if request.header("Accept") == Some(SERIALIZER_MIME_TYPE) {
+        block.pack_to_vec()
+    } else {
+        serde_json::to_vec(&BlockDto::from(block))?
+    };
+
+    Ok(HttpResponse { status: 200, body })
+}
+
+fn json_response(status: u16, body: &str) -> HttpResponse {
+    HttpResponse {
+        status,
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+// Error responses look like the ones from HORNET.
+fn error_response(status: u16, message: &str) -> HttpResponse {
+    let body = serde_json::json!({
+        "error": {
+            "code": status.to_string(),
+            "message": message,
+        }
+    });
+
+    json_response(status, &body.to_string())
+}
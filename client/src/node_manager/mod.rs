@@ -4,10 +4,20 @@
 //! The node manager that takes care of sending requests with healthy nodes and quorum if enabled
 
 pub mod builder;
-pub(crate) mod http_client;
+pub mod circuit_breaker;
+pub mod http_client;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(not(target_family = "wasm"))]
+pub mod network_events;
 /// Structs for nodes
 pub mod node;
+pub mod observer;
+pub mod quorum;
+pub mod request_options;
+pub mod retry;
 pub(crate) mod syncing;
+pub mod timeouts;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -18,7 +28,16 @@ use std::{
 use iota_types::api::response::InfoResponse;
 use serde_json::Value;
 
-use self::{http_client::HttpClient, node::Node};
+use self::{
+    circuit_breaker::{CircuitBreakerOptions, CircuitState},
+    http_client::{HttpClient, HttpMethod, Response},
+    node::{HealthyNode, Node},
+    observer::RequestAttempt,
+    quorum::QuorumResponses,
+    request_options::RequestOptions,
+    retry::RetryOptions,
+    timeouts::{RouteClass, RouteTimeouts},
+};
 use crate::{
     error::{Error, Result},
     node_manager::builder::NodeManagerBuilder,
@@ -28,17 +47,24 @@ use crate::{
 // will send the requests for some endpoints to multiple nodes and compares the results.
 #[derive(Clone)]
 pub(crate) struct NodeManager {
-    pub(crate) primary_node: Option<Node>,
+    // The primary node and the nodes are shared with the sync process, so they can be changed at runtime
+    pub(crate) primary_node: Arc<RwLock<Option<Node>>>,
     primary_pow_node: Option<Node>,
-    pub(crate) nodes: HashSet<Node>,
+    pub(crate) nodes: Arc<RwLock<HashSet<Node>>>,
     permanodes: Option<HashSet<Node>>,
     pub(crate) ignore_node_health: bool,
-    node_sync_interval: Duration,
-    pub(crate) healthy_nodes: Arc<RwLock<HashMap<Node, InfoResponse>>>,
+    pub(crate) node_sync_interval: Duration,
+    pub(crate) healthy_nodes: Arc<RwLock<HashMap<Node, HealthyNode>>>,
     quorum: bool,
     min_quorum_size: usize,
     quorum_threshold: usize,
+    circuit_breaker_options: CircuitBreakerOptions,
+    retry_options: RetryOptions,
+    route_timeouts: RouteTimeouts,
+    pub(crate) request_options: RequestOptions,
     pub(crate) http_client: HttpClient,
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) network_tracker: network_events::NetworkTracker,
 }
 
 impl std::fmt::Debug for NodeManager {
@@ -53,7 +79,11 @@ impl std::fmt::Debug for NodeManager {
         d.field("healthy_nodes", &self.healthy_nodes);
         d.field("quorum", &self.quorum);
         d.field("min_quorum_size", &self.min_quorum_size);
-        d.field("quorum_threshold", &self.quorum_threshold).finish()
+        d.field("quorum_threshold", &self.quorum_threshold);
+        d.field("circuit_breaker_options", &self.circuit_breaker_options);
+        d.field("retry_options", &self.retry_options);
+        d.field("route_timeouts", &self.route_timeouts);
+        d.field("request_options", &self.request_options).finish()
     }
 }
 
@@ -62,6 +92,45 @@ impl NodeManager {
         NodeManagerBuilder::new()
     }
 
+    // The primary node and the nodes of the pool, which get synced.
+    pub(crate) fn nodes_to_sync(&self) -> Result<HashSet<Node>> {
+        let primary_node = self.primary_node.read().map_err(|_| crate::Error::PoisonError)?;
+        let nodes = self.nodes.read().map_err(|_| crate::Error::PoisonError)?;
+
+        Ok(primary_node.iter().chain(nodes.iter()).cloned().collect())
+    }
+
+    pub(crate) fn has_permanodes(&self) -> bool {
+        matches!(&self.permanodes, Some(permanodes) if !permanodes.is_empty())
+    }
+
+    // The timeout of the request options, else the one of the route class for GET requests, else the given one.
+    pub(crate) fn timeout(&self, path: &str, is_get_request: bool, timeout: Duration) -> Duration {
+        let route_timeout = if is_get_request {
+            self.route_timeouts.get(RouteClass::of(path))
+        } else {
+            None
+        };
+
+        self.request_options.timeout.or(route_timeout).unwrap_or(timeout)
+    }
+
+    // If the request should be sent with quorum.
+    fn use_quorum(&self, need_quorum: bool) -> bool {
+        self.quorum && need_quorum && !self.request_options.skip_quorum && self.request_options.node.is_none()
+    }
+
+    // Removes the healthy nodes that were removed from the node pool or changed while they were synced.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn retain_nodes_to_sync(&self) -> Result<()> {
+        let nodes = self.nodes_to_sync()?;
+        self.healthy_nodes
+            .write()
+            .map_err(|_| crate::Error::PoisonError)?
+            .retain(|node, _| nodes.contains(node));
+        Ok(())
+    }
+
     fn get_nodes(
         &self,
         path: &str,
@@ -69,6 +138,14 @@ impl NodeManager {
         use_pow_nodes: bool,
         prefer_permanode: bool,
     ) -> Result<Vec<Node>> {
+        // A node of the request options is the only one that is used
+        if let Some(node) = &self.request_options.node {
+            let mut node = node.clone();
+            node.url.set_path(path);
+            node.url.set_query(query);
+            return Ok(vec![node]);
+        }
+
         let mut nodes_with_modified_url: Vec<Node> = Vec::new();
 
         if prefer_permanode || (path == "api/core/v2/blocks" && query.is_some()) {
@@ -89,46 +166,62 @@ impl NodeManager {
             }
         }
 
-        if let Some(primary_node) = self.primary_node.clone() {
+        let primary_node = self
+            .primary_node
+            .read()
+            .map_err(|_| crate::Error::PoisonError)?
+            .clone();
+        if let Some(primary_node) = primary_node {
             if !nodes_with_modified_url.iter().any(|n| n.url == primary_node.url) {
                 nodes_with_modified_url.push(primary_node);
             }
         }
 
-        // Add other nodes in random order, so they are not always used in the same order
-        let nodes_random_order = if !self.ignore_node_health {
+        // Add other nodes ranked by their score, so the fastest and most reliable ones are tried first
+        let nodes = self.nodes.read().map_err(|_| crate::Error::PoisonError)?;
+        let healthy_nodes = self.healthy_nodes.read().map_err(|_| crate::Error::PoisonError)?;
+        let mut ranked_nodes: Vec<(Node, f64)> = if !self.ignore_node_health {
             #[cfg(not(target_family = "wasm"))]
             {
-                self.healthy_nodes
-                    .read()
-                    .map_err(|_| crate::Error::PoisonError)?
+                healthy_nodes
                     .iter()
-                    .filter_map(|(n, info)| {
+                    // Nodes that were removed or changed since the last sync aren't used anymore
+                    .filter(|(n, _)| nodes.contains(n))
+                    .filter_map(|(n, healthy_node)| {
                         // Only add nodes with pow feature enabled, when remote PoW is used
                         if use_pow_nodes {
                             let pow_feature = String::from("pow");
 
-                            if info.features.contains(&pow_feature) {
-                                Some(n.clone())
+                            if healthy_node.info.features.contains(&pow_feature) {
+                                Some((n.clone(), healthy_node.stats.score()))
                             } else {
                                 None
                             }
                         } else {
-                            Some(n.clone())
+                            Some((n.clone(), healthy_node.stats.score()))
                         }
                     })
                     .collect()
             }
             #[cfg(target_family = "wasm")]
             {
-                self.nodes.clone()
+                nodes
+                    .iter()
+                    .map(|n| (n.clone(), Self::node_score(&healthy_nodes, n)))
+                    .collect()
             }
         } else {
-            self.nodes.clone()
+            nodes
+                .iter()
+                .map(|n| (n.clone(), Self::node_score(&healthy_nodes, n)))
+                .collect()
         };
+        drop(healthy_nodes);
+        drop(nodes);
+        ranked_nodes.sort_by(|(_, a), (_, b)| b.total_cmp(a));
 
-        // Add remaining nodes in random order
-        for node in nodes_random_order {
+        // Add remaining nodes by their rank
+        for (node, _) in ranked_nodes {
             if !nodes_with_modified_url.iter().any(|n| n.url == node.url) {
                 nodes_with_modified_url.push(node);
             }
@@ -141,6 +234,8 @@ impl NodeManager {
             return Err(crate::Error::HealthyNodePoolEmpty);
         }
 
+        self.apply_circuit_breakers(&mut nodes_with_modified_url)?;
+
         // Set path and query parameters
         nodes_with_modified_url.iter_mut().for_each(|node| {
             node.url.set_path(path);
@@ -150,6 +245,84 @@ impl NodeManager {
         Ok(nodes_with_modified_url)
     }
 
+    // Removes nodes with an open circuit and moves a node with a half-open circuit to the front, so it gets probed. If
+    // all circuits are open the nodes are kept, trying them is better than failing without a request.
+    fn apply_circuit_breakers(&self, nodes: &mut Vec<Node>) -> Result<()> {
+        let mut healthy_nodes = self.healthy_nodes.write().map_err(|_| crate::Error::PoisonError)?;
+        let mut available_nodes = Vec::with_capacity(nodes.len());
+
+        for node in nodes.iter() {
+            match healthy_nodes
+                .iter_mut()
+                .find(|(n, _)| n.url.origin() == node.url.origin())
+            {
+                Some((_, healthy_node)) => match healthy_node.circuit_breaker.state(&self.circuit_breaker_options) {
+                    CircuitState::Closed => available_nodes.push(node.clone()),
+                    CircuitState::HalfOpen => {
+                        if healthy_node.circuit_breaker.try_probe(&self.circuit_breaker_options) {
+                            log::debug!("probing node with half-open circuit: {}", node.url);
+                            available_nodes.insert(0, node.clone());
+                        }
+                    }
+                    CircuitState::Open => {}
+                },
+                None => available_nodes.push(node.clone()),
+            }
+        }
+
+        if !available_nodes.is_empty() {
+            *nodes = available_nodes;
+        }
+        Ok(())
+    }
+
+    // Score of a node, nodes without stats are ranked like nodes without latency samples.
+    fn node_score(healthy_nodes: &HashMap<Node, HealthyNode>, node: &Node) -> f64 {
+        healthy_nodes
+            .iter()
+            .find(|(n, _)| n.url.origin() == node.url.origin())
+            .map_or_else(
+                || node::NodeStats::default().score(),
+                |(_, healthy_node)| healthy_node.stats.score(),
+            )
+    }
+
+    // Records the latency and the outcome of a request in the stats and the circuit breaker of the node.
+    pub(crate) fn record_request(&self, node: &Node, latency: Duration, result: &Result<Response>) {
+        Self::record_request_in(&self.healthy_nodes, &self.circuit_breaker_options, node, latency, result)
+    }
+
+    fn record_request_in(
+        healthy_nodes: &RwLock<HashMap<Node, HealthyNode>>,
+        circuit_breaker_options: &CircuitBreakerOptions,
+        node: &Node,
+        latency: Duration,
+        result: &Result<Response>,
+    ) {
+        // Not found or invalid requests are no failure of the node
+        let failed = match result {
+            Ok(_) => false,
+            Err(Error::ResponseError { code, .. }) => *code >= 500,
+            Err(_) => true,
+        };
+        if let Ok(mut healthy_nodes) = healthy_nodes.write() {
+            if let Some((_, healthy_node)) = healthy_nodes
+                .iter_mut()
+                .find(|(n, _)| n.url.origin() == node.url.origin())
+            {
+                healthy_node.stats.record_request(latency, failed);
+                if healthy_node.circuit_breaker.record_request(failed, circuit_breaker_options) {
+                    log::warn!(
+                        "opened circuit of node {} after {} consecutive failed requests",
+                        node.url.origin().ascii_serialization(),
+                        healthy_node.circuit_breaker.consecutive_failures()
+                    );
+                }
+            }
+        }
+    }
+
+    // GET requests are idempotent, so they're retried with backoff if all nodes failed
     pub(crate) async fn get_request<T: serde::de::DeserializeOwned + std::fmt::Debug + serde::Serialize>(
         &self,
         path: &str,
@@ -158,130 +331,153 @@ impl NodeManager {
         need_quorum: bool,
         prefer_permanode: bool,
     ) -> Result<T> {
-        let mut result: HashMap<String, usize> = HashMap::new();
+        let timeout = self.timeout(path, true, timeout);
+        retry::with_retries(&self.retry_options, || {
+            self.try_get_request(path, query, timeout, need_quorum, prefer_permanode)
+        })
+        .await
+    }
+
+    async fn try_get_request<T: serde::de::DeserializeOwned + std::fmt::Debug + serde::Serialize>(
+        &self,
+        path: &str,
+        query: Option<&str>,
+        timeout: Duration,
+        need_quorum: bool,
+        prefer_permanode: bool,
+    ) -> Result<T> {
         // primary_pow_node should only be used for post request with remote PoW
         // Get node urls and set path
         let nodes = self.get_nodes(path, query, false, prefer_permanode)?;
-        if self.quorum && need_quorum && nodes.len() < self.min_quorum_size {
-            return Err(Error::QuorumPoolSizeError {
-                available_nodes: nodes.len(),
-                minimum_threshold: self.min_quorum_size,
-            });
+        if self.use_quorum(need_quorum) {
+            if nodes.len() < self.min_quorum_size {
+                return Err(Error::QuorumPoolSizeError {
+                    available_nodes: nodes.len(),
+                    minimum_threshold: self.min_quorum_size,
+                });
+            }
+            return self.get_request_with_quorum(nodes, timeout).await;
         }
 
-        // Track amount of results for quorum
-        let mut result_counter = 0;
         let mut error = None;
-        // Send requests parallel for quorum
-        #[cfg(target_family = "wasm")]
-        let wasm = true;
-        #[cfg(not(target_family = "wasm"))]
-        let wasm = false;
-        if !wasm && self.quorum && need_quorum && query.is_none() {
-            #[cfg(not(target_family = "wasm"))]
-            {
-                let mut tasks = Vec::new();
-                for (index, node) in nodes.into_iter().enumerate() {
-                    if index < self.min_quorum_size {
-                        let client_ = self.http_client.clone();
-                        tasks.push(async move { tokio::spawn(async move { client_.get(node, timeout).await }).await });
-                    }
-                }
-                for res in futures::future::try_join_all(tasks).await? {
-                    match res {
-                        Ok(res) => {
-                            if let Ok(res_text) = res.into_text().await {
-                                let counters = result.entry(res_text.to_string()).or_insert(0);
-                                *counters += 1;
-                                result_counter += 1;
-                            } else {
-                                log::warn!("couldn't convert node response to text");
+        // Send requests
+        for (index, node) in nodes.into_iter().enumerate() {
+            let start_time = instant::Instant::now();
+            let res = self
+                .http_client
+                .get(node.clone(), timeout, RequestAttempt::nth(index))
+                .await;
+            self.record_request(&node, start_time.elapsed(), &res);
+            match res {
+                Ok(res) => {
+                    match res.status() {
+                        200 => {
+                            // Handle node_info extra because we also want to return the url
+                            if path == "api/core/v2/info" {
+                                let node_info: InfoResponse = res.into_json()?;
+                                let wrapper = crate::node_api::core::routes::NodeInfoWrapper {
+                                    node_info,
+                                    url: format!("{}://{}", node.url.scheme(), node.url.host_str().unwrap_or("")),
+                                };
+                                let serde_res = serde_json::to_string(&wrapper)?;
+                                return Ok(serde_json::from_str(&serde_res)?);
                             }
-                        }
-                        Err(Error::ResponseError { code: 404, url, .. }) => {
-                            error.replace(crate::Error::NotFound(url));
-                        }
-                        Err(err) => {
-                            error.replace(err);
-                        }
-                    }
-                }
-            }
-        } else {
-            // Send requests
-            for node in nodes {
-                match self.http_client.get(node.clone(), timeout).await {
-                    Ok(res) => {
-                        match res.status() {
-                            200 => {
-                                // Handle node_info extra because we also want to return the url
-                                if path == "api/core/v2/info" {
-                                    let node_info: InfoResponse = res.into_json().await?;
-                                    let wrapper = crate::node_api::core::routes::NodeInfoWrapper {
-                                        node_info,
-                                        url: format!("{}://{}", node.url.scheme(), node.url.host_str().unwrap_or("")),
-                                    };
-                                    let serde_res = serde_json::to_string(&wrapper)?;
-                                    return Ok(serde_json::from_str(&serde_res)?);
-                                }
 
-                                match res.into_json::<T>().await {
-                                    Ok(result_data) => {
-                                        let counters = result.entry(serde_json::to_string(&result_data)?).or_insert(0);
-                                        *counters += 1;
-                                        result_counter += 1;
-                                        // Without quorum it's enough if we got one response
-                                        if !self.quorum
-                                            || result_counter >= self.min_quorum_size
-                                            || !need_quorum
-                                            // with query we ignore quorum because the nodes can store a different amount of history
-                                            || query.is_some()
-                                        {
-                                            break;
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error.replace(e);
-                                    }
+                            // Without quorum it's enough if we got one response
+                            match res.into_json::<T>() {
+                                Ok(result_data) => return Ok(result_data),
+                                Err(e) => {
+                                    error.replace(e);
                                 }
                             }
+                        }
 
-                            _ => {
-                                error.replace(crate::Error::NodeError(
-                                    res.into_text()
-                                        .await
-                                        .unwrap_or_else(|_| "couldn't convert node response into text".to_string()),
-                                ));
-                            }
+                        _ => {
+                            error.replace(crate::Error::NodeError(
+                                res.into_text()
+                                    .unwrap_or_else(|_| "couldn't convert node response into text".to_string()),
+                            ));
                         }
                     }
-                    Err(Error::ResponseError { code: 404, url, .. }) => {
-                        error.replace(crate::Error::NotFound(url));
-                    }
-                    Err(err) => {
-                        error.replace(err);
-                    }
+                }
+                Err(Error::ResponseError { code: 404, url, .. }) => {
+                    error.replace(crate::Error::NotFound(url));
+                }
+                Err(err) => {
+                    error.replace(err);
                 }
             }
         }
 
-        let res = result
-            .into_iter()
-            .max_by_key(|v| v.1)
-            .ok_or_else(|| error.unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into())))?;
-
-        // Return if quorum is false or check if quorum was reached
-        if !self.quorum
-            || res.1 as f64 >= self.min_quorum_size as f64 * (self.quorum_threshold as f64 / 100.0)
-            || !need_quorum
-            // with query we ignore quorum because the nodes can store a different amount of history
-            || query.is_some()
+        Err(error.unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into())))
+    }
+
+    // Sends the request to `min_quorum_size` nodes and only returns the response if `quorum_threshold`% of them
+    // returned the same one. Otherwise the error contains a report of the responses of all nodes.
+    async fn get_request_with_quorum<T: serde::de::DeserializeOwned>(
+        &self,
+        nodes: Vec<Node>,
+        timeout: Duration,
+    ) -> Result<T> {
+        let mut responses = QuorumResponses::default();
+
+        // Send requests parallel for quorum
+        #[cfg(not(target_family = "wasm"))]
         {
-            Ok(serde_json::from_str(&res.0)?)
+            let mut tasks = Vec::new();
+            for node in nodes.into_iter().take(self.min_quorum_size) {
+                let client_ = self.http_client.clone();
+                let healthy_nodes_ = self.healthy_nodes.clone();
+                let circuit_breaker_options = self.circuit_breaker_options;
+                tasks.push(async move {
+                    tokio::spawn(async move {
+                        let start_time = instant::Instant::now();
+                        let res = client_.get(node.clone(), timeout, RequestAttempt::Quorum).await;
+                        Self::record_request_in(
+                            &healthy_nodes_,
+                            &circuit_breaker_options,
+                            &node,
+                            start_time.elapsed(),
+                            &res,
+                        );
+                        (node, res)
+                    })
+                    .await
+                });
+            }
+            for (node, res) in futures::future::try_join_all(tasks).await? {
+                responses.add(&node, res.and_then(|res| res.into_text()));
+            }
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            for node in nodes {
+                if responses.successful_responses() >= self.min_quorum_size {
+                    break;
+                }
+                let start_time = instant::Instant::now();
+                let res = self
+                    .http_client
+                    .get(node.clone(), timeout, RequestAttempt::Quorum)
+                    .await;
+                self.record_request(&node, start_time.elapsed(), &res);
+                responses.add(&node, res.and_then(|res| res.into_text()));
+            }
+        }
+
+        let (quorum_size, response) = match responses.majority() {
+            Some(majority) => majority,
+            None => return Err(responses.into_error()),
+        };
+
+        // Check if quorum was reached
+        if quorum_size as f64 >= self.min_quorum_size as f64 * (self.quorum_threshold as f64 / 100.0) {
+            Ok(serde_json::from_str(response)?)
         } else {
             Err(Error::QuorumThresholdError {
-                quorum_size: res.1,
+                quorum_size,
                 minimum_threshold: self.min_quorum_size,
+                report: responses.into_report(),
             })
         }
     }
@@ -293,25 +489,35 @@ impl NodeManager {
         query: Option<&str>,
         timeout: Duration,
     ) -> Result<Vec<u8>> {
+        let timeout = self.timeout(path, true, timeout);
+        retry::with_retries(&self.retry_options, || self.try_get_request_bytes(path, query, timeout)).await
+    }
+
+    async fn try_get_request_bytes(&self, path: &str, query: Option<&str>, timeout: Duration) -> Result<Vec<u8>> {
         // primary_pow_node should only be used for post request with remote Pow
         // Get node urls and set path
         let nodes = self.get_nodes(path, query, false, false)?;
         let mut error = None;
         // Send requests
-        for node in nodes {
-            match self.http_client.get_bytes(node, timeout).await {
+        for (index, node) in nodes.into_iter().enumerate() {
+            let start_time = instant::Instant::now();
+            let res = self
+                .http_client
+                .get_bytes(node.clone(), timeout, RequestAttempt::nth(index))
+                .await;
+            self.record_request(&node, start_time.elapsed(), &res);
+            match res {
                 Ok(res) => {
                     let status = res.status();
-                    if let Ok(res_text) = res.into_bytes().await {
-                        // Without quorum it's enough if we got one response
-                        match status {
-                            200 => return Ok(res_text),
-                            _ => error.replace(crate::Error::NodeError(
-                                String::from_utf8(res_text)
-                                    .map_err(|_| Error::NodeError("non UTF8 node response".into()))?,
-                            )),
-                        };
-                    }
+                    let res_bytes = res.into_bytes();
+                    // Without quorum it's enough if we got one response
+                    match status {
+                        200 => return Ok(res_bytes),
+                        _ => error.replace(crate::Error::NodeError(
+                            String::from_utf8(res_bytes)
+                                .map_err(|_| Error::NodeError("non UTF8 node response".into()))?,
+                        )),
+                    };
                 }
                 Err(Error::ResponseError { code: 404, url, .. }) => {
                     error.replace(crate::Error::NotFound(url));
@@ -331,6 +537,7 @@ impl NodeManager {
         body: &[u8],
         local_pow: bool,
     ) -> Result<T> {
+        let timeout = self.timeout(path, false, timeout);
         // primary_pow_node should only be used for post request with remote PoW
         let nodes = self.get_nodes(path, None, !local_pow, false)?;
         if nodes.is_empty() {
@@ -338,17 +545,25 @@ impl NodeManager {
         }
         let mut error = None;
         // Send requests
-        for node in nodes {
-            match self.http_client.post_bytes(node, timeout, body).await {
+        for (index, node) in nodes.into_iter().enumerate() {
+            let start_time = instant::Instant::now();
+            let res = self
+                .http_client
+                .post_bytes(node.clone(), timeout, body, RequestAttempt::nth(index))
+                .await;
+            // With remote PoW the latency depends on the PoW, so it isn't comparable between nodes
+            if local_pow {
+                self.record_request(&node, start_time.elapsed(), &res);
+            }
+            match res {
                 Ok(res) => {
                     match res.status() {
-                        200 | 201 => match res.into_json::<T>().await {
+                        200 | 201 => match res.into_json::<T>() {
                             Ok(res) => return Ok(res),
                             Err(e) => error.replace(e),
                         },
                         _ => error.replace(crate::Error::NodeError(
                             res.into_text()
-                                .await
                                 .unwrap_or_else(|_| "couldn't convert node response into text".to_string()),
                         )),
                     };
@@ -368,6 +583,7 @@ impl NodeManager {
         json: Value,
         local_pow: bool,
     ) -> Result<T> {
+        let timeout = self.timeout(path, false, timeout);
         // primary_pow_node should only be used for post request with remote PoW
         let nodes = self.get_nodes(path, None, !local_pow, false)?;
         if nodes.is_empty() {
@@ -375,17 +591,25 @@ impl NodeManager {
         }
         let mut error = None;
         // Send requests
-        for node in nodes {
-            match self.http_client.post_json(node, timeout, json.clone()).await {
+        for (index, node) in nodes.into_iter().enumerate() {
+            let start_time = instant::Instant::now();
+            let res = self
+                .http_client
+                .post_json(node.clone(), timeout, json.clone(), RequestAttempt::nth(index))
+                .await;
+            // With remote PoW the latency depends on the PoW, so it isn't comparable between nodes
+            if local_pow {
+                self.record_request(&node, start_time.elapsed(), &res);
+            }
+            match res {
                 Ok(res) => {
                     match res.status() {
-                        200 | 201 => match res.into_json::<T>().await {
+                        200 | 201 => match res.into_json::<T>() {
                             Ok(res) => return Ok(res),
                             Err(e) => error.replace(e),
                         },
                         _ => error.replace(crate::Error::NodeError(
                             res.into_text()
-                                .await
                                 .unwrap_or_else(|_| "couldn't convert node response into text".to_string()),
                         )),
                     };
@@ -397,4 +621,39 @@ impl NodeManager {
         }
         Err(error.unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into())))
     }
+
+    // Management requests are about the state of a single node or change it, so they're sent once to one node: the
+    // node of the request options, else the primary node, else the best ranked node.
+    pub(crate) async fn management_request(
+        &self,
+        method: HttpMethod,
+        path: &str,
+        json: Option<Value>,
+        timeout: Duration,
+    ) -> Result<Response> {
+        let timeout = self.timeout(path, method == HttpMethod::Get, timeout);
+        let node = self
+            .get_nodes(path, None, false, false)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::NodeError("no available node".into()))?;
+
+        let start_time = instant::Instant::now();
+        let attempt = RequestAttempt::First;
+        let res = match method {
+            HttpMethod::Get => self.http_client.get(node.clone(), timeout, attempt).await,
+            HttpMethod::Post => {
+                self.http_client
+                    .post_json(node.clone(), timeout, json.unwrap_or_default(), attempt)
+                    .await
+            }
+            HttpMethod::Delete => self.http_client.delete(node.clone(), timeout, attempt).await,
+        };
+        self.record_request(&node, start_time.elapsed(), &res);
+
+        match res {
+            Err(Error::ResponseError { code: 404, url, .. }) => Err(Error::NotFound(url)),
+            res => res,
+        }
+    }
 }
@@ -0,0 +1,133 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Events of the node sync process about changes of the network
+
+use std::sync::{Arc, RwLock};
+
+use iota_types::block::protocol::ProtocolParameters;
+use tokio::sync::broadcast::{channel, Receiver, Sender};
+
+use crate::{
+    constants::NETWORK_EVENT_CHANNEL_CAPACITY,
+    error::{Error, Result},
+    Client,
+};
+
+/// Change of the network that was detected by the node sync process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetworkEvent {
+    /// The protocol parameters of the network changed, for example the protocol version, the rent structure or the
+    /// min PoW score. Blocks built with the previous protocol parameters might be invalid.
+    ProtocolParametersChanged {
+        /// The protocol parameters before the change.
+        previous: Box<ProtocolParameters>,
+        /// The new protocol parameters.
+        current: Box<ProtocolParameters>,
+    },
+    /// The bech32 HRP of the network changed.
+    Bech32HrpChanged {
+        /// The bech32 HRP before the change.
+        previous: String,
+        /// The new bech32 HRP.
+        current: String,
+    },
+    /// None of the nodes reports the network ID anymore that was used so far, so the client switched to the network
+    /// of the most nodes.
+    NetworkIdChanged {
+        /// The network ID before the change.
+        previous: u64,
+        /// The new network ID.
+        current: u64,
+    },
+    /// A healthy node reports a different network ID than the network of the client, so it isn't used. It's
+    /// published at every sync until the node is removed or reports the network of the client.
+    NodeNetworkMismatch {
+        /// The origin of the node URL, without credentials.
+        node: String,
+        /// The network ID the node reports.
+        network_id: u64,
+        /// The network ID of the client.
+        expected_network_id: u64,
+    },
+}
+
+/// Keeps the protocol parameters of the last sync and publishes the changes to the subscribers.
+#[derive(Clone, Debug)]
+pub(crate) struct NetworkTracker {
+    sender: Arc<Sender<NetworkEvent>>,
+    // `None` until a sync found healthy nodes
+    protocol_parameters: Arc<RwLock<Option<ProtocolParameters>>>,
+}
+
+impl Default for NetworkTracker {
+    fn default() -> Self {
+        let (sender, _) = channel(NETWORK_EVENT_CHANNEL_CAPACITY);
+        Self {
+            sender: Arc::new(sender),
+            protocol_parameters: Default::default(),
+        }
+    }
+}
+
+impl NetworkTracker {
+    pub(crate) fn subscribe(&self) -> Receiver<NetworkEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn publish(&self, event: NetworkEvent) {
+        log::debug!("network event: {event:?}");
+        // Sending only fails if nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+
+    /// The network name of the last sync with healthy nodes.
+    pub(crate) fn network_name(&self) -> Result<Option<String>> {
+        Ok(self
+            .protocol_parameters
+            .read()
+            .map_err(|_| Error::PoisonError)?
+            .as_ref()
+            .map(|protocol_parameters| protocol_parameters.network_name().to_string()))
+    }
+
+    /// Stores the protocol parameters of the current sync and publishes what changed since the previous one.
+    pub(crate) fn update(&self, current: &ProtocolParameters) -> Result<()> {
+        let previous = self
+            .protocol_parameters
+            .write()
+            .map_err(|_| Error::PoisonError)?
+            .replace(current.clone());
+
+        if let Some(previous) = previous {
+            if previous.network_id() != current.network_id() {
+                self.publish(NetworkEvent::NetworkIdChanged {
+                    previous: previous.network_id(),
+                    current: current.network_id(),
+                });
+            }
+            if previous.bech32_hrp() != current.bech32_hrp() {
+                self.publish(NetworkEvent::Bech32HrpChanged {
+                    previous: previous.bech32_hrp().to_string(),
+                    current: current.bech32_hrp().to_string(),
+                });
+            }
+            if &previous != current {
+                self.publish(NetworkEvent::ProtocolParametersChanged {
+                    previous: Box::new(previous),
+                    current: Box::new(current.clone()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Client {
+    /// Subscribes to the [`NetworkEvent`]s of the node sync process. Events that are published while the receiver
+    /// isn't polled are buffered up to a limit, after which the oldest ones are dropped.
+    pub fn subscribe_network_events(&self) -> Receiver<NetworkEvent> {
+        self.node_manager.network_tracker.subscribe()
+    }
+}
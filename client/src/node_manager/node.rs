@@ -1,18 +1,42 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::hash::Hash;
+use std::{collections::BTreeMap, hash::Hash, time::Duration};
 
+use iota_types::api::response::InfoResponse;
 use serde::{Deserialize, Serialize};
 pub use url::Url;
 
-/// Node authentication object.
+use crate::{
+    constants::{NODE_STATS_LATENCY_REFERENCE_MS, NODE_STATS_SMOOTHING_FACTOR},
+    node_manager::circuit_breaker::CircuitBreaker,
+};
+
+/// Node authentication object, serialized with the camelCase field names `jwt`, `basicAuthNamePwd`, `headers` and
+/// `clientCertificate`.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NodeAuth {
     /// JWT.
     pub jwt: Option<String>,
-    /// Username and password.
+    /// Username and password, sent as basic auth if no JWT is set.
+    #[serde(rename = "basicAuthNamePwd", alias = "basic_auth_name_pwd")]
     pub basic_auth_name_pwd: Option<(String, String)>,
+    /// Static headers that are added to every request, for example the API key of a gateway in front of the node.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Client certificate for mutual TLS.
+    #[serde(default, rename = "clientCertificate")]
+    pub client_certificate: Option<ClientCertificate>,
+}
+
+/// PEM encoded client certificate and private key, used to authenticate to a node with mutual TLS.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCertificate {
+    /// The PEM encoded certificate chain, starting with the client certificate.
+    pub certificate: String,
+    /// The PEM encoded private key of the client certificate.
+    pub private_key: String,
 }
 
 /// Node definition.
@@ -64,3 +88,55 @@ impl From<&NodeDto> for Node {
         }
     }
 }
+
+/// Request statistics of a node, used to rank the nodes for requests.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStats {
+    /// Moving average of the round-trip latency of the requests to the node, `None` if no request was sent yet.
+    pub latency: Option<Duration>,
+    /// Moving average of the rate of failed requests, between 0 and 1.
+    pub error_rate: f64,
+    /// Amount of requests sent to the node.
+    pub requests: u64,
+    /// Amount of requests that failed with a connection error, a timeout or a server error.
+    pub failed_requests: u64,
+    /// Amount of milestones the confirmed milestone of the node is behind the most synced node of the pool.
+    pub sync_lag: u32,
+}
+
+impl NodeStats {
+    /// Returns the score of the node, between 0 and 1, higher is better. Nodes without latency samples are assumed
+    /// to be fast, so they get tried.
+    pub fn score(&self) -> f64 {
+        let latency_ms = self.latency.map_or(0.0, |latency| latency.as_secs_f64() * 1000.0);
+        let latency_factor = NODE_STATS_LATENCY_REFERENCE_MS / (NODE_STATS_LATENCY_REFERENCE_MS + latency_ms);
+        let sync_factor = 1.0 / (1.0 + self.sync_lag as f64);
+
+        latency_factor * (1.0 - self.error_rate) * sync_factor
+    }
+
+    pub(crate) fn record_request(&mut self, latency: Duration, failed: bool) {
+        self.requests += 1;
+        if failed {
+            self.failed_requests += 1;
+        }
+
+        let failed = if failed { 1.0 } else { 0.0 };
+        self.error_rate += NODE_STATS_SMOOTHING_FACTOR * (failed - self.error_rate);
+        self.latency = Some(match self.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - NODE_STATS_SMOOTHING_FACTOR) + latency.mul_f64(NODE_STATS_SMOOTHING_FACTOR)
+            }
+            None => latency,
+        });
+    }
+}
+
+/// A node from the healthy node pool with its latest info, request statistics and circuit breaker.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct HealthyNode {
+    pub(crate) info: InfoResponse,
+    pub(crate) stats: NodeStats,
+    pub(crate) circuit_breaker: CircuitBreaker,
+}
@@ -0,0 +1,94 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hook to observe the requests that are sent to the nodes, for example to collect metrics
+
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::node_manager::http_client::HttpMethod;
+
+/// How a request relates to the other requests sent for the same API call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RequestAttempt {
+    /// The request to the first node that was tried.
+    First,
+    /// A request to a further node, after the previous nodes failed.
+    Fallback,
+    /// One of the requests sent in parallel to multiple nodes for quorum.
+    Quorum,
+}
+
+impl RequestAttempt {
+    // The attempt of the request to the node at this index of the nodes that are tried one after another
+    pub(crate) fn nth(index: usize) -> Self {
+        if index == 0 {
+            Self::First
+        } else {
+            Self::Fallback
+        }
+    }
+}
+
+/// A request that was sent to a node, reported to the [`RequestObserver`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestEvent {
+    /// The HTTP method.
+    pub method: HttpMethod,
+    /// The route with IDs and indexes replaced by placeholders, like `api/core/v2/blocks/{id}/metadata`, so it can be
+    /// used to group the requests.
+    pub route: String,
+    /// The URL path, without leading slash.
+    pub path: String,
+    /// The origin of the node URL, without credentials.
+    pub node_url: String,
+    /// The time until the response was received or the request failed.
+    pub latency: Duration,
+    /// The status code, `None` if no response was received.
+    pub status: Option<u16>,
+    /// The size of the request body in bytes.
+    pub request_bytes: usize,
+    /// The size of the response body in bytes.
+    pub response_bytes: usize,
+    /// If it was the first, a fallback or a quorum request.
+    pub attempt: RequestAttempt,
+}
+
+/// Observer that is called for every request sent to a node, set with
+/// [`ClientBuilder::with_request_observer()`](crate::ClientBuilder::with_request_observer).
+pub trait RequestObserver: std::fmt::Debug + Send + Sync {
+    /// Called after the response was received or the request failed. It's called on the task that sent the request,
+    /// so it shouldn't block.
+    fn on_request(&self, event: &RequestEvent);
+}
+
+/// Shared handle to a [`RequestObserver`], so it can be stored in the builders.
+#[derive(Clone, Debug)]
+pub(crate) struct ObserverHandle(pub(crate) Arc<dyn RequestObserver>);
+
+impl PartialEq for ObserverHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ObserverHandle {}
+
+/// Replaces the hex encoded IDs and the indexes in a path with placeholders.
+pub(crate) fn route_template(path: &str) -> String {
+    path.trim_start_matches('/')
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with("0x") {
+                "{id}"
+            } else if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                "{index}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
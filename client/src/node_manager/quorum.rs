@@ -0,0 +1,154 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Comparison of the responses of multiple nodes for quorum
+
+use std::collections::HashMap;
+
+use crypto::hashes::{blake2b::Blake2b256, Digest};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::Error, node_manager::node::Node};
+
+/// Report of a quorum request, showing which nodes agreed on which response.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumReport {
+    /// The response of every node that was asked.
+    pub responses: Vec<QuorumNodeResponse>,
+    /// The nodes grouped by the response they returned, the largest group first.
+    pub agreements: Vec<QuorumAgreement>,
+}
+
+/// The response of a single node in a [`QuorumReport`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumNodeResponse {
+    /// The origin of the node URL, without credentials.
+    pub node: String,
+    /// The hex encoded Blake2b256 hash of the response, without node specific fields like the ledger index. `None` if
+    /// the request failed.
+    pub response_hash: Option<String>,
+    /// The ledger index the node returned with the response, if the route has one. A node with a lower ledger index
+    /// than the others is likely not synced.
+    pub ledger_index: Option<u32>,
+    /// The error, if the request failed.
+    pub error: Option<String>,
+}
+
+/// Nodes that returned the same response in a [`QuorumReport`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumAgreement {
+    /// The hex encoded Blake2b256 hash of the response.
+    pub response_hash: String,
+    /// The nodes that returned the response.
+    pub nodes: Vec<String>,
+}
+
+/// Collects the responses of the nodes for a quorum request.
+#[derive(Default)]
+pub(crate) struct QuorumResponses {
+    responses: Vec<QuorumNodeResponse>,
+    // Amount of and first response text by response hash
+    results: HashMap<String, (usize, String)>,
+    error: Option<Error>,
+}
+
+impl QuorumResponses {
+    pub(crate) fn add(&mut self, node: &Node, response: Result<String, Error>) {
+        let node_origin = node.url.origin().ascii_serialization();
+        let response = response.and_then(|text| Ok((serde_json::from_str::<Value>(&text)?, text)));
+
+        match response {
+            Ok((mut value, text)) => {
+                let ledger_index = remove_ledger_index(&mut value);
+                let response_hash = prefix_hex::encode(Blake2b256::digest(value.to_string().as_bytes()).as_slice());
+
+                self.results.entry(response_hash.clone()).or_insert((0, text)).0 += 1;
+                self.responses.push(QuorumNodeResponse {
+                    node: node_origin,
+                    response_hash: Some(response_hash),
+                    ledger_index,
+                    error: None,
+                });
+            }
+            Err(error) => {
+                let error = match error {
+                    Error::ResponseError { code: 404, url, .. } => Error::NotFound(url),
+                    error => error,
+                };
+                self.responses.push(QuorumNodeResponse {
+                    node: node_origin,
+                    response_hash: None,
+                    ledger_index: None,
+                    error: Some(error.to_string()),
+                });
+                self.error.replace(error);
+            }
+        }
+    }
+
+    /// Amount of nodes that returned a response.
+    #[cfg(target_family = "wasm")]
+    pub(crate) fn successful_responses(&self) -> usize {
+        self.results.values().map(|(count, _)| count).sum()
+    }
+
+    /// Returns the response most nodes agreed on and by how many nodes.
+    pub(crate) fn majority(&self) -> Option<(usize, &str)> {
+        self.results
+            .values()
+            .max_by_key(|(count, _)| *count)
+            .map(|(count, text)| (*count, text.as_str()))
+    }
+
+    /// Returns the last error, for when no node returned a response.
+    pub(crate) fn into_error(self) -> Error {
+        self.error
+            .unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into()))
+    }
+
+    pub(crate) fn into_report(self) -> QuorumReport {
+        let mut agreements: Vec<QuorumAgreement> = self
+            .results
+            .into_keys()
+            .map(|response_hash| QuorumAgreement {
+                nodes: self
+                    .responses
+                    .iter()
+                    .filter(|response| response.response_hash.as_ref() == Some(&response_hash))
+                    .map(|response| response.node.clone())
+                    .collect(),
+                response_hash,
+            })
+            .collect();
+        agreements.sort_by(|a, b| {
+            b.nodes
+                .len()
+                .cmp(&a.nodes.len())
+                .then_with(|| a.response_hash.cmp(&b.response_hash))
+        });
+
+        QuorumReport {
+            responses: self.responses,
+            agreements,
+        }
+    }
+}
+
+// The ledger index depends on the sync state of a node and not on the requested resource, so it's removed before the
+// responses are compared.
+fn remove_ledger_index(value: &mut Value) -> Option<u32> {
+    let ledger_index = value.as_object_mut()?.remove("ledgerIndex");
+    let metadata_ledger_index = value
+        .get_mut("metadata")
+        .and_then(Value::as_object_mut)
+        .and_then(|metadata| metadata.remove("ledgerIndex"));
+
+    ledger_index
+        .or(metadata_ledger_index)
+        .and_then(|ledger_index| ledger_index.as_u64())
+        .and_then(|ledger_index| u32::try_from(ledger_index).ok())
+}
@@ -0,0 +1,61 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Options that change how single node API calls are sent
+
+use std::time::Duration;
+
+use crate::{node_manager::node::Node, Client};
+
+/// Options for node API calls, used with [`Client::with_request_options()`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// Timeout of every request, instead of the API timeout or the timeout of the route class.
+    pub timeout: Option<Duration>,
+    /// Node to which the requests are sent, instead of the nodes of the node pool. It's used even if it's unhealthy
+    /// or disabled and the requests aren't sent to other nodes if it fails, also not for quorum.
+    pub node: Option<Node>,
+    /// Sends the requests without quorum, also for routes that use quorum when it's enabled.
+    pub skip_quorum: bool,
+}
+
+impl RequestOptions {
+    /// Creates request options that don't change anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the timeout of every request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout.replace(timeout);
+        self
+    }
+
+    /// Sets the node to which the requests are sent.
+    pub fn with_node(mut self, node: impl Into<Node>) -> Self {
+        self.node.replace(node.into());
+        self
+    }
+
+    /// Sets if quorum should be skipped.
+    pub fn with_skip_quorum(mut self, skip_quorum: bool) -> Self {
+        self.skip_quorum = skip_quorum;
+        self
+    }
+}
+
+impl Client {
+    /// Returns a client that sends the node API calls with the given options, for example
+    /// `client.with_request_options(RequestOptions::new().with_skip_quorum(true)).get_output(&output_id)`. It shares
+    /// the node pool, the sync process and the cache with this client.
+    pub fn with_request_options(&self, options: RequestOptions) -> Self {
+        let mut client = self.clone();
+        client.node_manager.request_options = options;
+        // Dropping a client cancels its MQTT client, which must only happen with the original client
+        #[cfg(feature = "mqtt")]
+        {
+            client.mqtt_client = None;
+        }
+        client
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retries of idempotent requests with exponential backoff and jitter
+
+use std::{future::Future, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{DEFAULT_MAX_REQUEST_RETRIES, DEFAULT_REQUEST_RETRY_INITIAL_DELAY, DEFAULT_REQUEST_RETRY_MAX_DELAY},
+    error::{Error, Result},
+    utils::sleep,
+};
+
+/// Options for retrying GET requests when all nodes failed.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetryOptions {
+    /// Amount of retries after the first attempt, `0` disables retries.
+    pub max_retries: u32,
+    /// Delay before the first retry, it's doubled for every further retry.
+    pub initial_delay: Duration,
+    /// Upper bound of the delay between two attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_REQUEST_RETRIES,
+            initial_delay: DEFAULT_REQUEST_RETRY_INITIAL_DELAY,
+            max_delay: DEFAULT_REQUEST_RETRY_MAX_DELAY,
+        }
+    }
+}
+
+impl RetryOptions {
+    /// Returns the delay before the given retry, starting at 0. Half of the exponential delay is randomized, so clients
+    /// that failed at the same time don't retry at the same time.
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+
+        let mut random_bytes = [0u8; 4];
+        let jitter = match crypto::utils::rand::fill(&mut random_bytes) {
+            Ok(()) => u32::from_le_bytes(random_bytes) as f64 / u32::MAX as f64,
+            Err(_) => 1.0,
+        };
+
+        delay / 2 + (delay / 2).mul_f64(jitter)
+    }
+}
+
+/// Returns if a request that failed with this error could succeed when it's sent again.
+pub(crate) fn is_retryable(error: &Error) -> bool {
+    match error {
+        Error::ReqwestError(_) => true,
+        Error::ResponseError { code, .. } => *code >= 500,
+        _ => false,
+    }
+}
+
+/// Sends the request until it succeeds, fails with an error that isn't retryable or the retries are exhausted.
+pub(crate) async fn with_retries<T, F, Fut>(options: &RetryOptions, mut request: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retry = 0;
+    loop {
+        match request().await {
+            Err(error) if retry < options.max_retries && is_retryable(&error) => {
+                let delay = options.delay(retry);
+                log::debug!("retrying request in {:?} ms after: {error}", delay.as_millis());
+                sleep(delay).await;
+                retry += 1;
+            }
+            res => return res,
+        }
+    }
+}
@@ -1,56 +1,163 @@
 // Copyright 2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
 #[cfg(not(target_family = "wasm"))]
 use {
     crate::NetworkInfo,
     iota_types::{api::response::InfoResponse, block::protocol::ProtocolParameters},
-    std::collections::HashMap,
     std::{
         collections::HashSet,
         sync::{Arc, RwLock},
-        time::Duration,
     },
     tokio::{runtime::Runtime, sync::broadcast::Receiver, time::sleep},
 };
 
-use super::Node;
+use url::Url;
+
+use super::{
+    builder::validate_url,
+    node::{Node, NodeDto, NodeStats},
+};
+#[cfg(not(target_family = "wasm"))]
+use super::{circuit_breaker::CircuitBreaker, network_events::NetworkEvent, node::HealthyNode, NodeManager};
 use crate::{Client, Error, Result};
 
 impl Client {
     /// Get a node candidate from the healthy node pool.
     pub fn get_node(&self) -> Result<Node> {
-        if let Some(primary_node) = &self.node_manager.primary_node {
+        if let Some(primary_node) = &*self.node_manager.primary_node.read().map_err(|_| Error::PoisonError)? {
             return Ok(primary_node.clone());
         }
 
-        let pool = self.node_manager.nodes.clone();
+        let pool = self.node_manager.nodes.read().map_err(|_| Error::PoisonError)?;
 
-        pool.into_iter().next().ok_or(Error::HealthyNodePoolEmpty)
+        pool.iter().next().cloned().ok_or(Error::HealthyNodePoolEmpty)
     }
 
     /// returns the unhealthy nodes.
     #[cfg(not(target_family = "wasm"))]
-    pub fn unhealthy_nodes(&self) -> HashSet<&Node> {
+    pub fn unhealthy_nodes(&self) -> HashSet<Node> {
+        match (self.node_manager.healthy_nodes.read(), self.node_manager.nodes.read()) {
+            (Ok(healthy_nodes), Ok(nodes)) => nodes
+                .iter()
+                .filter(|node| !healthy_nodes.contains_key(node))
+                .cloned()
+                .collect(),
+            _ => HashSet::new(),
+        }
+    }
+
+    /// Returns the nodes of the node pool, sorted by their URL. The primary node is only included if it was added to
+    /// the pool too.
+    pub fn list_nodes(&self) -> Result<Vec<Node>> {
+        let mut nodes: Vec<Node> = self
+            .node_manager
+            .nodes
+            .read()
+            .map_err(|_| Error::PoisonError)?
+            .iter()
+            .cloned()
+            .collect();
+        nodes.sort_by(|a, b| a.url.as_str().cmp(b.url.as_str()));
+
+        Ok(nodes)
+    }
+
+    /// Adds a node to the node pool, replacing a node with the same URL origin, for example to change its
+    /// authentication or to disable it. Unless the node health is ignored, the node pool is synced right away, so the
+    /// node is used for requests once it's healthy.
+    pub async fn add_node(&self, node: NodeDto) -> Result<()> {
+        let mut node = Node::from(node);
+        node.url = validate_url(node.url)?;
+        {
+            let mut nodes = self.node_manager.nodes.write().map_err(|_| Error::PoisonError)?;
+            nodes.retain(|n| n.url.origin() != node.url.origin());
+            nodes.insert(node);
+        }
+
+        self.sync_node_pool().await
+    }
+
+    /// Removes the node with the URL origin of `url` from the node pool and unsets it as primary node. Returns if a
+    /// node was removed.
+    pub fn remove_node(&self, url: &str) -> Result<bool> {
+        let origin = Url::parse(url)?.origin();
+
+        let mut nodes = self.node_manager.nodes.write().map_err(|_| Error::PoisonError)?;
+        let pool_size = nodes.len();
+        nodes.retain(|n| n.url.origin() != origin);
+        let mut removed = nodes.len() != pool_size;
+        drop(nodes);
+
+        let mut primary_node = self.node_manager.primary_node.write().map_err(|_| Error::PoisonError)?;
+        if matches!(&*primary_node, Some(n) if n.url.origin() == origin) {
+            primary_node.take();
+            removed = true;
+        }
+        drop(primary_node);
+
+        // Stop using the node right away instead of with the next sync
         self.node_manager
+            .healthy_nodes
+            .write()
+            .map_err(|_| Error::PoisonError)?
+            .retain(|n, _| n.url.origin() != origin);
+
+        Ok(removed)
+    }
+
+    /// Sets the node that will be tried first for all requests, or unsets it with `None`. Returns the previous primary
+    /// node. Unless the node health is ignored, the node pool is synced right away.
+    pub async fn set_primary_node(&self, node: Option<NodeDto>) -> Result<Option<Node>> {
+        let node = match node {
+            Some(node) => {
+                let mut node = Node::from(node);
+                node.url = validate_url(node.url)?;
+                Some(node)
+            }
+            None => None,
+        };
+        let previous_node = std::mem::replace(
+            &mut *self.node_manager.primary_node.write().map_err(|_| Error::PoisonError)?,
+            node,
+        );
+
+        self.sync_node_pool().await?;
+
+        Ok(previous_node)
+    }
+
+    // Syncs the current node pool, so changes to it don't have to wait for the sync process
+    async fn sync_node_pool(&self) -> Result<()> {
+        #[cfg(not(target_family = "wasm"))]
+        if !self.node_manager.ignore_node_health {
+            let nodes = self.node_manager.nodes_to_sync()?;
+            Self::sync_nodes(&self.node_manager, &nodes, &self.network_info).await?;
+            self.node_manager.retain_nodes_to_sync()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the request statistics of the healthy nodes, which are used to rank them for requests.
+    pub fn node_stats(&self) -> Result<HashMap<Node, NodeStats>> {
+        Ok(self
+            .node_manager
             .healthy_nodes
             .read()
-            .map_or(HashSet::new(), |healthy_nodes| {
-                self.node_manager
-                    .nodes
-                    .iter()
-                    .filter(|node| !healthy_nodes.contains_key(node))
-                    .collect()
-            })
+            .map_err(|_| crate::Error::PoisonError)?
+            .iter()
+            .map(|(node, healthy_node)| (node.clone(), healthy_node.stats.clone()))
+            .collect())
     }
 
-    /// Sync the node lists per node_sync_interval milliseconds
+    /// Sync the node lists per node_sync_interval milliseconds, with the nodes that are in the node pool at that time
     #[cfg(not(target_family = "wasm"))]
     pub(crate) fn start_sync_process(
         runtime: &Runtime,
-        sync: Arc<RwLock<HashMap<Node, InfoResponse>>>,
-        nodes: HashSet<Node>,
-        node_sync_interval: Duration,
+        node_manager: NodeManager,
         network_info: Arc<RwLock<NetworkInfo>>,
         mut kill: Receiver<()>,
     ) {
@@ -60,8 +167,16 @@ impl Client {
                     _ = async {
                         // delay first since the first `sync_nodes` call is made by the builder
                         // to ensure the node list is filled before the client is used
-                        sleep(node_sync_interval).await;
-                        if let Err(e) = Client::sync_nodes(&sync, &nodes, &network_info).await {
+                        sleep(node_manager.node_sync_interval).await;
+                        let result = match node_manager.nodes_to_sync() {
+                            Ok(nodes) => {
+                                Client::sync_nodes(&node_manager, &nodes, &network_info)
+                                    .await
+                                    .and_then(|_| node_manager.retain_nodes_to_sync())
+                            }
+                            Err(e) => Err(e),
+                        };
+                        if let Err(e) = result {
                             log::warn!("Syncing nodes failed: {e}");
                         }
                     } => {}
@@ -73,17 +188,43 @@ impl Client {
 
     #[cfg(not(target_family = "wasm"))]
     pub(crate) async fn sync_nodes(
-        sync: &Arc<RwLock<HashMap<Node, InfoResponse>>>,
+        node_manager: &NodeManager,
         nodes: &HashSet<Node>,
         network_info: &Arc<RwLock<NetworkInfo>>,
     ) -> Result<()> {
         log::debug!("sync_nodes");
         let mut healthy_nodes = HashMap::new();
         let mut network_nodes: HashMap<String, Vec<(InfoResponse, Node)>> = HashMap::new();
+        // Keep the stats and circuit breakers of the previous syncs
+        let mut previous_nodes: HashMap<Node, (NodeStats, CircuitBreaker)> = node_manager
+            .healthy_nodes
+            .read()
+            .map_err(|_| crate::Error::PoisonError)?
+            .iter()
+            .map(|(node, healthy_node)| {
+                (
+                    node.clone(),
+                    (healthy_node.stats.clone(), healthy_node.circuit_breaker.clone()),
+                )
+            })
+            .collect();
 
         for node in nodes {
+            let start_time = instant::Instant::now();
+            let info = Client::get_node_info_with_http_client(
+                &node_manager.http_client,
+                node.url.as_ref(),
+                node.auth.clone(),
+            )
+            .await;
+            previous_nodes
+                .entry(node.clone())
+                .or_default()
+                .0
+                .record_request(start_time.elapsed(), info.is_err());
+
             // Put the healthy node url into the network_nodes
-            if let Ok(info) = Client::get_node_info(node.url.as_ref(), None).await {
+            if let Ok(info) = info {
                 if info.status.is_healthy {
                     match network_nodes.get_mut(&info.protocol.network_name) {
                         Some(network_node_entry) => {
@@ -101,30 +242,81 @@ impl Client {
             }
         }
 
-        // Get network_id with the most nodes
-        let mut most_nodes = ("network_id", 0);
-        for (network_id, node) in &network_nodes {
-            if node.len() > most_nodes.1 {
-                most_nodes.0 = network_id;
-                most_nodes.1 = node.len();
-            }
-        }
+        // Nodes of different networks are never mixed, the network of the previous sync is kept as long as any node
+        // reports it, else the network with the most nodes is used
+        let network_tracker = &node_manager.network_tracker;
+        let network_name = match network_tracker.network_name()? {
+            Some(network_name) if network_nodes.contains_key(&network_name) => Some(network_name),
+            _ => network_nodes
+                .iter()
+                .max_by_key(|(_, nodes)| nodes.len())
+                .map(|(network_name, _)| network_name.clone()),
+        };
 
-        if let Some(nodes) = network_nodes.get(most_nodes.0) {
-            if let Some((info, _node_url)) = nodes.first() {
+        if let Some(nodes) = network_name.as_ref().and_then(|network_name| network_nodes.get(network_name)) {
+            // The most synced node has the latest protocol parameters
+            let (latest_info, _) = nodes
+                .iter()
+                .max_by_key(|(info, _)| info.status.confirmed_milestone.index)
+                .expect("network nodes are never empty");
+            let protocol_parameters = ProtocolParameters::try_from(latest_info.protocol.clone())?;
+            let latest_confirmed_milestone_index = latest_info.status.confirmed_milestone.index;
+            {
                 let mut network_info = network_info.write().map_err(|_| crate::Error::PoisonError)?;
 
-                network_info.latest_milestone_timestamp = info.status.latest_milestone.timestamp;
-                network_info.protocol_parameters = ProtocolParameters::try_from(info.protocol.clone())?;
+                network_info.latest_milestone_timestamp = latest_info.status.latest_milestone.timestamp;
+                network_info.protocol_parameters = protocol_parameters.clone();
+            }
+            network_tracker.update(&protocol_parameters)?;
+
+            for (other_network_name, other_nodes) in &network_nodes {
+                if Some(other_network_name) == network_name.as_ref() {
+                    continue;
+                }
+                for (info, node) in other_nodes {
+                    // A node of another network doesn't fail the sync, even if its protocol parameters are invalid
+                    let node_network_id = match ProtocolParameters::try_from(info.protocol.clone()) {
+                        Ok(node_protocol_parameters) => node_protocol_parameters.network_id(),
+                        Err(e) => {
+                            log::warn!(
+                                "{} reports network {other_network_name} with invalid protocol parameters: {e}",
+                                node.url.origin().ascii_serialization()
+                            );
+                            continue;
+                        }
+                    };
+                    log::warn!(
+                        "{} reports network {other_network_name} instead of {}, it isn't used",
+                        node.url.origin().ascii_serialization(),
+                        protocol_parameters.network_name()
+                    );
+                    network_tracker.publish(NetworkEvent::NodeNetworkMismatch {
+                        node: node.url.origin().ascii_serialization(),
+                        network_id: node_network_id,
+                        expected_network_id: protocol_parameters.network_id(),
+                    });
+                }
             }
 
             for (info, node_url) in nodes {
-                healthy_nodes.insert(node_url.clone(), info.clone());
+                let (mut stats, circuit_breaker) = previous_nodes.remove(node_url).unwrap_or_default();
+                stats.sync_lag = latest_confirmed_milestone_index - info.status.confirmed_milestone.index;
+                healthy_nodes.insert(
+                    node_url.clone(),
+                    HealthyNode {
+                        info: info.clone(),
+                        stats,
+                        circuit_breaker,
+                    },
+                );
             }
         }
 
         // Update the sync list.
-        *sync.write().map_err(|_| crate::Error::PoisonError)? = healthy_nodes;
+        *node_manager
+            .healthy_nodes
+            .write()
+            .map_err(|_| crate::Error::PoisonError)? = healthy_nodes;
 
         Ok(())
     }
@@ -0,0 +1,71 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Timeouts per class of node API routes
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Class of node API routes that share a timeout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RouteClass {
+    /// The `health` and `api/core/v2/info` routes.
+    Health,
+    /// The core API routes, except the ones of the other classes.
+    Core,
+    /// The `utxo-changes` routes of the milestones, which can return large responses.
+    UtxoChanges,
+    /// The indexer routes.
+    Indexer,
+    /// The routes of all other plugins, like participation.
+    Plugins,
+}
+
+impl RouteClass {
+    /// Returns the class of the route with the given path.
+    pub fn of(path: &str) -> Self {
+        let path = path.trim_start_matches('/');
+        if path == "health" || path == "api/core/v2/info" {
+            Self::Health
+        } else if path.starts_with("api/indexer/") {
+            Self::Indexer
+        } else if path.starts_with("api/core/") && path.ends_with("/utxo-changes") {
+            Self::UtxoChanges
+        } else if path.starts_with("api/core/") {
+            Self::Core
+        } else {
+            Self::Plugins
+        }
+    }
+}
+
+/// Timeouts of the GET requests per [`RouteClass`]. Classes without a timeout use the API timeout of the client.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RouteTimeouts {
+    /// Timeout of the [`RouteClass::Health`] routes.
+    pub health: Option<Duration>,
+    /// Timeout of the [`RouteClass::Core`] routes.
+    pub core: Option<Duration>,
+    /// Timeout of the [`RouteClass::UtxoChanges`] routes.
+    pub utxo_changes: Option<Duration>,
+    /// Timeout of the [`RouteClass::Indexer`] routes.
+    pub indexer: Option<Duration>,
+    /// Timeout of the [`RouteClass::Plugins`] routes.
+    pub plugins: Option<Duration>,
+}
+
+impl RouteTimeouts {
+    /// Returns the timeout of the route class, if one is set.
+    pub fn get(&self, class: RouteClass) -> Option<Duration> {
+        match class {
+            RouteClass::Health => self.health,
+            RouteClass::Core => self.core,
+            RouteClass::UtxoChanges => self.utxo_changes,
+            RouteClass::Indexer => self.indexer,
+            RouteClass::Plugins => self.plugins,
+        }
+    }
+}
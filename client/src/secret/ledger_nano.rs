@@ -198,7 +198,8 @@ impl SecretManageExt for LedgerSecretManager {
             log::debug!("[LEDGER] {:?} {:?}", input_bip32_indices, essence_hash);
             ledger.prepare_blind_signing(input_bip32_indices, essence_hash)?;
         } else {
-            // figure out the remainder address and bip32 index (if there is one)
+            // figure out the remainder address and bip32 index (if there is one), the Ledger app supports a single
+            // remainder, so additional remainders of a split remainder are shown like the other outputs
             let (remainder_address, remainder_bip32): (Option<&Address>, LedgerBIP32Index) =
                 match &prepared_transaction.remainder {
                     Some(a) => {
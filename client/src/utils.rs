@@ -3,7 +3,7 @@
 
 //! Utility functions for IOTA
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use crypto::{
     hashes::{blake2b::Blake2b256, Digest},
@@ -101,6 +101,14 @@ pub async fn request_funds_from_faucet(url: &str, bech32_address: &str) -> Resul
     Ok(faucet_response)
 }
 
+/// Sleeps for the duration, also on wasm where there's no tokio timer.
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(target_family = "wasm")]
+    gloo_timers::future::TimeoutFuture::new(duration.as_millis().try_into().unwrap_or(u32::MAX)).await;
+    #[cfg(not(target_family = "wasm"))]
+    tokio::time::sleep(duration).await;
+}
+
 impl Client {
     /// Transforms bech32 to hex
     pub fn bech32_to_hex(bech32: &str) -> crate::Result<String> {
@@ -0,0 +1,158 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shared setup of the tests against the in-memory mock node, so they don't need a running node.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use iota_client::{
+    api_types::response::{OutputMetadataResponse, OutputWithMetadataResponse},
+    block::{
+        input::{Input, UtxoInput},
+        output::{dto::OutputDto, Output, OutputId},
+        payload::{
+            milestone::{MilestoneEssence, MilestoneId, MilestoneOptions, MilestonePayload},
+            transaction::{RegularTransactionEssence, TransactionEssence, TransactionPayload},
+            Payload,
+        },
+        protocol::ProtocolParameters,
+        rand::{
+            bytes::rand_bytes_array, milestone::rand_merkle_root, output::rand_inputs_commitment, parents::rand_parents,
+        },
+        signature::{Ed25519Signature, Signature},
+        unlock::{ReferenceUnlock, SignatureUnlock, Unlock, Unlocks},
+        Block, BlockBuilder,
+    },
+    crypto::signatures::ed25519::SecretKey,
+    node_manager::{
+        http_client::{HttpRequest, HttpResponse, HttpTransport},
+        mock::MockNode,
+        observer::{RequestEvent, RequestObserver},
+    },
+    Client, Result,
+};
+
+pub const NODE_URL: &str = "http://localhost:14265";
+pub const OUTPUT_ID: &str = "0x1e857d380f813d8035e487b6dfd2ff4740b6775273ba1b576f01381ba2a1a44c0000";
+
+pub fn setup_client(node: &MockNode) -> Client {
+    Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node.clone())
+        .finish()
+        .unwrap()
+}
+
+// Routes the requests to a different mock node per host.
+#[derive(Debug)]
+pub struct MockNodes(pub HashMap<String, MockNode>);
+
+#[async_trait]
+impl HttpTransport for MockNodes {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        self.0[request.url.host_str().unwrap()].send(request).await
+    }
+}
+
+// Records the request events.
+#[derive(Debug, Default, Clone)]
+pub struct RecordingObserver(pub Arc<Mutex<Vec<RequestEvent>>>);
+
+impl RequestObserver for RecordingObserver {
+    fn on_request(&self, event: &RequestEvent) {
+        self.0.lock().unwrap().push(event.clone());
+    }
+}
+
+pub fn treasury_requests(node: &MockNode) -> usize {
+    node.requests()
+        .iter()
+        .filter(|request| request.url.path() == "/api/core/v2/treasury")
+        .count()
+}
+
+pub fn output_with_metadata(output_id: OutputId, output: &Output) -> OutputWithMetadataResponse {
+    OutputWithMetadataResponse {
+        metadata: OutputMetadataResponse {
+            block_id: "0x2bb2ab8f8bc4ab7dd45b2e5c1d5e5a3b6c2fa1b0a5b9e7b6e1d0f6c3a8b4e2d1".to_string(),
+            transaction_id: output_id.transaction_id().to_string(),
+            output_index: output_id.index(),
+            is_spent: false,
+            milestone_index_spent: None,
+            milestone_timestamp_spent: None,
+            transaction_id_spent: None,
+            milestone_index_booked: 900,
+            milestone_timestamp_booked: 1667450000,
+            ledger_index: 1000,
+        },
+        output: OutputDto::from(output),
+    }
+}
+
+// A milestone with random signatures, which aren't checked by the mock node.
+pub fn milestone(index: u32, timestamp: u32, previous_milestone_id: MilestoneId) -> MilestonePayload {
+    let essence = MilestoneEssence::new(
+        index.into(),
+        timestamp,
+        2,
+        previous_milestone_id,
+        rand_parents(),
+        rand_merkle_root(),
+        rand_merkle_root(),
+        Vec::new(),
+        MilestoneOptions::new(vec![]).unwrap(),
+    )
+    .unwrap();
+    let signatures = vec![Signature::from(Ed25519Signature::new(
+        rand_bytes_array(),
+        rand_bytes_array(),
+    ))];
+
+    MilestonePayload::new(essence, signatures).unwrap()
+}
+
+// Builds a milestone with the signature of the given key.
+pub fn signed_milestone(index: u32, previous_milestone_id: MilestoneId, key: &SecretKey) -> MilestonePayload {
+    sign_milestone(milestone(index, 1000 + index, previous_milestone_id).essence().clone(), key)
+}
+
+pub fn sign_milestone(essence: MilestoneEssence, key: &SecretKey) -> MilestonePayload {
+    let signature = Ed25519Signature::new(key.public_key().to_bytes(), key.sign(&essence.hash()).to_bytes());
+
+    MilestonePayload::new(essence, vec![Signature::from(signature)]).unwrap()
+}
+
+// Builds a block with a transaction of the given inputs and outputs.
+pub fn transaction_block(inputs: &[OutputId], outputs: Vec<Output>, protocol_parameters: &ProtocolParameters) -> Block {
+    let essence = RegularTransactionEssence::builder(protocol_parameters.network_id(), rand_inputs_commitment())
+        .with_inputs(
+            inputs
+                .iter()
+                .map(|output_id| Input::Utxo(UtxoInput::from(*output_id)))
+                .collect(),
+        )
+        .with_outputs(outputs)
+        .finish(protocol_parameters)
+        .unwrap();
+    let signature = Signature::from(Ed25519Signature::new(rand_bytes_array(), rand_bytes_array()));
+    let unlocks = (0..inputs.len())
+        .map(|index| match index {
+            0 => Unlock::Signature(SignatureUnlock::from(signature.clone())),
+            _ => Unlock::Reference(ReferenceUnlock::new(0).unwrap()),
+        })
+        .collect();
+    let transaction =
+        TransactionPayload::new(TransactionEssence::Regular(essence), Unlocks::new(unlocks).unwrap()).unwrap();
+
+    BlockBuilder::<u64>::new(rand_parents())
+        .with_payload(Payload::from(transaction))
+        .finish(0)
+        .unwrap()
+}
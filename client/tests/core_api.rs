@@ -0,0 +1,279 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+// Core API tests against the in-memory mock node: blocks, outputs, the cache and the management routes.
+
+mod common;
+
+use std::{collections::HashMap, str::FromStr};
+
+use iota_client::{
+    api_types::{
+        dto::{PeerDto, RelationDto},
+        response::CreateSnapshotResponse,
+    },
+    block::{
+        metadata::BlockInclusionState,
+        output::{dto::OutputDto, unlock_condition::AddressUnlockCondition, BasicOutputBuilder, Output, OutputId},
+        rand::address::rand_address,
+        BlockId,
+    },
+    node_api::{core::routes::PruneDatabaseOptions, indexer::query_parameters::QueryParameter},
+    node_manager::{
+        http_client::{HttpMethod, HttpRequest, HttpResponse},
+        mock::MockNode,
+        node::NodeAuth,
+    },
+    CacheOptions, Client, Error,
+};
+use packable::PackableExt;
+
+use self::common::{output_with_metadata, setup_client, MockNodes, NODE_URL, OUTPUT_ID};
+
+#[tokio::test]
+async fn mock_node_post_and_get_block() {
+    let node = MockNode::new();
+    // The mock node doesn't check the nonce, so remote PoW is used to skip the local PoW.
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node.clone())
+        .with_local_pow(false)
+        .finish()
+        .unwrap();
+
+    let block = client
+        .block()
+        .with_tag(b"Hello".to_vec())
+        .with_data(b"Tangle".to_vec())
+        .finish()
+        .await
+        .unwrap();
+
+    assert_eq!(client.get_block(&block.id()).await.unwrap(), block);
+    assert_eq!(client.get_block_raw(&block.id()).await.unwrap(), block.pack_to_vec());
+
+    let metadata = client.get_block_metadata(&block.id()).await.unwrap();
+    assert_eq!(metadata.block_id(), &block.id());
+    assert_eq!(metadata.parents(), block.parents().iter().copied().collect::<Vec<_>>());
+    assert_eq!(
+        metadata.inclusion_state(),
+        &BlockInclusionState::NoTransaction {
+            referenced_by_milestone_index: 1000
+        }
+    );
+
+    let post_request = node
+        .requests()
+        .into_iter()
+        .find(|request| request.method == HttpMethod::Post)
+        .unwrap();
+    assert_eq!(post_request.url.path(), "/api/core/v2/blocks");
+    assert!(post_request.header("user-agent").unwrap().starts_with("iota-client/"));
+}
+
+#[tokio::test]
+async fn mock_node_outputs() {
+    let output_id = OutputId::from_str(OUTPUT_ID).unwrap();
+    let token_supply = setup_client(&MockNode::new()).get_token_supply().await.unwrap();
+    let output = Output::Basic(
+        BasicOutputBuilder::new_with_amount(1_000_000)
+            .unwrap()
+            .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+            .finish(token_supply)
+            .unwrap(),
+    );
+
+    let node = MockNode::new()
+        .with_output(&output_with_metadata(output_id, &output))
+        .unwrap()
+        .with_output_ids("api/indexer/v1/outputs/basic", &[output_id])
+        .unwrap();
+    let client = setup_client(&node);
+
+    let output_ids = client
+        .basic_output_ids(vec![QueryParameter::HasNativeTokens(false)])
+        .await
+        .unwrap();
+    assert_eq!(output_ids, vec![output_id]);
+
+    let outputs = client.get_outputs(output_ids).await.unwrap();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].output, OutputDto::from(&output));
+
+    let metadata = client.get_output_metadata(&output_id).await.unwrap();
+    assert_eq!(metadata.output_id().unwrap(), output_id);
+}
+
+#[tokio::test]
+async fn mock_node_cache() {
+    let spent_output_id = OutputId::from_str(OUTPUT_ID).unwrap();
+    let token_supply = setup_client(&MockNode::new()).get_token_supply().await.unwrap();
+    let output = Output::Basic(
+        BasicOutputBuilder::new_with_amount(1_000_000)
+            .unwrap()
+            .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+            .finish(token_supply)
+            .unwrap(),
+    );
+    let mut spent_output = output_with_metadata(spent_output_id, &output);
+    spent_output.metadata.is_spent = true;
+
+    let node = MockNode::new().with_output(&spent_output).unwrap();
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node.clone())
+        .with_local_pow(false)
+        .with_cache_options(CacheOptions {
+            max_entries: 2,
+            ..Default::default()
+        })
+        .finish()
+        .unwrap();
+    let requests = |path: String| {
+        node.requests()
+            .iter()
+            .filter(|request| request.method == HttpMethod::Get && request.url.path() == path)
+            .count()
+    };
+    let block_requests = |block_id: &BlockId| requests(format!("/api/core/v2/blocks/{block_id}"));
+
+    let first_block = client.block().with_tag(b"first".to_vec()).finish().await.unwrap();
+    let second_block = client.block().with_tag(b"second".to_vec()).finish().await.unwrap();
+    let third_block = client.block().with_tag(b"third".to_vec()).finish().await.unwrap();
+    client.clear_cache();
+    assert_eq!(client.cache_len(), Some(0));
+    let first_block_requests = block_requests(&first_block.id());
+    let second_block_requests = block_requests(&second_block.id());
+
+    // Outputs aren't cached, the ledger index in their metadata changes with every milestone
+    for _ in 0..3 {
+        assert_eq!(client.get_output(&spent_output_id).await.unwrap(), spent_output);
+    }
+    assert_eq!(requests(format!("/api/core/v2/outputs/{spent_output_id}")), 3);
+    assert_eq!(client.cache_len(), Some(0));
+
+    for _ in 0..3 {
+        assert_eq!(client.get_block(&first_block.id()).await.unwrap(), first_block);
+    }
+    assert_eq!(block_requests(&first_block.id()), first_block_requests + 1);
+
+    // The first block was used least recently, so it's evicted first
+    client.get_block(&second_block.id()).await.unwrap();
+    assert_eq!(client.cache_len(), Some(2));
+    client.get_block(&third_block.id()).await.unwrap();
+    assert_eq!(client.cache_len(), Some(2));
+    client.get_block(&second_block.id()).await.unwrap();
+    assert_eq!(block_requests(&second_block.id()), second_block_requests + 1);
+    client.get_block(&first_block.id()).await.unwrap();
+    assert_eq!(block_requests(&first_block.id()), first_block_requests + 2);
+
+    assert_eq!(setup_client(&node).cache_len(), None);
+}
+
+#[tokio::test]
+async fn mock_node_management() {
+    let peer_id = "12D3KooWRVt4Engu27jHnF2RjfX48EqiAqJbgLfFdHNt3Vn6BtJK";
+    let peer = PeerDto {
+        id: peer_id.to_string(),
+        multi_addresses: vec![format!("/ip4/127.0.0.1/tcp/15600/p2p/{peer_id}")],
+        alias: Some("peer".to_string()),
+        relation: RelationDto::Known,
+        connected: true,
+        gossip: None,
+    };
+    let node = MockNode::new()
+        .with_json(HttpMethod::Get, &format!("api/core/v2/peers/{peer_id}"), &peer)
+        .unwrap()
+        .with_json(HttpMethod::Post, "api/core/v2/peers", &peer)
+        .unwrap()
+        .with_response(
+            HttpMethod::Delete,
+            &format!("api/core/v2/peers/{peer_id}"),
+            HttpResponse {
+                status: 204,
+                body: Vec::new(),
+            },
+        )
+        .with_json(
+            HttpMethod::Post,
+            "api/core/v2/control/snapshots/create",
+            &CreateSnapshotResponse {
+                index: 100,
+                file_path: "snapshots/full_snapshot.bin".to_string(),
+            },
+        )
+        .unwrap()
+        .with_status(HttpMethod::Post, "api/core/v2/control/database/prune", 500);
+    // A primary node and another node, so the requests could be sent to both
+    let other_node = MockNode::new();
+    let client = Client::builder()
+        .with_primary_node(
+            "http://primary:14265",
+            Some(NodeAuth {
+                jwt: Some("token".to_string()),
+                basic_auth_name_pwd: None,
+                headers: Default::default(),
+                client_certificate: None,
+            }),
+        )
+        .unwrap()
+        .with_node("http://other:14265")
+        .unwrap()
+        .with_transport(MockNodes(HashMap::from([
+            ("primary".to_string(), node.clone()),
+            ("other".to_string(), other_node.clone()),
+        ])))
+        .finish()
+        .unwrap();
+
+    assert_eq!(client.get_peer(peer_id).await.unwrap(), peer);
+    assert_eq!(
+        client.add_peer(&peer.multi_addresses[0], Some("peer")).await.unwrap(),
+        peer
+    );
+    client.remove_peer(peer_id).await.unwrap();
+    assert!(matches!(client.get_peer("unknown").await, Err(Error::NotFound(_))));
+    assert_eq!(client.create_snapshot(100).await.unwrap().index, 100);
+    // Failed management requests aren't retried or sent to other nodes
+    assert!(client.prune_database(PruneDatabaseOptions::Depth(10)).await.is_err());
+
+    let requests = node
+        .requests()
+        .into_iter()
+        .filter(|request| request.url.path() != "/api/core/v2/info")
+        .collect::<Vec<_>>();
+    assert_eq!(
+        requests
+            .iter()
+            .map(|request| (request.method, request.url.path()))
+            .collect::<Vec<_>>(),
+        vec![
+            (HttpMethod::Get, &*format!("/api/core/v2/peers/{peer_id}")),
+            (HttpMethod::Post, "/api/core/v2/peers"),
+            (HttpMethod::Delete, &*format!("/api/core/v2/peers/{peer_id}")),
+            (HttpMethod::Get, "/api/core/v2/peers/unknown"),
+            (HttpMethod::Post, "/api/core/v2/control/snapshots/create"),
+            (HttpMethod::Post, "/api/core/v2/control/database/prune"),
+        ]
+    );
+    assert!(requests
+        .iter()
+        .all(|request| request.header("authorization") == Some("Bearer token")));
+    let json = |request: &HttpRequest| {
+        serde_json::from_slice::<serde_json::Value>(request.body.as_ref().unwrap()).unwrap()
+    };
+    assert_eq!(
+        json(&requests[1]),
+        serde_json::json!({ "multiAddress": peer.multi_addresses[0], "alias": "peer" })
+    );
+    assert_eq!(json(&requests[4]), serde_json::json!({ "index": 100 }));
+    assert_eq!(json(&requests[5]), serde_json::json!({ "depth": 10 }));
+    assert!(
+        other_node
+            .requests()
+            .iter()
+            .all(|request| request.url.path() == "/api/core/v2/info")
+    );
+}
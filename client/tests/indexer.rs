@@ -0,0 +1,174 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+// Indexer tests against the in-memory mock node: pagination and typed output queries.
+
+mod common;
+
+use std::str::FromStr;
+
+use futures::TryStreamExt;
+use iota_client::{
+    block::{
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, Output, OutputId},
+        rand::{address::rand_address, output::rand_output_id},
+    },
+    node_api::indexer::{
+        pagination::Cursor,
+        queries::{AliasOutputQuery, BasicOutputQuery, FoundryOutputQuery, NftOutputQuery, OutputQuery},
+        query_parameters::QueryParameter,
+    },
+    node_manager::{mock::MockNode, retry::RetryOptions},
+    Client, Error,
+};
+
+use self::common::{output_with_metadata, setup_client, NODE_URL, OUTPUT_ID};
+
+#[tokio::test]
+async fn mock_node_pagination() {
+    let route = "api/indexer/v1/outputs/basic";
+    let token_supply = setup_client(&MockNode::new()).get_token_supply().await.unwrap();
+    let outputs = (0..2)
+        .map(|_| {
+            let output = Output::Basic(
+                BasicOutputBuilder::new_with_amount(1_000_000)
+                    .unwrap()
+                    .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+                    .finish(token_supply)
+                    .unwrap(),
+            );
+            output_with_metadata(rand_output_id(), &output)
+        })
+        .collect::<Vec<_>>();
+    let output_ids = outputs
+        .iter()
+        .map(|output| output.metadata.output_id().unwrap())
+        .collect::<Vec<_>>();
+
+    // The second output is only served later
+    let node = MockNode::new()
+        .with_output(&outputs[0])
+        .unwrap()
+        .with_output_ids_page(&format!("{route}?pageSize=1"), &output_ids[..1], Some("c1.1"))
+        .unwrap()
+        .with_output_ids_page(&format!("{route}?cursor=c1.1&pageSize=1"), &output_ids[1..], None)
+        .unwrap();
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node.clone())
+        .with_retry_options(RetryOptions {
+            max_retries: 0,
+            ..Default::default()
+        })
+        .finish()
+        .unwrap();
+
+    let all_output_ids = client
+        .basic_output_ids(vec![QueryParameter::PageSize(1)])
+        .await
+        .unwrap();
+    assert_eq!(all_output_ids, output_ids);
+
+    let mut paginator = client.output_ids_paginator(route, vec![QueryParameter::PageSize(1)]);
+    assert!(paginator.cursor().is_none());
+    let page = paginator.next_outputs_page().await.unwrap().unwrap();
+    assert_eq!(page.items, outputs[..1]);
+    let cursor = paginator.cursor().cloned().unwrap();
+    assert_eq!(cursor, Cursor::new("c1.1"));
+    assert_eq!(page.cursor.as_ref(), Some(&cursor));
+
+    // A failed page doesn't move the cursor
+    assert!(paginator.next_outputs_page().await.is_err());
+    assert_eq!(paginator.cursor(), Some(&cursor));
+    assert!(!paginator.is_finished());
+
+    node.clone().with_output(&outputs[1]).unwrap();
+    let page = paginator.next_outputs_page().await.unwrap().unwrap();
+    assert_eq!(page.items, outputs[1..]);
+    assert!(page.cursor.is_none());
+    assert!(paginator.is_finished());
+    assert!(paginator.next_page().await.unwrap().is_none());
+
+    // A stored cursor resumes the query
+    let pages = client
+        .output_ids_paginator(route, vec![QueryParameter::PageSize(1)])
+        .with_cursor(cursor)
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].items, output_ids[1..]);
+
+    let pages = client
+        .output_ids_paginator(route, vec![QueryParameter::PageSize(1)])
+        .into_outputs_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    let streamed_outputs = pages.into_iter().flat_map(|page| page.items).collect::<Vec<_>>();
+    assert_eq!(streamed_outputs, outputs);
+}
+
+#[tokio::test]
+async fn mock_node_output_queries() {
+    let output_id = OutputId::from_str(OUTPUT_ID).unwrap();
+    let address = rand_address();
+    let bech32_hrp = setup_client(&MockNode::new()).get_bech32_hrp().await.unwrap();
+    let bech32_address = address.to_bech32(&bech32_hrp);
+
+    let node = MockNode::new()
+        .with_output_ids(
+            &format!(
+                "api/indexer/v1/outputs/basic?address={bech32_address}&createdAfter=1&createdBefore=2\
+                 &hasNativeTokens=false&tag=0x010203"
+            ),
+            &[output_id],
+        )
+        .unwrap();
+    let client = setup_client(&node);
+
+    let query = BasicOutputQuery::new()
+        .with_address(address)
+        .with_has_native_tokens(false)
+        .with_tag(vec![1, 2, 3])
+        .with_created_after(1)
+        .with_created_before(2);
+    assert_eq!(query.route(), "api/indexer/v1/outputs/basic");
+    assert_eq!(client.output_ids_by_query(&query).await.unwrap(), vec![output_id]);
+
+    let query = NftOutputQuery::new().with_issuer(address).with_page_size(10);
+    assert_eq!(
+        query.query_parameters(&bech32_hrp).unwrap(),
+        vec![QueryParameter::Issuer(bech32_address), QueryParameter::PageSize(10)]
+    );
+
+    // Invalid queries are rejected before a request is sent
+    let requests = node.requests().len();
+    let invalid_queries = [
+        AliasOutputQuery::new()
+            .with_created_after(2)
+            .with_created_before(2)
+            .query_parameters(&bech32_hrp),
+        FoundryOutputQuery::new()
+            .with_min_native_token_count(2)
+            .with_max_native_token_count(1)
+            .query_parameters(&bech32_hrp),
+        BasicOutputQuery::new()
+            .with_has_expiration(false)
+            .with_expires_before(10)
+            .query_parameters(&bech32_hrp),
+        NftOutputQuery::new().with_page_size(0).query_parameters(&bech32_hrp),
+    ];
+    for invalid_query in invalid_queries {
+        assert!(matches!(invalid_query, Err(Error::InvalidOutputQuery(_))));
+    }
+    assert!(matches!(
+        client
+            .output_ids_by_query(&BasicOutputQuery::new().with_timelocked_after(3).with_timelocked_before(1))
+            .await,
+        Err(Error::InvalidOutputQuery(_))
+    ));
+    assert_eq!(node.requests().len(), requests);
+}
@@ -4,8 +4,11 @@
 use std::str::FromStr;
 
 use iota_client::{
-    api::input_selection::try_select_inputs,
-    block::output::{AliasId, Output, RentStructure},
+    api::input_selection::{burn::Burn, try_select_inputs, types::InputSelectionOptions},
+    block::{
+        address::AliasAddress,
+        output::{AliasId, FoundryId, Output, RentStructure},
+    },
     Error, Result,
 };
 use iota_types::block::output::{NativeToken, SimpleTokenScheme, TokenId};
@@ -35,9 +38,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     assert_eq!(selected_transaction_data.inputs, inputs);
 
@@ -50,9 +53,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::NotEnoughBalance {
             found: 1_000_000,
@@ -71,9 +74,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // basic output + alias remainder
     assert_eq!(selected_transaction_data.outputs.len(), 2);
@@ -87,9 +90,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // One output should be added for the remainder
     assert_eq!(selected_transaction_data.outputs.len(), 2);
@@ -111,9 +114,12 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        true,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions {
+            burn: Some(&Burn::new().add_alias(alias_id_1)),
+            ..Default::default()
+        },
     )?;
     // No remainder
     assert_eq!(selected_transaction_data.outputs.len(), 1);
@@ -129,9 +135,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::BlockError(iota_types::block::Error::InsufficientStorageDepositAmount {
             amount: 1,
@@ -149,9 +155,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::MissingInput(err_msg)) => {
             assert_eq!(
@@ -180,9 +186,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::MissingInput(err_msg)) => {
             assert_eq!(
@@ -207,9 +213,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // Alias next state + foundry
     assert_eq!(selected_transaction_data.outputs.len(), 2);
@@ -235,9 +241,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // Alias next state + foundry + basic output with native tokens
     assert_eq!(selected_transaction_data.outputs.len(), 3);
@@ -281,9 +287,9 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // Alias next state + foundry + basic output with native tokens
     assert_eq!(selected_transaction_data.outputs.len(), 3);
@@ -315,9 +321,16 @@ fn input_selection_alias() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        true,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions {
+            burn: Some(&Burn::new().add_foundry(FoundryId::build(
+                &AliasAddress::new(alias_id_1),
+                0,
+                SimpleTokenScheme::KIND,
+            ))),
+            ..Default::default()
+        },
     )?;
     // Alias next state
     assert_eq!(selected_transaction_data.outputs.len(), 1);
@@ -1,7 +1,30 @@
 // Copyright 2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use iota_client::{api::input_selection::try_select_inputs, block::output::RentStructure, Error, Result};
+use std::str::FromStr;
+
+use iota_client::{
+    api::input_selection::{
+        burn::Burn,
+        coin_selection::CoinSelection,
+        minimum_storage_deposit_basic_output,
+        remainder::{RemainderPolicy, RemainderSplit},
+        report::{dry_run_select_inputs, CandidateDecision, SelectionReason, SkipReason},
+        try_select_inputs,
+        types::InputSelectionOptions,
+    },
+    block::{
+        address::Address,
+        output::{
+            unlock_condition::{ExpirationUnlockCondition, TimelockUnlockCondition, UnlockCondition},
+            BasicOutputBuilder, NativeToken, Output, RentStructure, TokenId, OUTPUT_COUNT_MAX,
+        },
+        rand::address::rand_address,
+    },
+    secret::types::OutputMetadata,
+    Error, Result,
+};
+use primitive_types::U256;
 
 use crate::input_selection::{build_input_signing_data_most_basic_outputs, build_most_basic_output};
 
@@ -21,9 +44,9 @@ fn input_selection_basic_outputs() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     assert_eq!(selected_transaction_data.inputs, inputs);
 
@@ -36,9 +59,9 @@ fn input_selection_basic_outputs() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::NotEnoughBalance {
             found: 1_000_000,
@@ -56,9 +79,9 @@ fn input_selection_basic_outputs() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     assert_eq!(selected_transaction_data.inputs, inputs);
     // One output should be added for the remainder
@@ -74,9 +97,9 @@ fn input_selection_basic_outputs() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // One input has enough amount
     assert_eq!(selected_transaction_data.inputs.len(), 1);
@@ -92,9 +115,9 @@ fn input_selection_basic_outputs() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::BlockError(iota_types::block::Error::InsufficientStorageDepositAmount {
             amount: 1,
@@ -105,3 +128,298 @@ fn input_selection_basic_outputs() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn input_selection_coin_selection_strategies() -> Result<()> {
+    let rent_structure = RentStructure::new(500, 10, 1);
+    let bech32_address = "rms1qr2xsmt3v3eyp2ja80wd2sq8xx0fslefmxguf7tshzezzr5qsctzc2f5dg6";
+    let mut inputs = build_input_signing_data_most_basic_outputs(vec![
+        (bech32_address, 1_000_000),
+        (bech32_address, 5_000_000),
+        (bech32_address, 2_000_000),
+        (bech32_address, 3_000_000),
+    ]);
+    // The 2 Mi output was booked first, then the 3 Mi output
+    for (input, timestamp) in inputs.iter_mut().zip([4, 3, 1, 2]) {
+        input.output_metadata = OutputMetadata::new(
+            *input.output_metadata.block_id(),
+            *input.output_metadata.output_id(),
+            false,
+            None,
+            None,
+            None,
+            0,
+            timestamp,
+            0,
+        );
+    }
+
+    let strategies: [(CoinSelection, &[u64]); 4] = [
+        (CoinSelection::LargestFirst, &[5_000_000]),
+        (CoinSelection::SmallestFirst, &[1_000_000, 2_000_000, 3_000_000]),
+        (CoinSelection::OldestFirst, &[2_000_000, 3_000_000]),
+        // Exact match, without remainder
+        (CoinSelection::BranchAndBound, &[1_000_000, 3_000_000]),
+    ];
+    for (strategy, selected_amounts) in strategies {
+        let selected_transaction_data = try_select_inputs(
+            Vec::new(),
+            inputs.clone(),
+            vec![build_most_basic_output(bech32_address, 4_000_000)],
+            None,
+            &rent_structure,
+            0,
+            TOKEN_SUPPLY,
+            InputSelectionOptions {
+                coin_selection_strategy: Some(&strategy),
+                ..Default::default()
+            },
+        )?;
+        let mut amounts = selected_transaction_data
+            .inputs
+            .iter()
+            .map(|input| input.output.amount())
+            .collect::<Vec<_>>();
+        amounts.sort_unstable();
+        assert_eq!(amounts, selected_amounts, "{strategy:?}");
+        assert_eq!(
+            selected_transaction_data.remainder.is_none(),
+            strategy == CoinSelection::BranchAndBound
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn input_selection_burn_native_tokens() -> Result<()> {
+    let rent_structure = RentStructure::new(500, 10, 1);
+    let bech32_address = "rms1qr2xsmt3v3eyp2ja80wd2sq8xx0fslefmxguf7tshzezzr5qsctzc2f5dg6";
+    let token_id = TokenId::from_str("0x08e68f7616cd4948efebc6a77c4f93aed770ac53860100000000000000000000000000000000")?;
+    let mut inputs = build_input_signing_data_most_basic_outputs(vec![(bech32_address, 2_000_000)]);
+    if let Output::Basic(basic_output) = &inputs[0].output {
+        inputs[0].output = BasicOutputBuilder::from(basic_output)
+            .add_native_token(NativeToken::new(token_id, U256::from(100))?)
+            .finish_output(TOKEN_SUPPLY)?;
+    }
+    let remaining_native_tokens = |burn: Option<&Burn>, output_amount| -> Result<Option<U256>> {
+        let selected_transaction_data = try_select_inputs(
+            Vec::new(),
+            inputs.clone(),
+            vec![build_most_basic_output(bech32_address, output_amount)],
+            None,
+            &rent_structure,
+            0,
+            TOKEN_SUPPLY,
+            InputSelectionOptions {
+                burn,
+                ..Default::default()
+            },
+        )?;
+        Ok(selected_transaction_data
+            .remainder
+            .and_then(|remainder| remainder.output.native_tokens().map(|native_tokens| native_tokens[0].amount())))
+    };
+
+    // Nothing is burned without a burn
+    assert_eq!(remaining_native_tokens(None, 1_000_000)?, Some(U256::from(100)));
+    match remaining_native_tokens(None, 2_000_000) {
+        Err(Error::NoBalanceForNativeTokenRemainder) => {}
+        _ => panic!("Should return NoBalanceForNativeTokenRemainder"),
+    }
+    // Only the listed amount is burned
+    let burn = Burn::new().add_native_token(token_id, U256::from(40));
+    assert_eq!(remaining_native_tokens(Some(&burn), 1_000_000)?, Some(U256::from(60)));
+    // All native tokens are burned, so no remainder is required
+    let burn = Burn::new().add_native_token(token_id, U256::from(100));
+    assert_eq!(remaining_native_tokens(Some(&burn), 2_000_000)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn input_selection_dry_run() -> Result<()> {
+    let rent_structure = RentStructure::new(500, 10, 1);
+    let bech32_address = "rms1qr2xsmt3v3eyp2ja80wd2sq8xx0fslefmxguf7tshzezzr5qsctzc2f5dg6";
+    let current_time = 100;
+    let mut inputs = build_input_signing_data_most_basic_outputs(vec![
+        (bech32_address, 1_000_000),
+        (bech32_address, 2_000_000),
+        (bech32_address, 3_000_000),
+        (bech32_address, 4_000_000),
+    ]);
+    inputs[3].bech32_address = "invalid".to_string();
+    let unlock_conditions = [
+        UnlockCondition::Timelock(TimelockUnlockCondition::new(current_time + 1)?),
+        UnlockCondition::Expiration(ExpirationUnlockCondition::new(rand_address(), current_time)?),
+        UnlockCondition::Expiration(ExpirationUnlockCondition::new(rand_address(), current_time)?),
+    ];
+    for (input, unlock_condition) in inputs[1..].iter_mut().zip(unlock_conditions) {
+        if let Output::Basic(basic_output) = &input.output {
+            input.output = BasicOutputBuilder::from(basic_output)
+                .add_unlock_condition(unlock_condition)
+                .finish_output(TOKEN_SUPPLY)?;
+        }
+    }
+    let decisions = [
+        CandidateDecision::Selected(SelectionReason::Amount),
+        CandidateDecision::Skipped(SkipReason::Timelocked),
+        CandidateDecision::Skipped(SkipReason::Expired),
+        CandidateDecision::Skipped(SkipReason::InvalidAddress),
+    ];
+
+    // The smallest input covers the output and the remainder, the others are reported with why they can't be unlocked
+    let report = dry_run_select_inputs(
+        Vec::new(),
+        inputs.clone(),
+        vec![build_most_basic_output(bech32_address, 500_000)],
+        None,
+        &rent_structure,
+        current_time,
+        TOKEN_SUPPLY,
+        InputSelectionOptions {
+            coin_selection_strategy: Some(&CoinSelection::SmallestFirst),
+            ..Default::default()
+        },
+    )?;
+    assert!(report.error.is_none());
+    assert_eq!(
+        report
+            .candidates
+            .iter()
+            .map(|candidate| (candidate.amount, candidate.decision))
+            .collect::<Vec<_>>(),
+        [1_000_000, 2_000_000, 3_000_000, 4_000_000]
+            .into_iter()
+            .zip(decisions)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(report.remainder_storage_deposit, Some(213000));
+    assert_eq!(report.balance_sheet.base_coin.inputs, 1_000_000);
+    assert_eq!(report.balance_sheet.base_coin.remainder, 500_000);
+
+    // Not enough balance without the timelocked and the expired inputs
+    let report = dry_run_select_inputs(
+        Vec::new(),
+        inputs,
+        vec![build_most_basic_output(bech32_address, 2_000_000)],
+        None,
+        &rent_structure,
+        current_time,
+        TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
+    )?;
+    assert_eq!(
+        report.error,
+        Some(
+            Error::NotEnoughBalance {
+                found: 1_000_000,
+                required: 2_000_000,
+            }
+            .to_string()
+        )
+    );
+    assert_eq!(report.balance_sheet.base_coin.missing, 1_000_000);
+    assert_eq!(report.remainder_storage_deposit, None);
+
+    Ok(())
+}
+
+#[test]
+fn input_selection_remainder_policy() -> Result<()> {
+    let rent_structure = RentStructure::new(500, 10, 1);
+    let bech32_address = "rms1qr2xsmt3v3eyp2ja80wd2sq8xx0fslefmxguf7tshzezzr5qsctzc2f5dg6";
+    let (change_address_1, change_address_2) = (rand_address(), rand_address());
+    let select = |inputs: Vec<_>, output_amount, remainder_policy: &RemainderPolicy| {
+        try_select_inputs(
+            Vec::new(),
+            inputs,
+            vec![build_most_basic_output(bech32_address, output_amount)],
+            None,
+            &rent_structure,
+            0,
+            TOKEN_SUPPLY,
+            InputSelectionOptions {
+                remainder_policy: Some(remainder_policy),
+                ..Default::default()
+            },
+        )
+    };
+
+    // One remainder output per native token, round-robin over the change addresses
+    let mut inputs = build_input_signing_data_most_basic_outputs(vec![(bech32_address, 10_000_000)]);
+    if let Output::Basic(basic_output) = &inputs[0].output {
+        let mut builder = BasicOutputBuilder::from(basic_output);
+        for serial_number in 1..=3 {
+            let token_id = TokenId::from_str(&format!(
+                "0x08e68f7616cd4948efebc6a77c4f93aed770ac538601000000000000000000000000000000{serial_number:02x}"
+            ))?;
+            builder = builder.add_native_token(NativeToken::new(token_id, U256::from(100))?);
+        }
+        inputs[0].output = builder.finish_output(TOKEN_SUPPLY)?;
+    }
+    let remainder_policy = RemainderPolicy::new(RemainderSplit::PerNativeToken)
+        .add_address(change_address_1)
+        .add_address(change_address_2);
+    let selected_transaction_data = select(inputs, 1_000_000, &remainder_policy)?;
+    let remainders = selected_transaction_data
+        .remainder
+        .iter()
+        .chain(selected_transaction_data.additional_remainders.iter())
+        .collect::<Vec<_>>();
+    assert_eq!(selected_transaction_data.outputs.len(), 4);
+    assert_eq!(
+        remainders.iter().map(|remainder| remainder.address).collect::<Vec<_>>(),
+        [change_address_1, change_address_2, change_address_1]
+    );
+    assert!(
+        remainders
+            .iter()
+            .all(|remainder| remainder.output.native_tokens().unwrap().len() == 1)
+    );
+    assert_eq!(
+        remainders
+            .iter()
+            .map(|remainder| remainder.output.amount())
+            .sum::<u64>(),
+        9_000_000
+    );
+    let storage_deposit = minimum_storage_deposit_basic_output(
+        &rent_structure,
+        &change_address_2,
+        &remainders[1].output.native_tokens().cloned(),
+        TOKEN_SUPPLY,
+    )?;
+    assert_eq!(remainders[1].output.amount(), storage_deposit);
+
+    // Fixed denominations, amounts below the storage deposit are ignored and the rest gets its own output
+    let inputs = build_input_signing_data_most_basic_outputs(vec![(bech32_address, 10_000_000)]);
+    let remainder_policy = RemainderPolicy::new(RemainderSplit::Denominations(vec![1_000_000, 100_000, 2_000_000]));
+    let selected_transaction_data = select(inputs.clone(), 1_500_000, &remainder_policy)?;
+    assert_eq!(
+        selected_transaction_data.outputs[1..]
+            .iter()
+            .map(|output| output.amount())
+            .collect::<Vec<_>>(),
+        [2_000_000, 2_000_000, 2_000_000, 2_000_000, 500_000]
+    );
+    let remainder_address = Address::try_from_bech32(bech32_address)?.1;
+    assert!(
+        selected_transaction_data
+            .additional_remainders
+            .iter()
+            .all(|remainder| remainder.address == remainder_address)
+    );
+
+    // The number of remainder outputs is limited, the rest is added to the first one
+    let remainder_policy = RemainderPolicy::new(RemainderSplit::Denominations(vec![50_000_000]));
+    let inputs = build_input_signing_data_most_basic_outputs(vec![(bech32_address, 10_000_000_000)]);
+    let selected_transaction_data = select(inputs, 1_000_000, &remainder_policy)?;
+    assert_eq!(selected_transaction_data.outputs.len(), OUTPUT_COUNT_MAX as usize);
+    assert_eq!(
+        selected_transaction_data.remainder.unwrap().output.amount(),
+        10_000_000_000 - 1_000_000 - 126 * 50_000_000
+    );
+
+    Ok(())
+}
@@ -4,7 +4,7 @@
 use std::str::FromStr;
 
 use iota_client::{
-    api::input_selection::try_select_inputs,
+    api::input_selection::{burn::Burn, try_select_inputs, types::InputSelectionOptions},
     block::output::{NftId, Output, RentStructure},
     Error, Result,
 };
@@ -33,9 +33,9 @@ fn input_selection_nfts() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     assert_eq!(selected_transaction_data.inputs, inputs);
 
@@ -48,9 +48,9 @@ fn input_selection_nfts() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::NotEnoughBalance {
             found: 1_000_000,
@@ -69,9 +69,9 @@ fn input_selection_nfts() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // basic output + nft remainder
     assert_eq!(selected_transaction_data.outputs.len(), 2);
@@ -85,9 +85,9 @@ fn input_selection_nfts() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     )?;
     // One output should be added for the remainder
     assert_eq!(selected_transaction_data.outputs.len(), 2);
@@ -109,15 +109,42 @@ fn input_selection_nfts() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        true,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions {
+            burn: Some(&Burn::new().add_nft(nft_id_1)),
+            ..Default::default()
+        },
     )?;
     // No remainder
     assert_eq!(selected_transaction_data.outputs.len(), 1);
     // Output is a basic output
     assert!(matches!(selected_transaction_data.outputs[0], Output::Basic(_)));
 
+    // nft that isn't listed in the burn is kept
+    let inputs = build_input_signing_data_nft_outputs(vec![(nft_id_1, bech32_address, 2_000_000)]);
+    let outputs = vec![build_most_basic_output(bech32_address, 1_000_000)];
+    let selected_transaction_data = try_select_inputs(
+        Vec::new(),
+        inputs,
+        outputs,
+        None,
+        &rent_structure,
+        0,
+        TOKEN_SUPPLY,
+        InputSelectionOptions {
+            burn: Some(&Burn::new().add_nft(nft_id_0)),
+            ..Default::default()
+        },
+    )?;
+    assert!(selected_transaction_data.outputs.iter().any(|output| {
+        if let Output::Nft(nft_output) = output {
+            *nft_output.nft_id() == nft_id_1
+        } else {
+            false
+        }
+    }));
+
     // not enough storage deposit for remainder
     let inputs = build_input_signing_data_nft_outputs(vec![(nft_id_1, bech32_address, 1_000_001)]);
     let outputs = vec![build_nft_output(nft_id_1, bech32_address, 1_000_000)];
@@ -127,9 +154,9 @@ fn input_selection_nfts() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::BlockError(iota_types::block::Error::InsufficientStorageDepositAmount {
             amount: 1,
@@ -147,9 +174,9 @@ fn input_selection_nfts() -> Result<()> {
         outputs,
         None,
         &rent_structure,
-        false,
         0,
         TOKEN_SUPPLY,
+        InputSelectionOptions::default(),
     ) {
         Err(Error::MissingInput(err_msg)) => {
             assert_eq!(
@@ -9,6 +9,7 @@ use dotenv::dotenv;
 use iota_client::{
     api::GetAddressesBuilderOptions as GenerateAddressesOptions,
     message_interface::{self, Message, Response},
+    node_api::core::routes::PruneDatabaseOptions,
     secret::SecretManagerDto,
 };
 
@@ -215,3 +216,57 @@ async fn stronghold() {
     // Remove garbage after test, but don't care about the result
     std::fs::remove_file("teststronghold.stronghold").unwrap_or(());
 }
+
+#[tokio::test]
+async fn node_pool() {
+    let client_config = r#"{
+            "nodes":[],
+            "ignoreNodeHealth":true
+    }"#
+    .to_string();
+    let message_handler = message_interface::create_message_handler(Some(client_config)).unwrap();
+
+    let message: Message =
+        serde_json::from_str(r#"{"name":"addNode","data":{"node":"http://localhost:14265"}}"#).unwrap();
+    let response = message_interface::send_message(&message_handler, message).await;
+    assert!(matches!(response, Response::Ok));
+
+    let response = message_interface::send_message(&message_handler, Message::ListNodes).await;
+    match response {
+        Response::Nodes(nodes) => {
+            assert_eq!(nodes.len(), 1);
+            assert_eq!(nodes[0].url.as_str(), "http://localhost:14265/");
+        }
+        _ => panic!("Unexpected response type"),
+    };
+
+    let message = Message::RemoveNode {
+        url: "http://localhost:14265".to_string(),
+    };
+    let response = message_interface::send_message(&message_handler, message).await;
+    assert!(matches!(response, Response::NodeRemoved(true)));
+}
+
+#[tokio::test]
+async fn node_management() {
+    let message: Message = serde_json::from_str(
+        r#"{"name":"addPeer","data":{"multiAddress":"/ip4/127.0.0.1/tcp/15600/p2p/12D3KooW","alias":"peer"}}"#,
+    )
+    .unwrap();
+    assert!(matches!(message, Message::AddPeer { alias: Some(_), .. }));
+
+    let message: Message =
+        serde_json::from_str(r#"{"name":"pruneDatabase","data":{"options":{"depth":10}}}"#).unwrap();
+    assert!(matches!(
+        message,
+        Message::PruneDatabase {
+            options: PruneDatabaseOptions::Depth(10)
+        }
+    ));
+
+    // Without nodes the requests can't be sent
+    let message_handler = message_interface::create_message_handler(Some(r#"{"nodes":[]}"#.to_string())).unwrap();
+    let message: Message = serde_json::from_str(r#"{"name":"removePeer","data":{"peerId":"12D3KooW"}}"#).unwrap();
+    let response = message_interface::send_message(&message_handler, message).await;
+    assert!(matches!(response, Response::Error(_)));
+}
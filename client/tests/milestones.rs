@@ -0,0 +1,291 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+// Milestone tests against the in-memory mock node: ledger diffs, milestone verification and proofs of inclusion.
+
+mod common;
+
+use std::collections::HashMap;
+
+use futures::TryStreamExt;
+use iota_client::{
+    api_types::response::UtxoChangesResponse,
+    block::{
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, Output, OutputId},
+        payload::milestone::{
+            dto::MilestonePayloadDto, MerkleRoot, MilestoneEssence, MilestoneOptions, MilestonePayload,
+        },
+        rand::{
+            address::rand_address,
+            block::{rand_block, rand_block_id},
+            bytes::rand_bytes_array,
+            milestone::{rand_merkle_root, rand_milestone_id},
+            output::rand_output_id,
+            parents::rand_parents,
+        },
+        signature::{Ed25519Signature, Signature},
+        BlockDto,
+    },
+    crypto::{
+        hashes::{blake2b::Blake2b256, Digest},
+        signatures::ed25519::SecretKey,
+    },
+    node_api::poi::proof::{MerkleProof, ProofOfInclusion},
+    node_manager::{http_client::HttpMethod, mock::MockNode},
+    Client, Error, MilestoneKeyRange, MilestoneVerificationOptions,
+};
+
+use self::common::{
+    milestone, output_with_metadata, setup_client, sign_milestone, signed_milestone, MockNodes, NODE_URL,
+};
+
+#[tokio::test]
+async fn mock_node_ledger_diffs() {
+    let token_supply = setup_client(&MockNode::new()).get_token_supply().await.unwrap();
+    let new_output = || {
+        Output::Basic(
+            BasicOutputBuilder::new_with_amount(1_000_000)
+                .unwrap()
+                .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+                .finish(token_supply)
+                .unwrap(),
+        )
+    };
+    let (output_id_a, output_id_b) = (rand_output_id(), rand_output_id());
+    let mut output_a = output_with_metadata(output_id_a, &new_output());
+    output_a.metadata.is_spent = true;
+    output_a.metadata.milestone_index_spent = Some(12);
+    let output_b = output_with_metadata(output_id_b, &new_output());
+
+    let mut milestones = vec![milestone(10, 1000, rand_milestone_id())];
+    for index in 11..=12 {
+        let previous_milestone_id = milestones.last().unwrap().id();
+        milestones.push(milestone(index, 1000 + index, previous_milestone_id));
+    }
+    let utxo_changes = |index: u32, created: &[OutputId], consumed: &[OutputId]| UtxoChangesResponse {
+        index,
+        created_outputs: created.iter().map(ToString::to_string).collect(),
+        consumed_outputs: consumed.iter().map(ToString::to_string).collect(),
+    };
+    let utxo_changes_route = |index: u32| format!("api/core/v2/milestones/by-index/{index}/utxo-changes");
+
+    // Milestone 11 is pruned on the node, so its changes and outputs are only served by the permanode
+    let mut node = MockNode::new()
+        .with_output(&output_a)
+        .unwrap()
+        .with_json(HttpMethod::Get, &utxo_changes_route(10), &utxo_changes(10, &[output_id_a], &[]))
+        .unwrap()
+        .with_json(HttpMethod::Get, &utxo_changes_route(12), &utxo_changes(12, &[], &[output_id_a]))
+        .unwrap();
+    let permanode = MockNode::new()
+        .with_output(&output_b)
+        .unwrap()
+        .with_json(HttpMethod::Get, &utxo_changes_route(11), &utxo_changes(11, &[output_id_b], &[]))
+        .unwrap();
+    for milestone in &milestones {
+        node = node.with_milestone(milestone).unwrap();
+    }
+    let client = Client::builder()
+        .with_node("http://node:14265")
+        .unwrap()
+        .with_permanode("http://permanode:14265", None)
+        .unwrap()
+        .with_transport(MockNodes(HashMap::from([
+            ("node".to_string(), node.clone()),
+            ("permanode".to_string(), permanode.clone()),
+        ])))
+        .finish()
+        .unwrap();
+
+    let diffs = client.ledger_diffs(10..=12, 2).try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(
+        diffs.iter().map(|diff| diff.milestone_index).collect::<Vec<_>>(),
+        vec![10, 11, 12]
+    );
+    for (diff, milestone) in diffs.iter().zip(&milestones) {
+        assert_eq!(diff.milestone_id, milestone.id());
+        assert_eq!(diff.milestone_timestamp, milestone.essence().timestamp());
+    }
+    assert_eq!(diffs[0].created_outputs, vec![output_a.clone()]);
+    assert!(diffs[0].consumed_outputs.is_empty());
+    assert_eq!(diffs[1].created_outputs, vec![output_b]);
+    assert!(diffs[2].created_outputs.is_empty());
+    assert_eq!(diffs[2].consumed_outputs, vec![output_a]);
+
+    // The permanode is only asked for the UTXO changes that the node doesn't have
+    let permanode_utxo_changes = permanode
+        .requests()
+        .into_iter()
+        .filter(|request| request.url.path().ends_with("/utxo-changes"))
+        .map(|request| request.url.path().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(permanode_utxo_changes, vec![format!("/{}", utxo_changes_route(11))]);
+
+    // Without permanode a pruned milestone fails
+    assert!(matches!(
+        setup_client(&node).get_ledger_diff(11).await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn mock_node_milestone_verification() {
+    let (key, other_key) = (SecretKey::generate().unwrap(), SecretKey::generate().unwrap());
+    let mut milestones = vec![signed_milestone(10, rand_milestone_id(), &key)];
+    for index in 11..=14 {
+        let previous_milestone_id = milestones.last().unwrap().id();
+        milestones.push(signed_milestone(index, previous_milestone_id, &key));
+    }
+    let options = MilestoneVerificationOptions {
+        public_key_count: 1,
+        key_ranges: vec![MilestoneKeyRange {
+            public_key: prefix_hex::encode(key.public_key().to_bytes()),
+            start_index: 0,
+            end_index: 0,
+        }],
+        trusted_milestone_id: Some(milestones[2].id()),
+    };
+    let verifying_client = |node: &MockNode| {
+        Client::builder()
+            .with_node(NODE_URL)
+            .unwrap()
+            .with_transport(node.clone())
+            .with_milestone_verification(options.clone())
+            .finish()
+            .unwrap()
+    };
+    let serve = |milestones: &[MilestonePayload]| {
+        milestones
+            .iter()
+            .fold(MockNode::new(), |node, milestone| node.with_milestone(milestone).unwrap())
+    };
+
+    // The chain is walked forwards and backwards from the trusted milestone
+    let client = verifying_client(&serve(&milestones));
+    assert_eq!(client.get_verified_milestone_by_index(14).await.unwrap(), milestones[4]);
+    assert_eq!(client.get_verified_milestone_by_index(10).await.unwrap(), milestones[0]);
+    assert_eq!(client.get_verified_milestone_by_index(13).await.unwrap(), milestones[3]);
+
+    // A validly signed milestone that isn't linked to the trusted milestone is rejected
+    let mut unchained = milestones.clone();
+    unchained[3] = signed_milestone(13, rand_milestone_id(), &key);
+    assert!(matches!(
+        verifying_client(&serve(&unchained)).get_verified_milestone_by_index(14).await,
+        Err(Error::MilestoneVerificationError { index: 13, .. })
+    ));
+
+    // A milestone signed by a key of the coordinator that isn't applicable is rejected
+    let mut wrong_key = milestones.clone();
+    wrong_key[3] = signed_milestone(13, milestones[2].id(), &other_key);
+    assert!(matches!(
+        verifying_client(&serve(&wrong_key)).get_verified_milestone_by_index(13).await,
+        Err(Error::MilestoneVerificationError { index: 13, .. })
+    ));
+
+    // A forged signature is rejected
+    let mut forged = milestones.clone();
+    let signature = Ed25519Signature::new(key.public_key().to_bytes(), rand_bytes_array());
+    forged[4] = MilestonePayload::new(milestones[4].essence().clone(), vec![Signature::from(signature)]).unwrap();
+    assert!(matches!(
+        verifying_client(&serve(&forged)).get_verified_milestone_by_index(14).await,
+        Err(Error::MilestoneVerificationError { index: 14, .. })
+    ));
+
+    // Without the options the milestones can't be verified
+    assert!(matches!(
+        setup_client(&serve(&milestones)).get_verified_milestone_by_index(12).await,
+        Err(Error::MilestoneVerificationError { .. })
+    ));
+}
+
+#[tokio::test]
+async fn mock_node_proof_of_inclusion() {
+    let key = SecretKey::generate().unwrap();
+    let block = rand_block(0);
+    let (block_id, sibling_id, other_id) = (block.id(), rand_block_id(), rand_block_id());
+
+    // Merkle tree of the block IDs [sibling, block, other], hashed like the nodes do
+    let hash = |prefix: u8, data: &[&[u8]]| {
+        let mut hasher = Blake2b256::new();
+        hasher.update([prefix]);
+        data.iter().for_each(|data| hasher.update(data));
+        <[u8; 32]>::from(hasher.finalize())
+    };
+    let (sibling_hash, other_hash) = (hash(0, &[sibling_id.as_ref()]), hash(0, &[other_id.as_ref()]));
+    let left_hash = hash(1, &[&sibling_hash, &hash(0, &[block_id.as_ref()])]);
+    let inclusion_merkle_root = hash(1, &[&left_hash, &other_hash]);
+
+    let essence = MilestoneEssence::new(
+        20.into(),
+        2000,
+        2,
+        rand_milestone_id(),
+        rand_parents(),
+        MerkleRoot::new(inclusion_merkle_root),
+        rand_merkle_root(),
+        Vec::new(),
+        MilestoneOptions::new(vec![]).unwrap(),
+    )
+    .unwrap();
+    let milestone = sign_milestone(essence, &key);
+
+    // The proof as it's returned by the node
+    let node = MockNode::new()
+        .with_json(
+            HttpMethod::Get,
+            &format!("api/poi/v1/create/{block_id}"),
+            &serde_json::json!({
+                "milestone": MilestonePayloadDto::from(&milestone),
+                "block": BlockDto::from(&block),
+                "proof": {
+                    "l": { "l": { "h": prefix_hex::encode(sibling_hash) }, "r": { "value": block_id.to_string() } },
+                    "r": { "h": prefix_hex::encode(other_hash) },
+                },
+            }),
+        )
+        .unwrap();
+    let proof = setup_client(&node).create_proof_of_inclusion(&block_id).await.unwrap();
+
+    let options = |key: &SecretKey| MilestoneVerificationOptions {
+        public_key_count: 1,
+        key_ranges: vec![MilestoneKeyRange {
+            public_key: prefix_hex::encode(key.public_key().to_bytes()),
+            start_index: 0,
+            end_index: 0,
+        }],
+        trusted_milestone_id: None,
+    };
+    assert_eq!(proof.proof.hash().unwrap(), inclusion_merkle_root);
+    assert_eq!(proof.verify(&options(&key)).unwrap(), block_id);
+
+    // The bundle can be stored and verified again later
+    let stored: ProofOfInclusion = serde_json::from_str(&serde_json::to_string(&proof).unwrap()).unwrap();
+    assert_eq!(stored, proof);
+    assert_eq!(stored.verify(&options(&key)).unwrap(), block_id);
+
+    // A milestone that isn't signed by the coordinator
+    assert!(matches!(
+        proof.verify(&options(&SecretKey::generate().unwrap())),
+        Err(Error::MilestoneVerificationError { index: 20, .. })
+    ));
+
+    // A path that doesn't lead to the inclusion merkle root
+    let mut tampered = proof.clone();
+    if let MerkleProof::Node { right, .. } = &mut tampered.proof {
+        **right = MerkleProof::Hash {
+            hash: prefix_hex::encode(rand_bytes_array::<32>()),
+        };
+    }
+    assert!(matches!(
+        tampered.verify(&options(&key)),
+        Err(Error::InvalidProofOfInclusion(_))
+    ));
+
+    // A block that isn't part of the proof
+    let mut other_block = proof;
+    other_block.block = BlockDto::from(&rand_block(0));
+    assert!(matches!(
+        other_block.verify(&options(&key)),
+        Err(Error::InvalidProofOfInclusion(_))
+    ));
+}
@@ -0,0 +1,739 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+// Node manager tests against the in-memory mock node: health, ranking, retries, quorum, auth and the node pool.
+
+mod common;
+
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use iota_client::{
+    api_types::response::{OutputWithMetadataResponse, TreasuryResponse},
+    block::{
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, Output, OutputId},
+        payload::transaction::TransactionId,
+        protocol::ProtocolParameters,
+        rand::address::rand_address,
+        BlockId,
+    },
+    node_api::indexer::query_parameters::QueryParameter,
+    node_manager::{
+        circuit_breaker::CircuitBreakerOptions,
+        http_client::HttpMethod,
+        mock::MockNode,
+        network_events::NetworkEvent,
+        node::{ClientCertificate, Node, NodeAuth, NodeDto},
+        observer::RequestAttempt,
+        quorum::QuorumReport,
+        request_options::RequestOptions,
+        retry::RetryOptions,
+        timeouts::RouteTimeouts,
+    },
+    Client, Error, Url,
+};
+
+use self::common::{
+    output_with_metadata, setup_client, treasury_requests, MockNodes, RecordingObserver, NODE_URL, OUTPUT_ID,
+};
+
+#[tokio::test]
+async fn mock_node_is_healthy() {
+    let node = MockNode::new();
+    let client = setup_client(&node);
+
+    assert!(client.unhealthy_nodes().is_empty());
+    assert!(client.get_health(NODE_URL).await.unwrap());
+
+    let info = client.get_info().await.unwrap();
+    assert_eq!(info.node_info.protocol.network_name, "testnet");
+    assert_eq!(info.url, "http://localhost");
+    assert_eq!(client.get_bech32_hrp().await.unwrap(), "rms");
+    assert_eq!(client.get_tips().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn mock_node_errors() {
+    let node = MockNode::new().with_status(HttpMethod::Get, "api/core/v2/treasury", 500);
+    let client = setup_client(&node);
+
+    let transaction_id = TransactionId::from_str(&OUTPUT_ID[..66]).unwrap();
+    assert!(matches!(
+        client.get_included_block(&transaction_id).await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        client.get_treasury().await,
+        Err(Error::ResponseError { code: 500, .. })
+    ));
+}
+
+#[tokio::test]
+async fn mock_node_unhealthy() {
+    let mut info = setup_client(&MockNode::new()).get_info().await.unwrap().node_info;
+    info.status.is_healthy = false;
+    let node = MockNode::new().with_info(&info).unwrap();
+    let client = setup_client(&node);
+
+    assert_eq!(client.unhealthy_nodes().len(), 1);
+}
+
+#[tokio::test]
+async fn mock_node_ranking() {
+    let failing_node_url = "http://127.0.0.1:14265";
+    let treasury = TreasuryResponse {
+        milestone_id: "0x2bb2ab8f8bc4ab7dd45b2e5c1d5e5a3b6c2fa1b0a5b9e7b6e1d0f6c3a8b4e2d1".to_string(),
+        amount: "1000".to_string(),
+    };
+    let nodes = MockNodes(HashMap::from([
+        (
+            "localhost".to_string(),
+            MockNode::new()
+                .with_json(HttpMethod::Get, "api/core/v2/treasury", &treasury)
+                .unwrap(),
+        ),
+        (
+            "127.0.0.1".to_string(),
+            MockNode::new().with_status(HttpMethod::Get, "api/core/v2/treasury", 500),
+        ),
+    ]));
+    // The primary node is always tried first, so the requests fall back to the other node
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_primary_node(failing_node_url, None)
+        .unwrap()
+        .with_transport(nodes)
+        .with_circuit_breaker_options(CircuitBreakerOptions {
+            failure_threshold: 0,
+            ..Default::default()
+        })
+        .finish()
+        .unwrap();
+
+    for _ in 0..3 {
+        assert_eq!(client.get_treasury().await.unwrap(), treasury);
+    }
+
+    let node_stats = client.node_stats().unwrap();
+    assert_eq!(node_stats.len(), 2);
+    let stats = |url: &str| {
+        node_stats
+            .iter()
+            .find(|(node, _)| node.url.as_str().starts_with(url))
+            .unwrap()
+            .1
+    };
+    assert_eq!(stats(failing_node_url).failed_requests, 3);
+    assert!(stats(failing_node_url).error_rate > 0.0);
+    assert!(stats(failing_node_url).score() < stats(NODE_URL).score());
+    assert!(stats(NODE_URL).latency.is_some());
+}
+
+#[tokio::test]
+async fn mock_node_circuit_breaker() {
+    let failing_node_url = "http://127.0.0.1:14265";
+    let treasury = TreasuryResponse {
+        milestone_id: "0x2bb2ab8f8bc4ab7dd45b2e5c1d5e5a3b6c2fa1b0a5b9e7b6e1d0f6c3a8b4e2d1".to_string(),
+        amount: "1000".to_string(),
+    };
+    let failing_node = MockNode::new().with_status(HttpMethod::Get, "api/core/v2/treasury", 503);
+    let nodes = MockNodes(HashMap::from([
+        (
+            "localhost".to_string(),
+            MockNode::new()
+                .with_json(HttpMethod::Get, "api/core/v2/treasury", &treasury)
+                .unwrap(),
+        ),
+        ("127.0.0.1".to_string(), failing_node.clone()),
+    ]));
+    let cool_down = Duration::from_millis(200);
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_primary_node(failing_node_url, None)
+        .unwrap()
+        .with_transport(nodes)
+        .with_circuit_breaker_options(CircuitBreakerOptions {
+            failure_threshold: 2,
+            cool_down,
+        })
+        .finish()
+        .unwrap();
+
+    // The circuit of the primary node opens after two failures, so it isn't tried anymore
+    for _ in 0..5 {
+        assert_eq!(client.get_treasury().await.unwrap(), treasury);
+    }
+    assert_eq!(treasury_requests(&failing_node), 2);
+
+    // After the cool-down a single request probes the node, which opens the circuit again
+    tokio::time::sleep(cool_down).await;
+    for _ in 0..3 {
+        assert_eq!(client.get_treasury().await.unwrap(), treasury);
+    }
+    assert_eq!(treasury_requests(&failing_node), 3);
+}
+
+#[tokio::test]
+async fn mock_node_retries() {
+    let node = MockNode::new().with_status(HttpMethod::Get, "api/core/v2/treasury", 503);
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node.clone())
+        .with_circuit_breaker_options(CircuitBreakerOptions {
+            failure_threshold: 0,
+            ..Default::default()
+        })
+        .with_retry_options(RetryOptions {
+            max_retries: 2,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+        })
+        .finish()
+        .unwrap();
+
+    assert!(matches!(
+        client.get_treasury().await,
+        Err(Error::ResponseError { code: 503, .. })
+    ));
+    assert_eq!(treasury_requests(&node), 3);
+
+    // Not found isn't retried
+    let transaction_id = TransactionId::from_str(&OUTPUT_ID[..66]).unwrap();
+    assert!(matches!(
+        client.get_included_block(&transaction_id).await,
+        Err(Error::NotFound(_))
+    ));
+    assert_eq!(
+        node.requests()
+            .iter()
+            .filter(|request| request.url.path().ends_with("/included-block"))
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn mock_node_quorum() {
+    let output_id = OutputId::from_str(OUTPUT_ID).unwrap();
+    let token_supply = setup_client(&MockNode::new()).get_token_supply().await.unwrap();
+    let output = |amount| {
+        Output::Basic(
+            BasicOutputBuilder::new_with_amount(amount)
+                .unwrap()
+                .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+                .finish(token_supply)
+                .unwrap(),
+        )
+    };
+    let honest_output = output_with_metadata(output_id, &output(1_000_000));
+    // A node that is behind returns a different ledger index, which doesn't count as disagreement
+    let mut stale_output = honest_output.clone();
+    stale_output.metadata.ledger_index = 990;
+    let malicious_output = output_with_metadata(output_id, &output(2_000_000));
+
+    let node = |output: &OutputWithMetadataResponse| {
+        MockNode::new()
+            .with_output(output)
+            .unwrap()
+            .with_output_ids("api/indexer/v1/outputs/basic", &[output_id])
+            .unwrap()
+    };
+    let client = |min_quorum_size, quorum_threshold| {
+        Client::builder()
+            .with_nodes(&[NODE_URL, "http://127.0.0.1:14265", "http://127.0.0.2:14265"])
+            .unwrap()
+            .with_transport(MockNodes(HashMap::from([
+                ("localhost".to_string(), node(&honest_output)),
+                ("127.0.0.1".to_string(), node(&stale_output)),
+                ("127.0.0.2".to_string(), node(&malicious_output)),
+            ])))
+            .with_quorum(true)
+            .with_min_quorum_size(min_quorum_size)
+            .with_quorum_threshold(quorum_threshold)
+            .finish()
+            .unwrap()
+    };
+
+    let client_ = client(3, 66);
+    assert_eq!(client_.get_output(&output_id).await.unwrap().output, honest_output.output);
+    assert_eq!(
+        client_.get_output_metadata(&output_id).await.unwrap().block_id,
+        honest_output.metadata.block_id
+    );
+    assert_eq!(
+        client_
+            .basic_output_ids(vec![QueryParameter::HasNativeTokens(false)])
+            .await
+            .unwrap(),
+        vec![output_id]
+    );
+
+    let report = match client(3, 100).get_output(&output_id).await {
+        Err(Error::QuorumThresholdError {
+            quorum_size: 2,
+            minimum_threshold: 3,
+            report,
+        }) => report,
+        res => panic!("expected quorum threshold error, got {res:?}"),
+    };
+    let QuorumReport { responses, agreements } = report;
+    assert_eq!(responses.len(), 3);
+    assert!(responses.iter().all(|response| response.error.is_none()));
+    let stale_response = responses
+        .iter()
+        .find(|response| response.node == "http://127.0.0.1:14265")
+        .unwrap();
+    assert_eq!(stale_response.ledger_index, Some(990));
+
+    assert_eq!(agreements.len(), 2);
+    let mut majority = agreements[0].nodes.clone();
+    majority.sort();
+    assert_eq!(majority, vec!["http://127.0.0.1:14265", "http://localhost:14265"]);
+    assert_eq!(agreements[1].nodes, vec!["http://127.0.0.2:14265"]);
+    assert_eq!(stale_response.response_hash.as_ref(), Some(&agreements[0].response_hash));
+}
+
+#[tokio::test]
+async fn mock_node_request_observer() {
+    let failing_node_url = "http://127.0.0.1:14265";
+    let nodes = MockNodes(HashMap::from([
+        ("localhost".to_string(), MockNode::new()),
+        (
+            "127.0.0.1".to_string(),
+            MockNode::new().with_status(HttpMethod::Get, "api/core/v2/treasury", 500),
+        ),
+    ]));
+    let observer = RecordingObserver::default();
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_primary_node(failing_node_url, None)
+        .unwrap()
+        .with_transport(nodes)
+        .with_request_observer(observer.clone())
+        .with_retry_options(RetryOptions {
+            max_retries: 0,
+            ..Default::default()
+        })
+        .finish()
+        .unwrap();
+
+    // The node info requests of the sync are observed too
+    let events = observer.0.lock().unwrap().clone();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.route == "api/core/v2/info"
+        && event.status == Some(200)
+        && event.attempt == RequestAttempt::First
+        && event.response_bytes > 0));
+    observer.0.lock().unwrap().clear();
+
+    client.get_treasury().await.unwrap_err();
+    let block_id = BlockId::from_str(&OUTPUT_ID[..66]).unwrap();
+    client.get_block_metadata(&block_id).await.unwrap_err();
+
+    let events = observer.0.lock().unwrap().clone();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0].route, "api/core/v2/treasury");
+    assert_eq!(events[0].method, HttpMethod::Get);
+    assert_eq!(events[0].node_url, failing_node_url);
+    assert_eq!(events[0].status, Some(500));
+    assert_eq!(events[0].attempt, RequestAttempt::First);
+    assert_eq!(events[0].request_bytes, 0);
+    assert_eq!(events[1].node_url, NODE_URL);
+    assert_eq!(events[1].status, Some(404));
+    assert_eq!(events[1].attempt, RequestAttempt::Fallback);
+    assert_eq!(events[2].route, "api/core/v2/blocks/{id}/metadata");
+    assert_eq!(events[2].path, format!("api/core/v2/blocks/{block_id}/metadata"));
+}
+
+#[tokio::test]
+async fn mock_node_auth() {
+    let node = MockNode::new();
+    let basic_auth = NodeAuth {
+        jwt: None,
+        basic_auth_name_pwd: Some(("user".to_string(), "password".to_string())),
+        headers: [("X-Api-Key".to_string(), "key".to_string())].into_iter().collect(),
+        client_certificate: None,
+    };
+    let client = Client::builder()
+        .with_node_auth(NODE_URL, Some(basic_auth.clone()))
+        .unwrap()
+        .with_transport(node.clone())
+        .finish()
+        .unwrap();
+
+    client.get_tips().await.unwrap();
+    // The node info request of the sync is authenticated too
+    let requests = node.requests();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        assert_eq!(request.header("authorization"), Some("Basic dXNlcjpwYXNzd29yZA=="));
+        assert_eq!(request.header("x-api-key"), Some("key"));
+        // The credentials are only sent as header
+        assert_eq!(request.url.username(), "");
+        assert_eq!(request.url.password(), None);
+        assert_eq!(request.client_certificate, None);
+    }
+
+    // A JWT takes precedence over basic auth
+    let node = MockNode::new();
+    let client = Client::builder()
+        .with_node_auth(
+            NODE_URL,
+            Some(NodeAuth {
+                jwt: Some("token".to_string()),
+                ..basic_auth.clone()
+            }),
+        )
+        .unwrap()
+        .with_transport(node.clone())
+        .finish()
+        .unwrap();
+    client.get_tips().await.unwrap();
+    assert!(
+        node.requests()
+            .iter()
+            .all(|request| request.header("authorization") == Some("Bearer token"))
+    );
+
+    // The client certificate is handed to the transport, the default one fails before connecting if it's invalid
+    let client_certificate = ClientCertificate {
+        certificate: "invalid".to_string(),
+        private_key: "invalid".to_string(),
+    };
+    let error = Client::get_node_info(
+        NODE_URL,
+        Some(NodeAuth {
+            client_certificate: Some(client_certificate.clone()),
+            ..basic_auth.clone()
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(error, Error::ClientCertificateError(_)));
+    let node = MockNode::new();
+    let client = Client::builder()
+        .with_node_auth(
+            NODE_URL,
+            Some(NodeAuth {
+                client_certificate: Some(client_certificate.clone()),
+                ..basic_auth
+            }),
+        )
+        .unwrap()
+        .with_transport(node.clone())
+        .finish()
+        .unwrap();
+    client.get_tips().await.unwrap();
+    assert!(
+        node.requests()
+            .iter()
+            .all(|request| request.client_certificate.as_ref() == Some(&client_certificate))
+    );
+}
+
+#[tokio::test]
+async fn mock_node_node_pool() {
+    let other_node_url = "http://127.0.0.1:14265";
+    let other_node = MockNode::new();
+    let nodes = MockNodes(HashMap::from([
+        ("localhost".to_string(), MockNode::new()),
+        ("127.0.0.1".to_string(), other_node.clone()),
+    ]));
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(nodes)
+        .with_node_sync_interval(Duration::from_millis(50))
+        .finish()
+        .unwrap();
+    let info_requests = || {
+        other_node
+            .requests()
+            .iter()
+            .filter(|request| request.url.path() == "/api/core/v2/info")
+            .count()
+    };
+
+    assert_eq!(client.list_nodes().unwrap().len(), 1);
+    assert_eq!(info_requests(), 0);
+
+    // The added node is synced right away and by the sync process
+    client
+        .add_node(NodeDto::Url(Url::parse(other_node_url).unwrap()))
+        .await
+        .unwrap();
+    let nodes = client.list_nodes().unwrap();
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0].url.as_str(), "http://127.0.0.1:14265/");
+    assert_eq!(client.node_stats().unwrap().len(), 2);
+    assert!(client.unhealthy_nodes().is_empty());
+    let synced_info_requests = info_requests();
+    assert!(synced_info_requests > 0);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(info_requests() > synced_info_requests);
+
+    // The primary node is tried first
+    assert_eq!(
+        client
+            .set_primary_node(Some(NodeDto::Url(Url::parse(other_node_url).unwrap())))
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(client.get_node().unwrap().url.as_str(), "http://127.0.0.1:14265/");
+    client.get_tips().await.unwrap();
+    assert!(
+        other_node
+            .requests()
+            .iter()
+            .any(|request| request.url.path() == "/api/core/v2/tips")
+    );
+
+    // Replacing the node with a disabled one keeps it in the pool, but it's not used anymore
+    client
+        .add_node(NodeDto::Node(Node {
+            url: Url::parse(other_node_url).unwrap(),
+            auth: None,
+            disabled: true,
+        }))
+        .await
+        .unwrap();
+    let nodes = client.list_nodes().unwrap();
+    assert_eq!(nodes.len(), 2);
+    assert!(nodes[0].disabled);
+
+    // Removing the node also unsets it as primary node
+    assert!(client.remove_node(other_node_url).unwrap());
+    assert!(!client.remove_node(other_node_url).unwrap());
+    assert_eq!(client.list_nodes().unwrap().len(), 1);
+    assert_eq!(client.get_node().unwrap().url.as_str(), "http://localhost:14265/");
+
+    let tips_requests = || {
+        other_node
+            .requests()
+            .iter()
+            .filter(|request| request.url.path() == "/api/core/v2/tips")
+            .count()
+    };
+    let removed_node_tips_requests = tips_requests();
+    client.get_tips().await.unwrap();
+    assert_eq!(tips_requests(), removed_node_tips_requests);
+}
+
+#[tokio::test]
+async fn mock_node_sync_with_clones() {
+    let node = MockNode::new();
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node.clone())
+        .with_node_sync_interval(Duration::from_millis(50))
+        .finish()
+        .unwrap();
+    let info_requests = || {
+        node.requests()
+            .iter()
+            .filter(|request| request.url.path() == "/api/core/v2/info")
+            .count()
+    };
+
+    // Clones share the sync process, dropping them doesn't interrupt the running sync for the others
+    let synced_info_requests = info_requests();
+    for _ in 0..10 {
+        drop(client.clone());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(info_requests() > synced_info_requests);
+    assert!(client.unhealthy_nodes().is_empty());
+}
+
+#[tokio::test]
+async fn mock_node_request_options() {
+    let output_id = OutputId::from_str(OUTPUT_ID).unwrap();
+    let token_supply = setup_client(&MockNode::new()).get_token_supply().await.unwrap();
+    let output = |amount| {
+        let output = Output::Basic(
+            BasicOutputBuilder::new_with_amount(amount)
+                .unwrap()
+                .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+                .finish(token_supply)
+                .unwrap(),
+        );
+        output_with_metadata(output_id, &output)
+    };
+    let (honest_output, other_output) = (output(1_000_000), output(2_000_000));
+    let node = |output: &OutputWithMetadataResponse| {
+        MockNode::new()
+            .with_output(output)
+            .unwrap()
+            .with_output_ids("api/indexer/v1/outputs/basic", &[output_id])
+            .unwrap()
+    };
+    let (honest_node, other_node) = (node(&honest_output), node(&other_output));
+    let client = Client::builder()
+        .with_nodes(&[NODE_URL, "http://127.0.0.1:14265"])
+        .unwrap()
+        .with_transport(MockNodes(HashMap::from([
+            ("localhost".to_string(), honest_node.clone()),
+            ("127.0.0.1".to_string(), other_node.clone()),
+        ])))
+        .with_quorum(true)
+        .with_min_quorum_size(2)
+        .with_quorum_threshold(100)
+        .with_route_timeouts(RouteTimeouts {
+            health: Some(Duration::from_secs(2)),
+            indexer: Some(Duration::from_secs(60)),
+            ..Default::default()
+        })
+        .finish()
+        .unwrap();
+    let last_timeout = |node: &MockNode| node.requests().last().unwrap().timeout;
+
+    assert!(matches!(
+        client.get_output(&output_id).await,
+        Err(Error::QuorumThresholdError { .. })
+    ));
+    client
+        .with_request_options(RequestOptions::new().with_skip_quorum(true))
+        .get_output(&output_id)
+        .await
+        .unwrap();
+    let forced_node_client =
+        client.with_request_options(RequestOptions::new().with_node(Url::parse("http://127.0.0.1:14265").unwrap()));
+    assert_eq!(
+        forced_node_client.get_output(&output_id).await.unwrap().output,
+        other_output.output
+    );
+
+    // The timeout of the route class, else the API timeout, is overridden by the request options
+    client
+        .basic_output_ids(vec![QueryParameter::HasNativeTokens(false)])
+        .await
+        .unwrap();
+    assert_eq!(last_timeout(&honest_node), Duration::from_secs(60));
+    client.get_health(NODE_URL).await.unwrap();
+    assert_eq!(last_timeout(&honest_node), Duration::from_secs(2));
+    client.get_tips().await.unwrap();
+    let tips_request = honest_node
+        .requests()
+        .into_iter()
+        .chain(other_node.requests())
+        .find(|request| request.url.path() == "/api/core/v2/tips")
+        .unwrap();
+    assert_eq!(tips_request.timeout, Duration::from_secs(15));
+    client
+        .with_request_options(RequestOptions::new().with_timeout(Duration::from_secs(1)))
+        .basic_output_ids(vec![QueryParameter::HasNativeTokens(false)])
+        .await
+        .unwrap();
+    assert_eq!(last_timeout(&honest_node), Duration::from_secs(1));
+    assert_eq!(last_timeout(&other_node), Duration::from_secs(1));
+}
+
+// Receives network events until one matches.
+async fn wait_for_network_event(
+    events: &mut tokio::sync::broadcast::Receiver<NetworkEvent>,
+    matches: impl Fn(&NetworkEvent) -> bool,
+) -> NetworkEvent {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            // Older events can be dropped if there are too many
+            if let Ok(event) = events.recv().await {
+                if matches(&event) {
+                    return event;
+                }
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn mock_node_network_events() {
+    let info = setup_client(&MockNode::new()).get_info().await.unwrap().node_info;
+    let mut other_network_info = info.clone();
+    other_network_info.protocol.network_name = "other".to_string();
+    let other_network_id = ProtocolParameters::try_from(other_network_info.protocol.clone())
+        .unwrap()
+        .network_id();
+    // The same mock node serves two hosts, so their network always has the most nodes
+    let node = MockNode::new();
+    let other_network_node = MockNode::new().with_info(&other_network_info).unwrap();
+    // Invalid protocol parameters of a node of another network don't fail the sync
+    let mut invalid_network_info = info.clone();
+    invalid_network_info.protocol.network_name = "x".repeat(300);
+    let invalid_network_node = MockNode::new().with_info(&invalid_network_info).unwrap();
+    let client = Client::builder()
+        .with_nodes(&[
+            NODE_URL,
+            "http://127.0.0.1:14265",
+            "http://127.0.0.2:14265",
+            "http://127.0.0.3:14265",
+        ])
+        .unwrap()
+        .with_transport(MockNodes(HashMap::from([
+            ("localhost".to_string(), node.clone()),
+            ("127.0.0.1".to_string(), other_network_node),
+            ("127.0.0.2".to_string(), node.clone()),
+            ("127.0.0.3".to_string(), invalid_network_node),
+        ])))
+        .with_node_sync_interval(Duration::from_millis(50))
+        .finish()
+        .unwrap();
+    let mut events = client.subscribe_network_events();
+
+    // Nodes of other networks aren't used
+    assert_eq!(client.node_stats().unwrap().len(), 2);
+    let event = wait_for_network_event(&mut events, |event| {
+        matches!(event, NetworkEvent::NodeNetworkMismatch { .. })
+    })
+    .await;
+    assert_eq!(
+        event,
+        NetworkEvent::NodeNetworkMismatch {
+            node: "http://127.0.0.1:14265".to_string(),
+            network_id: other_network_id,
+            expected_network_id: client.get_network_id().await.unwrap(),
+        }
+    );
+
+    let mut changed_info = info.clone();
+    changed_info.protocol.bech32_hrp = "tst".to_string();
+    changed_info.protocol.min_pow_score += 1;
+    node.clone().with_info(&changed_info).unwrap();
+    let event = wait_for_network_event(&mut events, |event| {
+        matches!(event, NetworkEvent::Bech32HrpChanged { .. })
+    })
+    .await;
+    assert_eq!(
+        event,
+        NetworkEvent::Bech32HrpChanged {
+            previous: "rms".to_string(),
+            current: "tst".to_string()
+        }
+    );
+    match wait_for_network_event(&mut events, |event| {
+        matches!(event, NetworkEvent::ProtocolParametersChanged { .. })
+    })
+    .await
+    {
+        NetworkEvent::ProtocolParametersChanged { previous, current } => {
+            assert_eq!(current.min_pow_score(), previous.min_pow_score() + 1);
+        }
+        event => panic!("unexpected event {event:?}"),
+    }
+    assert_eq!(client.get_bech32_hrp().await.unwrap(), "tst");
+
+    // The network only changes if no node reports it anymore
+    node.clone().with_info(&other_network_info).unwrap();
+    let event = wait_for_network_event(&mut events, |event| {
+        matches!(event, NetworkEvent::NetworkIdChanged { .. })
+    })
+    .await;
+    assert!(matches!(event, NetworkEvent::NetworkIdChanged { current, .. } if current == other_network_id));
+    assert_eq!(client.get_network_name().await.unwrap(), "other");
+}
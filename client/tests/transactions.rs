@@ -0,0 +1,480 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+// Transaction tests against the in-memory mock node: chain history, balances, tracking and output reservations.
+
+mod common;
+
+use std::time::Duration;
+
+use iota_client::{
+    api::{BaseCoinBalance, NativeTokenBalance, TransactionStatus, TransactionTrackerOptions},
+    api_types::{dto::LedgerInclusionStateDto, response::BlockMetadataResponse},
+    block::{
+        address::{Address, AliasAddress},
+        input::UtxoInput,
+        output::{
+            feature::{Feature, MetadataFeature},
+            unlock_condition::{
+                AddressUnlockCondition, ExpirationUnlockCondition, GovernorAddressUnlockCondition,
+                ImmutableAliasAddressUnlockCondition, StateControllerAddressUnlockCondition,
+                StorageDepositReturnUnlockCondition, TimelockUnlockCondition, UnlockCondition,
+            },
+            AliasId, AliasOutputBuilder, BasicOutputBuilder, ChainId, FoundryOutputBuilder, NativeToken, NftId,
+            NftOutputBuilder, OutputId, SimpleTokenScheme, TokenId, TokenScheme,
+        },
+        payload::{transaction::TransactionId, Payload},
+        rand::{
+            address::{rand_address, rand_ed25519_address},
+            block::rand_block_id,
+            bytes::rand_bytes_array,
+            milestone::rand_milestone_id,
+            output::rand_output_id,
+        },
+        semantic::ConflictReason,
+        Block,
+    },
+    node_manager::{http_client::HttpMethod, mock::MockNode},
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    Client, Error, ReservationOptions,
+};
+use primitive_types::U256;
+
+use self::common::{milestone, output_with_metadata, setup_client, transaction_block, NODE_URL};
+
+#[tokio::test]
+async fn mock_node_chain_history() {
+    let protocol_parameters = setup_client(&MockNode::new()).get_protocol_parameters().await.unwrap();
+    let token_supply = protocol_parameters.token_supply();
+    let basic_output = || {
+        BasicOutputBuilder::new_with_amount(1_000_000)
+            .unwrap()
+            .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+            .finish_output(token_supply)
+            .unwrap()
+    };
+    let nft_output = |nft_id: NftId, metadata: &[u8]| {
+        NftOutputBuilder::new_with_amount(1_000_000, nft_id)
+            .unwrap()
+            .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+            .add_feature(Feature::Metadata(MetadataFeature::new(metadata.to_vec()).unwrap()))
+            .finish_output(token_supply)
+            .unwrap()
+    };
+    let (funds_a, funds_b) = (rand_output_id(), rand_output_id());
+    let output_id = |block: &Block, index: u16| match block.payload() {
+        Some(Payload::Transaction(transaction)) => OutputId::new(transaction.id(), index).unwrap(),
+        _ => unreachable!(),
+    };
+
+    // Minted, transferred without changes, then its metadata is updated in a transaction with more inputs
+    let mint = transaction_block(&[funds_a], vec![nft_output(NftId::null(), b"v1")], &protocol_parameters);
+    let nft_id = NftId::from(&output_id(&mint, 0));
+    let transfer = transaction_block(&[output_id(&mint, 0)], vec![nft_output(nft_id, b"v1")], &protocol_parameters);
+    let update = transaction_block(
+        &[funds_b, output_id(&transfer, 0)],
+        vec![basic_output(), nft_output(nft_id, b"v2")],
+        &protocol_parameters,
+    );
+    let outputs = [
+        (funds_a, basic_output()),
+        (funds_b, basic_output()),
+        (output_id(&mint, 0), nft_output(NftId::null(), b"v1")),
+        (output_id(&transfer, 0), nft_output(nft_id, b"v1")),
+        (output_id(&update, 1), nft_output(nft_id, b"v2")),
+    ];
+
+    let mut node = MockNode::new()
+        .with_block(mint.clone())
+        .with_block(transfer.clone())
+        .with_block(update.clone())
+        .with_output_ids(&format!("api/indexer/v1/outputs/nft/{nft_id}"), &[output_id(&update, 1)])
+        .unwrap();
+    for (output_id, output) in &outputs {
+        node = node.with_output(&output_with_metadata(*output_id, output)).unwrap();
+    }
+
+    let history = setup_client(&node).get_chain_history(ChainId::Nft(nft_id)).await.unwrap();
+    assert_eq!(
+        history.iter().map(|transition| transition.output_id).collect::<Vec<_>>(),
+        vec![output_id(&mint, 0), output_id(&transfer, 0), output_id(&update, 1)]
+    );
+    assert_eq!(
+        history
+            .iter()
+            .map(|transition| transition.previous_output_id)
+            .collect::<Vec<_>>(),
+        vec![None, Some(output_id(&mint, 0)), Some(output_id(&transfer, 0))]
+    );
+    assert_eq!(
+        history
+            .iter()
+            .map(|transition| transition.metadata_changed)
+            .collect::<Vec<_>>(),
+        vec![true, false, true]
+    );
+    for transition in &history {
+        assert_eq!(&transition.transaction_id, transition.output_id.transaction_id());
+        assert_eq!(transition.milestone_timestamp, 1667450000);
+        assert_eq!(transition.state_index, None);
+    }
+
+    // A chain that doesn't exist
+    assert!(matches!(
+        setup_client(&node)
+            .get_chain_history(ChainId::Nft(NftId::from(&rand_output_id())))
+            .await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn mock_node_address_balance() {
+    let token_supply = setup_client(&MockNode::new()).get_token_supply().await.unwrap();
+    let bech32_hrp = setup_client(&MockNode::new()).get_bech32_hrp().await.unwrap();
+    let address = Address::Ed25519(rand_ed25519_address());
+    let other_address = Address::Ed25519(rand_ed25519_address());
+    // The timestamp of the latest milestone of the mock node
+    let timestamp = 1667460000;
+    let token_id = TokenId::new(rand_bytes_array());
+    let basic_output = |amount: u64, owner: Address, unlock_conditions: Vec<UnlockCondition>, tokens: u64| {
+        let mut builder = BasicOutputBuilder::new_with_amount(amount)
+            .unwrap()
+            .with_unlock_conditions(
+                std::iter::once(UnlockCondition::from(AddressUnlockCondition::new(owner))).chain(unlock_conditions),
+            );
+        if tokens > 0 {
+            builder = builder.add_native_token(NativeToken::new(token_id, U256::from(tokens)).unwrap());
+        }
+        builder.finish_output(token_supply).unwrap()
+    };
+    let expiration = |return_address: Address, timestamp: u32| {
+        UnlockCondition::from(ExpirationUnlockCondition::new(return_address, timestamp).unwrap())
+    };
+
+    let basic_outputs = [
+        basic_output(1_000_000, address, Vec::new(), 100),
+        basic_output(
+            2_000_000,
+            address,
+            vec![TimelockUnlockCondition::new(timestamp + 100).unwrap().into()],
+            50,
+        ),
+        basic_output(3_000_000, address, vec![expiration(other_address, timestamp + 100)], 0),
+        basic_output(
+            4_000_000,
+            address,
+            vec![StorageDepositReturnUnlockCondition::new(other_address, 500_000, token_supply)
+                .unwrap()
+                .into()],
+            0,
+        ),
+        // Expired, so it belongs to the return address
+        basic_output(5_000_000, other_address, vec![expiration(address, timestamp - 100)], 0),
+        // Not expired yet, so it still belongs to the other address
+        basic_output(6_000_000, other_address, vec![expiration(address, timestamp + 100)], 0),
+    ];
+    let nft_id = NftId::from(&rand_output_id());
+    let nft_output = NftOutputBuilder::new_with_amount(1_000_000, nft_id)
+        .unwrap()
+        .add_unlock_condition(AddressUnlockCondition::new(address).into())
+        .finish_output(token_supply)
+        .unwrap();
+    let alias_id = AliasId::from(&rand_output_id());
+    let alias_output = AliasOutputBuilder::new_with_amount(1_000_000, alias_id)
+        .unwrap()
+        .with_foundry_counter(1)
+        .add_unlock_condition(StateControllerAddressUnlockCondition::new(address).into())
+        .add_unlock_condition(GovernorAddressUnlockCondition::new(other_address).into())
+        .finish_output(token_supply)
+        .unwrap();
+    let token_scheme =
+        TokenScheme::Simple(SimpleTokenScheme::new(U256::from(10), U256::from(0), U256::from(10)).unwrap());
+    let foundry_output = FoundryOutputBuilder::new_with_amount(1_000_000, 1, token_scheme)
+        .unwrap()
+        .add_unlock_condition(ImmutableAliasAddressUnlockCondition::new(AliasAddress::new(alias_id)).into())
+        .finish_output(token_supply)
+        .unwrap();
+
+    // The indexer queries fall back to the routes without query parameters, the balance only keeps the outputs of the
+    // address
+    let basic_output_ids = basic_outputs.iter().map(|_| rand_output_id()).collect::<Vec<_>>();
+    let (nft_output_id, alias_output_id, foundry_output_id) = (rand_output_id(), rand_output_id(), rand_output_id());
+    let mut node = MockNode::new()
+        .with_output_ids("api/indexer/v1/outputs/basic", &basic_output_ids)
+        .unwrap()
+        .with_output_ids("api/indexer/v1/outputs/nft", &[nft_output_id])
+        .unwrap()
+        .with_output_ids("api/indexer/v1/outputs/alias", &[alias_output_id])
+        .unwrap()
+        .with_output_ids("api/indexer/v1/outputs/foundry", &[foundry_output_id])
+        .unwrap();
+    let outputs = basic_output_ids.iter().zip(basic_outputs.iter()).chain([
+        (&nft_output_id, &nft_output),
+        (&alias_output_id, &alias_output),
+        (&foundry_output_id, &foundry_output),
+    ]);
+    for (output_id, output) in outputs {
+        node = node.with_output(&output_with_metadata(*output_id, output)).unwrap();
+    }
+
+    let balance = setup_client(&node)
+        .get_address_balance(&address.to_bech32(&bech32_hrp))
+        .await
+        .unwrap();
+    assert_eq!(balance.milestone_timestamp, timestamp);
+    assert_eq!(
+        balance.base_coin,
+        BaseCoinBalance {
+            total: 18_000_000,
+            spendable: 12_500_000,
+            timelocked: 2_000_000,
+            expiring: 3_000_000,
+            storage_deposit_return: 500_000,
+        }
+    );
+    assert_eq!(
+        balance.native_tokens[&token_id],
+        NativeTokenBalance {
+            total: U256::from(150),
+            spendable: U256::from(100),
+            timelocked: U256::from(50),
+            expiring: U256::from(0),
+        }
+    );
+    assert_eq!(balance.nfts, vec![nft_id]);
+    assert_eq!(balance.aliases, vec![alias_id]);
+    assert_eq!(balance.foundries, vec![foundry_output.as_foundry().id()]);
+    let expired_query = format!(
+        "expirationReturnAddress={}&expiresBefore={timestamp}",
+        address.to_bech32(&bech32_hrp)
+    );
+    assert!(
+        node.requests()
+            .iter()
+            .any(|request| request.url.query() == Some(expired_query.as_str()))
+    );
+}
+
+#[tokio::test]
+async fn mock_node_transaction_tracker() {
+    let protocol_parameters = setup_client(&MockNode::new()).get_protocol_parameters().await.unwrap();
+    let output = || {
+        BasicOutputBuilder::new_with_amount(1_000_000)
+            .unwrap()
+            .add_unlock_condition(AddressUnlockCondition::new(rand_address()).into())
+            .finish_output(protocol_parameters.token_supply())
+            .unwrap()
+    };
+    let transaction_id = |block: &Block| match block.payload() {
+        Some(Payload::Transaction(transaction)) => transaction.id(),
+        _ => unreachable!(),
+    };
+    let options = TransactionTrackerOptions {
+        interval: Duration::from_millis(10),
+        timeout: Duration::from_millis(50),
+    };
+
+    // Included blocks are referenced by the confirmed milestone of the mock node
+    let included = transaction_block(&[rand_output_id()], vec![output()], &protocol_parameters);
+    let conflicting = transaction_block(&[rand_output_id()], vec![output()], &protocol_parameters);
+    let metadata = BlockMetadataResponse {
+        block_id: conflicting.id().to_string(),
+        parents: conflicting.parents().iter().map(ToString::to_string).collect(),
+        is_solid: true,
+        referenced_by_milestone_index: Some(1000),
+        milestone_index: None,
+        ledger_inclusion_state: Some(LedgerInclusionStateDto::Conflicting),
+        conflict_reason: Some(1),
+        white_flag_index: None,
+        should_promote: None,
+        should_reattach: None,
+    };
+    let node = MockNode::new()
+        .with_block(included.clone())
+        .with_milestone(&milestone(1000, 1667460000, rand_milestone_id()))
+        .unwrap()
+        .with_json(
+            HttpMethod::Get,
+            &format!("api/core/v2/blocks/{}/metadata", conflicting.id()),
+            &metadata,
+        )
+        .unwrap();
+    let client = setup_client(&node);
+
+    // The block of a reattachment is found by the transaction ID
+    assert_eq!(
+        client
+            .track_transaction(&transaction_id(&included), &[rand_block_id()], options)
+            .await
+            .unwrap(),
+        TransactionStatus::Confirmed {
+            block_id: included.id(),
+            milestone_index: 1000,
+            milestone_timestamp: 1667460000,
+        }
+    );
+    assert_eq!(
+        client
+            .track_transaction(&transaction_id(&conflicting), &[conflicting.id()], options)
+            .await
+            .unwrap(),
+        TransactionStatus::Conflicting {
+            block_id: conflicting.id(),
+            milestone_index: 1000,
+            reason: ConflictReason::InputUtxoAlreadySpent,
+        }
+    );
+
+    // Unknown transactions are checked until the timeout
+    let requests = node.requests().len();
+    assert_eq!(
+        client
+            .track_transaction(&TransactionId::from(rand_bytes_array()), &[], options)
+            .await
+            .unwrap(),
+        TransactionStatus::TimedOut
+    );
+    assert!(node.requests().len() > requests + 1);
+}
+
+#[tokio::test]
+async fn mock_node_output_reservations() {
+    let protocol_parameters = setup_client(&MockNode::new()).get_protocol_parameters().await.unwrap();
+    let secret_manager = SecretManager::Mnemonic(
+        MnemonicSecretManager::try_from_mnemonic(
+            "acoustic trophy damage hint search taste love bicycle foster cradle brown govern endless depend situate athlete pudding blame question genius transfer van random vast",
+        )
+        .unwrap(),
+    );
+    let address = setup_client(&MockNode::new())
+        .get_addresses(&secret_manager)
+        .with_range(0..1)
+        .get_raw()
+        .await
+        .unwrap()[0];
+    let output = |address: Address| {
+        BasicOutputBuilder::new_with_amount(1_000_000)
+            .unwrap()
+            .add_unlock_condition(AddressUnlockCondition::new(address).into())
+            .finish_output(protocol_parameters.token_supply())
+            .unwrap()
+    };
+
+    // The indexer queries of all addresses fall back to the route without query parameters, the outputs are only
+    // selected for the first address
+    let output_ids = [rand_output_id(), rand_output_id()];
+    let mut info = setup_client(&MockNode::new()).get_info().await.unwrap().node_info;
+    info.status.latest_milestone.timestamp = Some(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as u32,
+    );
+    let mut node = MockNode::new()
+        .with_info(&info)
+        .unwrap()
+        .with_output_ids("api/indexer/v1/outputs/basic", &output_ids)
+        .unwrap()
+        .with_milestone(&milestone(1000, 1667460000, rand_milestone_id()))
+        .unwrap();
+    for output_id in &output_ids {
+        node = node.with_output(&output_with_metadata(*output_id, &output(address))).unwrap();
+    }
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node.clone())
+        .with_output_reservations(ReservationOptions::default())
+        .finish()
+        .unwrap();
+
+    // Concurrent transaction builds select different outputs
+    let prepare_transaction = || async {
+        client
+            .block()
+            .with_secret_manager(&secret_manager)
+            .with_outputs(vec![output(rand_address())])
+            .unwrap()
+            .prepare_transaction()
+            .await
+            .unwrap()
+            .inputs_data[0]
+            .output_id()
+            .to_owned()
+    };
+    let (first_input, second_input) = tokio::join!(prepare_transaction(), prepare_transaction());
+    assert_ne!(first_input, second_input);
+    let mut reserved_outputs = client.reserved_outputs().unwrap();
+    reserved_outputs.sort();
+    let mut expected_outputs = output_ids.to_vec();
+    expected_outputs.sort();
+    assert_eq!(reserved_outputs, expected_outputs);
+
+    // Outputs are only reserved if none of them is reserved already
+    let other_output_id = rand_output_id();
+    assert!(!client.reserve_outputs(&[other_output_id, first_input]).unwrap());
+    assert!(!client.is_output_reserved(&other_output_id).unwrap());
+
+    // The inputs are released once the transaction is confirmed
+    let block = transaction_block(&[first_input], vec![output(rand_address())], &protocol_parameters);
+    let transaction_id = match block.payload() {
+        Some(Payload::Transaction(transaction)) => transaction.id(),
+        _ => unreachable!(),
+    };
+    node.clone().with_block(block.clone());
+    client
+        .track_transaction(&transaction_id, &[block.id()], TransactionTrackerOptions::default())
+        .await
+        .unwrap();
+    assert!(!client.is_output_reserved(&first_input).unwrap());
+    assert!(client.is_output_reserved(&second_input).unwrap());
+
+    // Custom inputs aren't used if they're reserved already
+    assert!(matches!(
+        client
+            .block()
+            .with_secret_manager(&secret_manager)
+            .with_input(UtxoInput::from(second_input))
+            .unwrap()
+            .with_outputs(vec![output(rand_address())])
+            .unwrap()
+            .prepare_transaction()
+            .await,
+        Err(Error::OutputReserved(output_id)) if output_id == second_input
+    ));
+
+    // The inputs are released if the transaction can't be prepared
+    assert!(matches!(
+        client
+            .block()
+            .with_secret_manager(&secret_manager)
+            .with_outputs(vec![output(rand_address())])
+            .unwrap()
+            .with_tag(vec![0; 65])
+            .prepare_transaction()
+            .await,
+        Err(Error::BlockError(_))
+    ));
+    assert_eq!(client.reserved_outputs().unwrap(), vec![second_input]);
+
+    // Retrying a transaction until it's included also releases its inputs
+    let block = transaction_block(&[second_input], vec![output(rand_address())], &protocol_parameters);
+    node.clone().with_block(block.clone());
+    client.retry_until_included(&block.id(), Some(0), Some(1)).await.unwrap();
+    assert!(client.reserved_outputs().unwrap().is_empty());
+
+    // Reservations are released after the TTL
+    let client = Client::builder()
+        .with_node(NODE_URL)
+        .unwrap()
+        .with_transport(node)
+        .with_output_reservations(ReservationOptions {
+            ttl: Duration::from_millis(200),
+        })
+        .finish()
+        .unwrap();
+    assert!(client.reserve_outputs(&[second_input]).unwrap());
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(client.reserved_outputs().unwrap().is_empty());
+}
@@ -336,6 +336,31 @@ pub struct AddPeerResponse(pub PeerDto);
 )]
 pub struct PeerResponse(pub PeerDto);
 
+/// Response of POST /api/core/v2/control/database/prune.
+/// Returns the index of the oldest milestone that is still in the database after the pruning.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct PruneDatabaseResponse {
+    pub index: u32,
+}
+
+/// Response of POST /api/core/v2/control/snapshots/create.
+/// Returns the milestone index and the file path of the created snapshot.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct CreateSnapshotResponse {
+    pub index: u32,
+    pub file_path: String,
+}
+
 /// Response of GET /api/plugins/debug/whiteflag.
 /// Returns the computed merkle tree hash for the given white flag traversal.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -0,0 +1,241 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use alloc::vec::Vec;
+
+use crate::block::{semantic::ConflictReason, BlockId};
+
+/// Describes if and how a block was included in the ledger.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "state", rename_all = "camelCase")
+)]
+pub enum BlockInclusionState {
+    /// The block isn't referenced by a milestone yet.
+    Pending,
+    /// The block was referenced by a milestone and its transaction was included in the ledger.
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    Included {
+        /// The index of the milestone that referenced the block.
+        referenced_by_milestone_index: u32,
+    },
+    /// The block was referenced by a milestone but its transaction conflicts with the ledger.
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    Conflicting {
+        /// The index of the milestone that referenced the block.
+        referenced_by_milestone_index: u32,
+        /// Why the transaction conflicts with the ledger.
+        reason: ConflictReason,
+    },
+    /// The block was referenced by a milestone and doesn't contain a transaction.
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    NoTransaction {
+        /// The index of the milestone that referenced the block.
+        referenced_by_milestone_index: u32,
+    },
+}
+
+impl BlockInclusionState {
+    /// Returns the index of the milestone that referenced the block, if it was referenced.
+    pub fn referenced_by_milestone_index(&self) -> Option<u32> {
+        match self {
+            Self::Pending => None,
+            Self::Included {
+                referenced_by_milestone_index,
+            }
+            | Self::Conflicting {
+                referenced_by_milestone_index,
+                ..
+            }
+            | Self::NoTransaction {
+                referenced_by_milestone_index,
+            } => Some(*referenced_by_milestone_index),
+        }
+    }
+
+    /// Returns if the block was referenced by a milestone.
+    pub fn is_referenced(&self) -> bool {
+        !matches!(self, Self::Pending)
+    }
+}
+
+/// The metadata of a block, as returned by the nodes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct BlockMetadata {
+    block_id: BlockId,
+    parents: Vec<BlockId>,
+    is_solid: bool,
+    inclusion_state: BlockInclusionState,
+    milestone_index: Option<u32>,
+    white_flag_index: Option<u32>,
+    should_promote: bool,
+    should_reattach: bool,
+}
+
+impl BlockMetadata {
+    /// Creates a new [`BlockMetadata`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        block_id: BlockId,
+        parents: Vec<BlockId>,
+        is_solid: bool,
+        inclusion_state: BlockInclusionState,
+        milestone_index: Option<u32>,
+        white_flag_index: Option<u32>,
+        should_promote: bool,
+        should_reattach: bool,
+    ) -> Self {
+        Self {
+            block_id,
+            parents,
+            is_solid,
+            inclusion_state,
+            milestone_index,
+            white_flag_index,
+            should_promote,
+            should_reattach,
+        }
+    }
+
+    /// Returns the ID of the block.
+    pub fn block_id(&self) -> &BlockId {
+        &self.block_id
+    }
+
+    /// Returns the parents of the block.
+    pub fn parents(&self) -> &[BlockId] {
+        &self.parents
+    }
+
+    /// Returns if the past cone of the block is known to the node.
+    pub fn is_solid(&self) -> bool {
+        self.is_solid
+    }
+
+    /// Returns the inclusion state of the block.
+    pub fn inclusion_state(&self) -> &BlockInclusionState {
+        &self.inclusion_state
+    }
+
+    /// Returns the index of the milestone that referenced the block, if it was referenced.
+    pub fn referenced_by_milestone_index(&self) -> Option<u32> {
+        self.inclusion_state.referenced_by_milestone_index()
+    }
+
+    /// Returns the index of the milestone, if the block contains one.
+    pub fn milestone_index(&self) -> Option<u32> {
+        self.milestone_index
+    }
+
+    /// Returns the index of the milestone at which the block was checked with the white flag, if it was referenced.
+    pub fn white_flag_index(&self) -> Option<u32> {
+        self.white_flag_index
+    }
+
+    /// Returns if the block should be promoted to get referenced.
+    pub fn should_promote(&self) -> bool {
+        self.should_promote
+    }
+
+    /// Returns if the block should be reattached to get referenced.
+    pub fn should_reattach(&self) -> bool {
+        self.should_reattach
+    }
+}
+
+#[cfg(feature = "api")]
+#[allow(missing_docs)]
+pub mod dto {
+    use alloc::string::ToString;
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::{
+        api::{dto::LedgerInclusionStateDto, response::BlockMetadataResponse},
+        block::error::dto::DtoError,
+    };
+
+    impl From<&BlockMetadata> for BlockMetadataResponse {
+        fn from(value: &BlockMetadata) -> Self {
+            let (ledger_inclusion_state, conflict_reason) = match value.inclusion_state {
+                BlockInclusionState::Pending => (None, None),
+                BlockInclusionState::Included { .. } => (Some(LedgerInclusionStateDto::Included), None),
+                BlockInclusionState::Conflicting { reason, .. } => {
+                    (Some(LedgerInclusionStateDto::Conflicting), Some(reason as u8))
+                }
+                BlockInclusionState::NoTransaction { .. } => (Some(LedgerInclusionStateDto::NoTransaction), None),
+            };
+
+            Self {
+                block_id: value.block_id.to_string(),
+                parents: value.parents.iter().map(BlockId::to_string).collect(),
+                is_solid: value.is_solid,
+                referenced_by_milestone_index: value.referenced_by_milestone_index(),
+                milestone_index: value.milestone_index,
+                ledger_inclusion_state,
+                conflict_reason,
+                white_flag_index: value.white_flag_index,
+                // Nodes only return them for blocks that aren't referenced yet
+                should_promote: (!value.inclusion_state.is_referenced()).then_some(value.should_promote),
+                should_reattach: (!value.inclusion_state.is_referenced()).then_some(value.should_reattach),
+            }
+        }
+    }
+
+    impl TryFrom<&BlockMetadataResponse> for BlockMetadata {
+        type Error = DtoError;
+
+        fn try_from(value: &BlockMetadataResponse) -> Result<Self, Self::Error> {
+            let block_id = BlockId::from_str(&value.block_id).map_err(|_| DtoError::InvalidField("blockId"))?;
+            let parents = value
+                .parents
+                .iter()
+                .map(|parent| BlockId::from_str(parent))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| DtoError::InvalidField("parents"))?;
+
+            let inclusion_state = match &value.ledger_inclusion_state {
+                None => BlockInclusionState::Pending,
+                Some(ledger_inclusion_state) => {
+                    let referenced_by_milestone_index = value
+                        .referenced_by_milestone_index
+                        .ok_or(DtoError::InvalidField("referencedByMilestoneIndex"))?;
+
+                    match ledger_inclusion_state {
+                        LedgerInclusionStateDto::Included => BlockInclusionState::Included {
+                            referenced_by_milestone_index,
+                        },
+                        LedgerInclusionStateDto::Conflicting => BlockInclusionState::Conflicting {
+                            referenced_by_milestone_index,
+                            reason: value
+                                .conflict_reason
+                                .and_then(|reason| ConflictReason::try_from(reason).ok())
+                                .ok_or(DtoError::InvalidField("conflictReason"))?,
+                        },
+                        LedgerInclusionStateDto::NoTransaction => BlockInclusionState::NoTransaction {
+                            referenced_by_milestone_index,
+                        },
+                    }
+                }
+            };
+
+            Ok(Self {
+                block_id,
+                parents,
+                is_solid: value.is_solid,
+                inclusion_state,
+                milestone_index: value.milestone_index,
+                white_flag_index: value.white_flag_index,
+                should_promote: value.should_promote.unwrap_or(false),
+                should_reattach: value.should_reattach.unwrap_or(false),
+            })
+        }
+    }
+}
@@ -21,6 +21,8 @@ pub mod block;
 pub mod helper;
 /// A module that provides types and syntactic validations of inputs.
 pub mod input;
+/// A module that provides the metadata of blocks.
+pub mod metadata;
 /// A module that provides types and syntactic validations of outputs.
 pub mod output;
 /// A module that provides types and syntactic validations of parents.
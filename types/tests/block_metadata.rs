@@ -0,0 +1,90 @@
+// Copyright 2022 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use iota_types::{
+    api::{dto::LedgerInclusionStateDto, response::BlockMetadataResponse},
+    block::{
+        metadata::{BlockInclusionState, BlockMetadata},
+        rand::block::{rand_block_id, rand_block_ids},
+        semantic::ConflictReason,
+        DtoError,
+    },
+};
+
+fn response(
+    ledger_inclusion_state: Option<LedgerInclusionStateDto>,
+    conflict_reason: Option<u8>,
+) -> BlockMetadataResponse {
+    let pending = ledger_inclusion_state.is_none();
+
+    BlockMetadataResponse {
+        block_id: rand_block_id().to_string(),
+        parents: rand_block_ids(2).iter().map(ToString::to_string).collect(),
+        is_solid: true,
+        referenced_by_milestone_index: ledger_inclusion_state.as_ref().map(|_| 42),
+        milestone_index: None,
+        ledger_inclusion_state,
+        conflict_reason,
+        white_flag_index: None,
+        should_promote: pending.then_some(true),
+        should_reattach: pending.then_some(false),
+    }
+}
+
+#[test]
+fn pending() {
+    let response = response(None, None);
+    let metadata = BlockMetadata::try_from(&response).unwrap();
+
+    assert_eq!(metadata.block_id().to_string(), response.block_id);
+    assert_eq!(metadata.parents().len(), 2);
+    assert_eq!(metadata.inclusion_state(), &BlockInclusionState::Pending);
+    assert_eq!(metadata.referenced_by_milestone_index(), None);
+    assert!(metadata.should_promote());
+    assert!(!metadata.should_reattach());
+}
+
+#[test]
+fn referenced() {
+    let included = BlockMetadata::try_from(&response(Some(LedgerInclusionStateDto::Included), None)).unwrap();
+    assert_eq!(
+        included.inclusion_state(),
+        &BlockInclusionState::Included {
+            referenced_by_milestone_index: 42
+        }
+    );
+
+    let conflicting = BlockMetadata::try_from(&response(Some(LedgerInclusionStateDto::Conflicting), Some(1))).unwrap();
+    assert_eq!(
+        conflicting.inclusion_state(),
+        &BlockInclusionState::Conflicting {
+            referenced_by_milestone_index: 42,
+            reason: ConflictReason::InputUtxoAlreadySpent
+        }
+    );
+    assert_eq!(conflicting.referenced_by_milestone_index(), Some(42));
+}
+
+#[test]
+fn invalid_conflict_reason() {
+    for conflict_reason in [None, Some(13)] {
+        assert!(matches!(
+            BlockMetadata::try_from(&response(Some(LedgerInclusionStateDto::Conflicting), conflict_reason)),
+            Err(DtoError::InvalidField("conflictReason"))
+        ));
+    }
+}
+
+#[test]
+fn response_round_trip() {
+    for (ledger_inclusion_state, conflict_reason) in [
+        (None, None),
+        (Some(LedgerInclusionStateDto::NoTransaction), None),
+        (Some(LedgerInclusionStateDto::Conflicting), Some(255)),
+    ] {
+        let response = response(ledger_inclusion_state, conflict_reason);
+        let metadata = BlockMetadata::try_from(&response).unwrap();
+
+        assert_eq!(BlockMetadataResponse::from(&metadata), response);
+    }
+}